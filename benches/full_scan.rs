@@ -92,8 +92,77 @@ fn full_scan_benchmark(c: &mut Criterion) {
         })
     });
 
+    group.bench_function("100_files_smart", |b| {
+        b.iter(|| {
+            let config = abyss::AbyssConfig {
+                path: temp_100.path().to_path_buf(),
+                output: PathBuf::from("/dev/null"),
+                compression: abyss::config::CompressionMode::Smart,
+                graph: true,
+                verbose: false,
+                ..Default::default()
+            };
+            let _ = abyss::run(config);
+        })
+    });
+
+    group.finish();
+}
+
+/// Per-file analysis cost: one parse per analysis (previous pipeline) vs one shared parse.
+fn analysis_benchmark(c: &mut Criterion) {
+    use abyss::utils::{ast, concepts, dependencies, summary};
+
+    let temp = create_test_repo(50);
+    let files: Vec<String> = std::fs::read_dir(temp.path())
+        .unwrap()
+        .flatten()
+        .filter_map(|e| std::fs::read_to_string(e.path()).ok())
+        .collect();
+
+    let mut group = c.benchmark_group("file_analysis");
+    group.sample_size(20);
+
+    // What every analysis used to do: a fresh parser and freshly compiled query per call
+    let rust = abyss::utils::language::for_extension("rs").unwrap();
+    group.bench_function("uncached_queries", |b| {
+        b.iter(|| {
+            for content in &files {
+                for query in [&rust.imports, &rust.concepts, &rust.symbols, &rust.bodies] {
+                    let mut parser = tree_sitter::Parser::new();
+                    parser.set_language(&rust.language().unwrap()).unwrap();
+                    let tree = parser.parse(content, None);
+                    let query = rust.query(query.as_deref().unwrap());
+                    std::hint::black_box((tree, query));
+                }
+            }
+        })
+    });
+
+    group.bench_function("separate_parses", |b| {
+        b.iter(|| {
+            for content in &files {
+                let imports = dependencies::extract_imports(content, "rs");
+                let concepts = concepts::extract_concepts(content, "rs");
+                let compressed = ast::compress_ast(content, "rs");
+                let summary = summary::summarize_content(content, "rs");
+                std::hint::black_box((imports, concepts, compressed, summary));
+            }
+        })
+    });
+
+    group.bench_function("single_parse", |b| {
+        b.iter(|| {
+            for content in &files {
+                let analysis = abyss::utils::source::SourceAnalysis::analyze(content, "rs");
+                let compressed = analysis.compress(content);
+                std::hint::black_box((analysis, compressed));
+            }
+        })
+    });
+
     group.finish();
 }
 
-criterion_group!(benches, full_scan_benchmark);
+criterion_group!(benches, full_scan_benchmark, analysis_benchmark);
 criterion_main!(benches);
@@ -1,26 +1,52 @@
-use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use abyss::utils::tokens::{Tokenizer, clear_count_cache, count_tokens, estimate_tokens};
+use criterion::{BatchSize, Criterion, Throughput, criterion_group, criterion_main};
 use std::hint::black_box;
 
 fn count_tokens_benchmark(c: &mut Criterion) {
     let small_content = "fn main() { println!(\"Hello, world!\"); }";
     let medium_content = small_content.repeat(100);
     let large_content = small_content.repeat(1000);
+    let encoder = Tokenizer::Cl100kBase.encoder().unwrap();
 
     let mut group = c.benchmark_group("tokenization");
 
     group.throughput(Throughput::Bytes(small_content.len() as u64));
     group.bench_function("small_40b", |b| {
-        b.iter(|| abyss::utils::tokens::count_tokens(black_box(small_content)))
+        b.iter(|| encoder.count(black_box(small_content)))
     });
 
     group.throughput(Throughput::Bytes(medium_content.len() as u64));
     group.bench_function("medium_4kb", |b| {
-        b.iter(|| abyss::utils::tokens::count_tokens(black_box(&medium_content)))
+        b.iter(|| encoder.count(black_box(&medium_content)))
     });
 
     group.throughput(Throughput::Bytes(large_content.len() as u64));
     group.bench_function("large_40kb", |b| {
-        b.iter(|| abyss::utils::tokens::count_tokens(black_box(&large_content)))
+        b.iter(|| encoder.count(black_box(&large_content)))
+    });
+
+    // What each file used to pay: the BPE table rebuilt on every call
+    group.throughput(Throughput::Bytes(medium_content.len() as u64));
+    group.bench_function("medium_4kb_rebuilt_bpe", |b| {
+        b.iter(|| {
+            let bpe = tiktoken_rs::cl100k_base().unwrap();
+            bpe.encode_with_special_tokens(black_box(&medium_content))
+                .len()
+        })
+    });
+    // The cached path: a first count (cache emptied before each one), then a repeated one
+    group.bench_function("medium_4kb_cached_cold", |b| {
+        b.iter_batched(
+            clear_count_cache,
+            |_| count_tokens(black_box(&medium_content)),
+            BatchSize::PerIteration,
+        )
+    });
+    group.bench_function("medium_4kb_cached_warm", |b| {
+        b.iter(|| count_tokens(black_box(&medium_content)))
+    });
+    group.bench_function("medium_4kb_estimate", |b| {
+        b.iter(|| estimate_tokens(black_box(&medium_content)))
     });
 
     group.finish();
@@ -32,27 +58,35 @@ fn parallel_tokenization_benchmark(c: &mut Criterion) {
     let files: Vec<String> = (0..100)
         .map(|i| format!("fn file{}() {{ println!(\"content {}\"); }}", i, i).repeat(50))
         .collect();
+    let encoder = Tokenizer::Cl100kBase.encoder().unwrap();
 
     let mut group = c.benchmark_group("parallel_tokenization");
 
     group.bench_function("sequential", |b| {
-        b.iter(|| {
-            files
-                .iter()
-                .filter_map(|f| abyss::utils::tokens::count_tokens(f).ok())
-                .sum::<usize>()
-        })
+        b.iter(|| files.iter().map(|f| encoder.count(f)).sum::<usize>())
     });
 
     group.bench_function("parallel_rayon", |b| {
+        b.iter(|| files.par_iter().map(|f| encoder.count(f)).sum::<usize>())
+    });
+
+    group.bench_function("parallel_rebuilt_bpe", |b| {
         b.iter(|| {
             files
                 .par_iter()
-                .filter_map(|f| abyss::utils::tokens::count_tokens(f).ok())
+                .filter_map(|f| {
+                    let bpe = tiktoken_rs::cl100k_base().ok()?;
+                    Some(bpe.encode_with_special_tokens(f).len())
+                })
                 .sum::<usize>()
         })
     });
 
+    // Selection only estimates; exact counts are left for the files that are output
+    group.bench_function("parallel_estimate", |b| {
+        b.iter(|| files.par_iter().map(|f| estimate_tokens(f)).sum::<usize>())
+    });
+
     group.finish();
 }
 
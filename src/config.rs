@@ -1,7 +1,10 @@
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 
+use crate::utils::tokens::Tokenizer;
+
 /// Output format for generated context
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum OutputFormat {
@@ -47,6 +50,7 @@ impl CompressionLevel {
     }
 
     /// Parse from string (for CLI)
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "none" | "0" => Some(CompressionLevel::None),
@@ -69,6 +73,224 @@ impl std::fmt::Display for CompressionLevel {
     }
 }
 
+/// Per-language overrides from a `[languages.<name>]` table in `abyss.toml`
+///
+/// Queries replace the built-in ones for that language (see `utils::language`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LanguageOverride {
+    /// Extra file extensions handled by this language
+    pub extensions: Vec<String>,
+    /// Query with `@import` captures
+    pub imports: Option<String>,
+    /// Query with `@name` captures for concept headers
+    pub concepts: Option<String>,
+    /// Query with `@class`, `@fn`, ... captures for summaries
+    pub symbols: Option<String>,
+    /// Query with `@body` captures elided by `--smart`
+    pub bodies: Option<String>,
+    /// Text that replaces an elided body
+    pub placeholder: Option<String>,
+}
+
+/// A `[[ranking.rules]]` entry: files matching `pattern` get `score` as their base rank
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RankRule {
+    /// Glob matched against each path component, or the whole relative path if it has a `/`
+    pub pattern: String,
+    /// Base score (the built-in profile ranges from 100 for tests to 1000 for READMEs)
+    pub score: i32,
+}
+
+/// Multipliers applied to each signal when combining them into a file's final score
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RankWeights {
+    pub pagerank: f64,
+    pub churn: f64,
+    pub entropy: f64,
+    pub heuristic: f64,
+    pub recency: f64,
+}
+
+impl Default for RankWeights {
+    fn default() -> Self {
+        Self {
+            pagerank: 1000.0,
+            churn: 1.0,
+            entropy: 10.0,
+            heuristic: 1.0,
+            recency: 100.0,
+        }
+    }
+}
+
+/// File ranking settings from the `[ranking]` table in `abyss.toml`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RankingConfig {
+    /// Custom rules, checked in order before the built-in profile (first match wins)
+    pub rules: Vec<RankRule>,
+    /// Fall back to the built-in rules (README, manifests, entry points, tests, ...)
+    pub default_profile: bool,
+    /// Score of files no rule matches
+    pub default_score: i32,
+    /// Points subtracted per directory level
+    pub depth_penalty: i32,
+    /// Days after which a file's recency score halves
+    pub recency_half_life: f64,
+    /// Signal weights for the final score
+    pub weights: RankWeights,
+    /// Globs for the files the task is about; PageRank then measures proximity to them
+    /// instead of global centrality
+    pub focus: Vec<String>,
+}
+
+impl Default for RankingConfig {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            default_profile: true,
+            default_score: 500,
+            depth_penalty: 10,
+            recency_half_life: 30.0,
+            weights: RankWeights::default(),
+            focus: Vec::new(),
+        }
+    }
+}
+
+/// What discovery does with a file whose header marks it as generated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GeneratedAction {
+    /// Leave it out and list it in the dropped ledger
+    Exclude,
+    /// Keep it, but only as its concept header and symbol summary
+    Summary,
+    /// Treat it like any other file
+    Keep,
+}
+
+impl GeneratedAction {
+    /// Parse from string (for CLI)
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "exclude" => Some(GeneratedAction::Exclude),
+            "summary" => Some(GeneratedAction::Summary),
+            "keep" => Some(GeneratedAction::Keep),
+            _ => None,
+        }
+    }
+}
+
+/// Action per generated-file banner, from the `[generated]` table in `abyss.toml`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeneratedConfig {
+    /// Go's `Code generated ... DO NOT EDIT.`
+    pub go: GeneratedAction,
+    /// protoc, prost and tonic banners
+    pub protobuf: GeneratedAction,
+    /// An `@generated` banner: the tag opening a header comment, or beside `DO NOT EDIT`
+    pub tagged: GeneratedAction,
+    /// Any other `DO NOT EDIT` notice; also used on hand-maintained files, so only summarized
+    pub do_not_edit: GeneratedAction,
+}
+
+impl Default for GeneratedConfig {
+    fn default() -> Self {
+        Self {
+            go: GeneratedAction::Exclude,
+            protobuf: GeneratedAction::Exclude,
+            tagged: GeneratedAction::Exclude,
+            do_not_edit: GeneratedAction::Summary,
+        }
+    }
+}
+
+impl GeneratedConfig {
+    /// Action for files carrying `marker`
+    pub fn action(&self, marker: crate::utils::generated::Marker) -> GeneratedAction {
+        use crate::utils::generated::Marker;
+        match marker {
+            Marker::Go => self.go,
+            Marker::Protobuf => self.protobuf,
+            Marker::Tagged => self.tagged,
+            Marker::DoNotEdit => self.do_not_edit,
+        }
+    }
+
+    /// Applies a `MARKER=ACTION` override (`go=summary`, `all=keep`).
+    pub fn set(&mut self, spec: &str) -> anyhow::Result<()> {
+        let (marker, action) = spec
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Expected MARKER=ACTION, got '{}'", spec))?;
+        let action = GeneratedAction::from_str(action.trim()).ok_or_else(|| {
+            anyhow::anyhow!("Unknown action '{}'. Use: exclude, summary, keep", action)
+        })?;
+        match marker.trim() {
+            "go" => self.go = action,
+            "protobuf" => self.protobuf = action,
+            "tagged" => self.tagged = action,
+            "do_not_edit" => self.do_not_edit = action,
+            "all" => {
+                *self = Self {
+                    go: action,
+                    protobuf: action,
+                    tagged: action,
+                    do_not_edit: action,
+                }
+            }
+            other => anyhow::bail!(
+                "Unknown marker '{}'. Use: go, protobuf, tagged, do_not_edit, all",
+                other
+            ),
+        }
+        Ok(())
+    }
+}
+
+/// A model's context limits and tokenizer, from `[models.<name>]` in `abyss.toml`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelPreset {
+    /// Total tokens the model accepts, prompt and answer together
+    pub context_window: usize,
+    /// Tokens kept free for the model's answer
+    #[serde(default)]
+    pub reserved_output: usize,
+    /// Encoding the model's tokens are counted with
+    #[serde(default)]
+    pub tokenizer: Tokenizer,
+}
+
+impl ModelPreset {
+    /// Built-in presets, also behind `--gpt`, `--claude` and `--gemini`
+    pub const BUILTIN: &'static [&'static str] = &["gpt", "claude", "gemini"];
+
+    /// Built-in preset by name
+    pub fn builtin(name: &str) -> Option<Self> {
+        let (context_window, reserved_output, tokenizer) = match name {
+            "gpt" => (128_000, 16_384, Tokenizer::O200kBase),
+            // No public BPE; ~3.5 characters per token on source code
+            "claude" => (200_000, 8_192, Tokenizer::CharsPerToken(3.5)),
+            "gemini" => (1_048_576, 8_192, Tokenizer::CharsPerToken(4.0)),
+            _ => return None,
+        };
+        Some(Self {
+            context_window,
+            reserved_output,
+            tokenizer,
+        })
+    }
+
+    /// Tokens left for the prompt once the answer is reserved
+    pub fn budget(&self) -> usize {
+        self.context_window.saturating_sub(self.reserved_output)
+    }
+}
+
 /// Main configuration for abyss
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -110,14 +332,52 @@ pub struct AbyssConfig {
     pub prompt: Option<String>,
     /// Maximum number of tokens to include in output
     pub max_tokens: Option<usize>,
+    /// Encoding tokens are counted with; the model's when unset
+    pub tokenizer: Option<Tokenizer>,
+    /// Model preset (`gpt`, `claude`, `gemini` or a `[models]` entry) the budget and
+    /// tokenizer are derived from
+    pub model: Option<String>,
+    /// User-defined model presets; override built-ins of the same name
+    pub models: BTreeMap<String, ModelPreset>,
+    /// Under `max_tokens`, selecting a file also pulls in its transitive dependencies,
+    /// at least as signature-only stubs
+    pub dependency_closure: bool,
+    /// Place each test file right after the file it exercises; under `max_tokens`,
+    /// tests are only kept alongside their subject
+    pub pair_tests: bool,
+    /// Emit only the best-ranked file of each cluster of near-duplicates; the others are
+    /// listed as "similar to X (94%)"
+    pub dedup: bool,
+    /// Estimated similarity (0.0 to 1.0) at which two files count as near-duplicates
+    pub dedup_threshold: f64,
+    /// List near-duplicates as a unified diff against the file they resemble
+    pub dedup_diff: bool,
     /// Diff mode: Scan only changed files relative to this git ref (e.g., "main", "HEAD~1")
     pub diff: Option<String>,
+    /// Question the output is for; under `max_tokens`, files near its matches rank higher
+    pub query: Option<String>,
+    /// Only files changed since this date (`2024-05-01`) or age (`2w`, `30 days`)
+    pub since: Option<String>,
+    /// Only files with a commit by this author (name or email substring)
+    pub author: Option<String>,
+    /// Only files this CODEOWNERS owner (`@org/team`, `@user`) owns
+    pub owner: Option<String>,
+    /// Group the directory structure by CODEOWNERS owner
+    pub group_by_owner: bool,
     /// Include Mermaid dependency graph in output
     pub graph: bool,
     /// Path to export portable bundle
     pub bundle: Option<PathBuf>,
     /// Generate semantic explanation of diffs
     pub explain_diff: bool,
+    /// Extra C/C++ include directories for resolving `#include` (relative to the repository)
+    pub include_dirs: Vec<PathBuf>,
+    /// Per-language query overrides, keyed by language name (`rust`, `typescript`, ...)
+    pub languages: BTreeMap<String, LanguageOverride>,
+    /// File ranking rules and signal weights
+    pub ranking: RankingConfig,
+    /// What to do with files whose header marks them as generated
+    pub generated: GeneratedConfig,
 }
 
 /// Workspace configuration for multi-repository merging
@@ -156,10 +416,57 @@ impl AbyssConfig {
             glob::Pattern::new(pattern)
                 .map_err(|e| anyhow::anyhow!("Invalid include pattern '{}': {}", pattern, e))?;
         }
+        if !(self.dedup_threshold > 0.0 && self.dedup_threshold <= 1.0) {
+            anyhow::bail!(
+                "Dedup threshold must be between 0 and 1, got {}",
+                self.dedup_threshold
+            );
+        }
+        for pattern in &self.ranking.focus {
+            glob::Pattern::new(pattern)
+                .map_err(|e| anyhow::anyhow!("Invalid focus pattern '{}': {}", pattern, e))?;
+        }
+
+        if let Some(model) = &self.model {
+            self.preset(model)?;
+        }
 
         Ok(())
     }
 
+    /// Installs the `[languages]` overrides and C/C++ include directories, which the
+    /// analysis reads from process-wide state. Call once after the configuration is loaded.
+    pub fn configure_analysis(&self) {
+        crate::utils::project::ProjectLayout::set_include_dirs(&self.include_dirs);
+        crate::utils::language::configure(&self.languages);
+    }
+
+    /// Preset `name`, from `[models]` first and the built-ins second
+    pub fn preset(&self, name: &str) -> anyhow::Result<ModelPreset> {
+        if let Some(preset) = self.models.get(name) {
+            return Ok(preset.clone());
+        }
+        ModelPreset::builtin(name).ok_or_else(|| {
+            let known: Vec<&str> = ModelPreset::BUILTIN
+                .iter()
+                .copied()
+                .chain(self.models.keys().map(String::as_str))
+                .collect();
+            anyhow::anyhow!("Unknown model '{}'. Use: {}", name, known.join(", "))
+        })
+    }
+
+    /// Fills in `max_tokens` and `tokenizer` from the model preset where they are unset.
+    pub fn apply_model(&mut self) -> anyhow::Result<()> {
+        let Some(model) = &self.model else {
+            return Ok(());
+        };
+        let preset = self.preset(model)?;
+        self.max_tokens.get_or_insert(preset.budget());
+        self.tokenizer.get_or_insert(preset.tokenizer);
+        Ok(())
+    }
+
     /// Attempts to load configuration from `abyss.toml` in the current directory.
     /// Returns None if file doesn't exist, Err on parse failure.
     pub fn load_from_file() -> Option<Self> {
@@ -273,11 +580,28 @@ impl Default for AbyssConfig {
             max_depth: None,
             prompt: None,
             max_tokens: None,
+            tokenizer: None,
+            model: None,
+            models: BTreeMap::new(),
+            dependency_closure: false,
+            pair_tests: false,
+            dedup: false,
+            dedup_threshold: 0.85,
+            dedup_diff: false,
             redact: false,
             diff: None,
+            query: None,
+            since: None,
+            author: None,
+            owner: None,
+            group_by_owner: false,
             graph: false,
             bundle: None,
             explain_diff: false,
+            include_dirs: Vec::new(),
+            languages: BTreeMap::new(),
+            ranking: RankingConfig::default(),
+            generated: GeneratedConfig::default(),
         }
     }
 }
@@ -307,11 +631,100 @@ mod tests {
             prompt: None,
             redact: false,
             diff: None,
+            query: None,
+            since: None,
+            author: None,
+            owner: None,
+            group_by_owner: false,
             graph: false,
             max_tokens: None,
+            tokenizer: None,
+            model: None,
+            models: BTreeMap::new(),
+            dependency_closure: false,
+            pair_tests: false,
+            dedup: false,
+            dedup_threshold: 0.85,
+            dedup_diff: false,
             bundle: None,
             explain_diff: false,
+            include_dirs: vec![],
+            languages: BTreeMap::new(),
+            ranking: RankingConfig::default(),
+            generated: GeneratedConfig::default(),
         };
         assert!(config.validate().is_err());
     }
+
+    #[test]
+    fn test_language_overrides_from_toml() {
+        let config: AbyssConfig = toml::from_str(
+            r#"
+            [languages.typescript]
+            extensions = ["mtsx"]
+            concepts = "(enum_declaration name: (_) @name)"
+            "#,
+        )
+        .unwrap();
+        let ts = &config.languages["typescript"];
+        assert_eq!(ts.extensions, vec!["mtsx"]);
+        assert!(ts.concepts.is_some());
+        assert!(ts.imports.is_none());
+    }
+
+    #[test]
+    fn test_ranking_from_toml() {
+        let config: AbyssConfig = toml::from_str(
+            r#"
+            [ranking]
+            depth_penalty = 0
+
+            [[ranking.rules]]
+            pattern = "migrations"
+            score = 50
+
+            [ranking.weights]
+            churn = 2.5
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.ranking.rules.len(), 1);
+        assert_eq!(config.ranking.rules[0].score, 50);
+        assert_eq!(config.ranking.depth_penalty, 0);
+        assert_eq!(config.ranking.default_score, 500);
+        assert!(config.ranking.default_profile);
+        assert_eq!(config.ranking.weights.churn, 2.5);
+        assert_eq!(config.ranking.weights.pagerank, 1000.0);
+    }
+
+    #[test]
+    fn test_model_presets() {
+        let mut config: AbyssConfig = toml::from_str(
+            r#"
+            model = "local"
+
+            [models.local]
+            context_window = 32768
+            reserved_output = 4096
+            tokenizer = "chars:3"
+            "#,
+        )
+        .unwrap();
+        config.apply_model().unwrap();
+        assert_eq!(config.max_tokens, Some(28_672));
+        assert_eq!(config.tokenizer, Some(Tokenizer::CharsPerToken(3.0)));
+
+        // Explicit settings win over the preset
+        let mut config = AbyssConfig {
+            model: Some("gpt".to_string()),
+            max_tokens: Some(50_000),
+            ..Default::default()
+        };
+        config.apply_model().unwrap();
+        assert_eq!(config.max_tokens, Some(50_000));
+        assert_eq!(config.tokenizer, Some(Tokenizer::O200kBase));
+
+        config.model = Some("gpt-9".to_string());
+        assert!(config.apply_model().is_err());
+    }
 }
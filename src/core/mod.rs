@@ -2,8 +2,11 @@
 //!
 //! This module contains the core types, scanner, and processing logic.
 
+pub mod render;
 pub mod scanner;
+pub mod selection;
 mod types;
 
-pub use scanner::discover_files;
+pub use scanner::{Discovery, discover, discover_files};
+pub use selection::{RankReport, Selection, select_files};
 pub use types::*;
@@ -0,0 +1,158 @@
+//! File rendering for the output
+//!
+//! Applies compression and the concept header to a file's text. Budget selection renders
+//! files the same way to measure what they will really cost.
+
+use crate::config::{AbyssConfig, CompressionLevel, CompressionMode};
+use crate::utils::compression::{compress_aggressive, compress_by_level, compress_content};
+use crate::utils::source::SourceAnalysis;
+
+/// How much of a file goes into the output, from most to least detailed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Fidelity {
+    /// The configured compression mode and level
+    Full,
+    /// Comments and boilerplate removed
+    Standard,
+    /// Function bodies elided, signatures kept
+    Aggressive,
+    /// Only the concept header and symbol summary
+    Summary,
+}
+
+impl Fidelity {
+    /// Fallbacks tried, in order, when a file does not fit the budget
+    pub const LADDER: [Fidelity; 4] = [
+        Fidelity::Full,
+        Fidelity::Standard,
+        Fidelity::Aggressive,
+        Fidelity::Summary,
+    ];
+}
+
+impl std::fmt::Display for Fidelity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Fidelity::Full => write!(f, "full"),
+            Fidelity::Standard => write!(f, "standard"),
+            Fidelity::Aggressive => write!(f, "aggressive"),
+            Fidelity::Summary => write!(f, "summary"),
+        }
+    }
+}
+
+/// Line comment delimiters for headers inserted into a file of this extension
+pub fn comment_style(extension: &str) -> (&'static str, &'static str) {
+    match extension {
+        "py" | "rb" | "sh" | "yaml" | "toml" | "dockerfile" | "makefile" => ("#", ""),
+        "html" | "xml" | "md" | "vue" | "svelte" | "astro" => ("<!--", " -->"),
+        _ => ("//", ""),
+    }
+}
+
+/// Compresses `content` for the given fidelity and prepends the concept header.
+///
+/// `source` must be the analysis of `content` (see `SourceAnalysis::matches`).
+pub fn render(
+    mut content: String,
+    source: &SourceAnalysis,
+    extension: &str,
+    config: &AbyssConfig,
+    fidelity: Fidelity,
+) -> String {
+    let (prefix, suffix) = comment_style(extension);
+
+    match fidelity {
+        Fidelity::Full => content = compress_configured(content, source, extension, config),
+        Fidelity::Standard => {
+            content = compress_by_level(&content, CompressionLevel::Standard, extension);
+        }
+        Fidelity::Aggressive => content = source.compress_aggressive(&content),
+        Fidelity::Summary => {
+            let lines = content.lines().count();
+            content = match &source.summary {
+                Some(summary) => format!("{} Summary: {}{}\n", prefix, summary, suffix),
+                None => String::new(),
+            };
+            content.push_str(&format!("{} ({} lines omitted){}\n", prefix, lines, suffix));
+        }
+    }
+
+    if !source.concepts.is_empty() {
+        content = format!(
+            "{} Concepts: {}{}\n{}",
+            prefix,
+            source.concepts.join(", "),
+            suffix,
+            content
+        );
+    }
+    content
+}
+
+/// Compression from `config.compression` and `config.compression_level`
+fn compress_configured(
+    mut content: String,
+    source: &SourceAnalysis,
+    extension: &str,
+    config: &AbyssConfig,
+) -> String {
+    let mut bodies_elided = false;
+
+    match config.compression {
+        CompressionMode::Simple => {
+            content = compress_content(&content);
+        }
+        CompressionMode::Smart => {
+            content = match config.compression_level {
+                CompressionLevel::Aggressive => source.compress_aggressive(&content),
+                _ => source.compress(&content),
+            };
+            bodies_elided = true;
+        }
+        CompressionMode::None => {}
+    }
+
+    match config.compression_level {
+        CompressionLevel::None => {}
+        // Aggressive is body elision, already done if `--smart` ran
+        CompressionLevel::Aggressive if bodies_elided => {}
+        CompressionLevel::Aggressive if !source.matches(&content) => {
+            content = compress_aggressive(&content, extension);
+        }
+        CompressionLevel::Aggressive => {
+            content = source.compress_aggressive(&content);
+        }
+        level => {
+            content = compress_by_level(&content, level, extension);
+        }
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fidelity_renderings() {
+        let content = "use std::fmt;\n\n// Adds numbers\npub fn add(a: i32, b: i32) -> i32 {\n    let sum = a + b;\n    sum\n}\n\npub struct Point { x: i32 }\n";
+        let source = SourceAnalysis::analyze(content, "rs");
+        let config = AbyssConfig::default();
+
+        let rendered: Vec<String> = Fidelity::LADDER
+            .iter()
+            .map(|&f| render(content.to_string(), &source, "rs", &config, f))
+            .collect();
+
+        assert!(rendered[0].ends_with(content));
+        assert!(rendered[0].starts_with("// Concepts: "));
+        assert!(!rendered[1].contains("// Adds numbers"));
+        assert!(rendered[2].contains("pub fn add(a: i32, b: i32) -> i32"));
+        assert!(!rendered[2].contains("let sum"));
+        assert!(rendered[3].contains("// Summary: "));
+        assert!(rendered[3].contains("(9 lines omitted)"));
+        assert!(!rendered[3].contains("pub fn add"));
+        assert!(rendered[2].len() < rendered[0].len());
+    }
+}
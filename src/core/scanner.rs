@@ -1,12 +1,16 @@
 //! File scanner for discovering and filtering files
 //!
-//! Handles file discovery, workspace support, and diff filtering.
+//! Handles file discovery, workspace support, and diff, history, CODEOWNERS and
+//! generated-file filtering.
 
 use crate::config::AbyssConfig;
-use crate::core::ScanEvent;
-use crate::utils::git_stats::get_diff_files;
+use crate::config::GeneratedAction;
+use crate::core::{DropReason, DroppedFile, ScanEvent};
+use crate::utils::codeowners::CodeOwners;
+use crate::utils::git_stats::{get_diff_files, now, parse_since, touched_files};
 use anyhow::{Context, Result};
 use crossbeam_channel::Sender;
+use rayon::prelude::*;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
@@ -17,14 +21,32 @@ fn notify(tx: &Option<Sender<ScanEvent>>, event: ScanEvent) {
     }
 }
 
+/// What discovery found, and what it left out
+#[derive(Debug, Default)]
+pub struct Discovery {
+    /// Files paired with their repository root
+    pub files: Vec<(PathBuf, PathBuf)>,
+    /// Files dropped after the walk (generated files)
+    pub dropped: Vec<DroppedFile>,
+    /// Files the walk skipped for ignore patterns, include patterns or size
+    pub filtered: Vec<DroppedFile>,
+}
+
 /// Discover files according to configuration
 ///
 /// Returns a tuple of (files_with_roots, dropped_files) where each file is paired
 /// with its repository root for multi-repo support.
+#[allow(clippy::type_complexity)]
 pub fn discover_files(
     config: &AbyssConfig,
     tx: Option<Sender<ScanEvent>>,
-) -> Result<(Vec<(PathBuf, PathBuf)>, Vec<PathBuf>)> {
+) -> Result<(Vec<(PathBuf, PathBuf)>, Vec<DroppedFile>)> {
+    let discovery = discover(config, tx)?;
+    Ok((discovery.files, discovery.dropped))
+}
+
+/// Discover files, also keeping what the walk filtered out (for `--explain-rank`).
+pub fn discover(config: &AbyssConfig, tx: Option<Sender<ScanEvent>>) -> Result<Discovery> {
     notify(&tx, ScanEvent::StartScanning);
 
     let root_path = config
@@ -32,21 +54,24 @@ pub fn discover_files(
         .canonicalize()
         .with_context(|| format!("Failed to find directory: {:?}", config.path))?;
 
-    // Use if-expression to avoid late initialization
-    let collected_files = if crate::utils::workspace::is_workspace_file(&root_path) {
-        scan_workspace(config, &root_path)?
+    let mut discovery = Discovery::default();
+    discovery.files = if crate::utils::workspace::is_workspace_file(&root_path) {
+        scan_workspace(config, &root_path, &mut discovery)?
     } else {
-        scan_single_directory(config, &root_path)?
+        scan_single_directory(config, &root_path, &mut discovery)?
     };
 
-    notify(&tx, ScanEvent::FilesFound(collected_files.len()));
+    notify(&tx, ScanEvent::FilesFound(discovery.files.len()));
 
-    let dropped_files: Vec<PathBuf> = Vec::new();
-    Ok((collected_files, dropped_files))
+    Ok(discovery)
 }
 
 /// Scan a workspace configuration file
-fn scan_workspace(config: &AbyssConfig, root_path: &Path) -> Result<Vec<(PathBuf, PathBuf)>> {
+fn scan_workspace(
+    config: &AbyssConfig,
+    root_path: &Path,
+    discovery: &mut Discovery,
+) -> Result<Vec<(PathBuf, PathBuf)>> {
     let ws_config = crate::utils::workspace::load_workspace_config(root_path)?;
     let mut collected_files = Vec::new();
 
@@ -56,8 +81,11 @@ fn scan_workspace(config: &AbyssConfig, root_path: &Path) -> Result<Vec<(PathBuf
             continue;
         }
 
-        let paths = scan_repository(config, &repo.path)?;
+        let paths = scan_repository(config, &repo.path, &mut discovery.filtered)?;
         let filtered = filter_by_diff(config, &repo.path, paths);
+        let filtered = filter_by_history(config, &repo.path, filtered);
+        let filtered = filter_by_owner(config, &repo.path, filtered);
+        let filtered = filter_generated(config, filtered, &mut discovery.dropped);
 
         for path in filtered {
             collected_files.push((path, repo.path.clone()));
@@ -71,9 +99,13 @@ fn scan_workspace(config: &AbyssConfig, root_path: &Path) -> Result<Vec<(PathBuf
 fn scan_single_directory(
     config: &AbyssConfig,
     root_path: &Path,
+    discovery: &mut Discovery,
 ) -> Result<Vec<(PathBuf, PathBuf)>> {
-    let paths = scan_repository(config, root_path)?;
+    let paths = scan_repository(config, root_path, &mut discovery.filtered)?;
     let filtered = filter_by_diff(config, root_path, paths);
+    let filtered = filter_by_history(config, root_path, filtered);
+    let filtered = filter_by_owner(config, root_path, filtered);
+    let filtered = filter_generated(config, filtered, &mut discovery.dropped);
     let root_owned = root_path.to_path_buf();
 
     Ok(filtered
@@ -82,21 +114,38 @@ fn scan_single_directory(
         .collect())
 }
 
-/// Scan a repository with ignore patterns
-fn scan_repository(config: &AbyssConfig, repo_path: &Path) -> Result<Vec<PathBuf>> {
-    // Load .abyssignore for this repo
-    let abyssignore_patterns = crate::utils::abyssignore::load_abyssignore(repo_path);
+/// Scan a repository with ignore patterns, recording what they filter out
+fn scan_repository(
+    config: &AbyssConfig,
+    repo_path: &Path,
+    filtered: &mut Vec<DroppedFile>,
+) -> Result<Vec<PathBuf>> {
+    let all_ignore_patterns = ignore_patterns(config, repo_path);
+    let (paths, skipped) = crate::fs::walk_directory_with_filtered(
+        repo_path,
+        walk_config(config, &all_ignore_patterns),
+    )?;
+    filtered.extend(skipped);
+    Ok(paths)
+}
+
+/// Configured ignore patterns plus the repository's `.abyssignore`
+fn ignore_patterns(config: &AbyssConfig, repo_path: &Path) -> Vec<String> {
     let mut all_ignore_patterns = config.ignore_patterns.clone();
-    all_ignore_patterns.extend(abyssignore_patterns);
+    all_ignore_patterns.extend(crate::utils::abyssignore::load_abyssignore(repo_path));
+    all_ignore_patterns
+}
 
-    let walk_config = crate::fs::WalkConfig {
-        ignore_patterns: &all_ignore_patterns,
+fn walk_config<'a>(
+    config: &'a AbyssConfig,
+    ignore_patterns: &'a [String],
+) -> crate::fs::WalkConfig<'a> {
+    crate::fs::WalkConfig {
+        ignore_patterns,
         include_patterns: &config.include_patterns,
         max_depth: config.max_depth,
         max_file_size: config.max_file_size,
-    };
-
-    crate::fs::walk_directory_with_config(repo_path, walk_config)
+    }
 }
 
 /// Filter paths by diff target if specified
@@ -116,6 +165,68 @@ fn filter_by_diff(config: &AbyssConfig, repo_path: &Path, mut paths: Vec<PathBuf
     paths
 }
 
+/// Keep files touched since `config.since` and/or by `config.author`
+fn filter_by_history(
+    config: &AbyssConfig,
+    repo_path: &Path,
+    mut paths: Vec<PathBuf>,
+) -> Vec<PathBuf> {
+    if config.since.is_none() && config.author.is_none() {
+        return paths;
+    }
+    // Validated with the config; an unparsable date filters nothing
+    let since = config
+        .since
+        .as_deref()
+        .and_then(|s| parse_since(s, now()).ok());
+    let touched = touched_files(repo_path, since, config.author.as_deref());
+    paths.retain(|p| touched.contains(p));
+    paths
+}
+
+/// Keep files owned by `config.owner` according to the repository's CODEOWNERS
+fn filter_by_owner(
+    config: &AbyssConfig,
+    repo_path: &Path,
+    mut paths: Vec<PathBuf>,
+) -> Vec<PathBuf> {
+    let Some(owner) = &config.owner else {
+        return paths;
+    };
+    let owners = CodeOwners::load(repo_path).unwrap_or_else(|| {
+        eprintln!("Warning: No CODEOWNERS file in {:?}", repo_path);
+        CodeOwners::default()
+    });
+    paths.retain(|p| owners.is_owned_by(p.strip_prefix(repo_path).unwrap_or(p), owner));
+    paths
+}
+
+/// Drop files whose header marks them as generated, where `[generated]` says to exclude them
+///
+/// Files forced to a summary are kept here; selection renders them (`utils::generated`).
+fn filter_generated(
+    config: &AbyssConfig,
+    mut paths: Vec<PathBuf>,
+    dropped: &mut Vec<DroppedFile>,
+) -> Vec<PathBuf> {
+    let markers: Vec<_> = paths
+        .par_iter()
+        .map(|path| crate::utils::generated::sniff(path))
+        .collect();
+    let mut markers = markers.into_iter();
+    paths.retain(|path| match markers.next().flatten() {
+        Some(marker) if config.generated.action(marker) == GeneratedAction::Exclude => {
+            dropped.push(DroppedFile::new(
+                path.clone(),
+                DropReason::Generated(marker.to_string()),
+            ));
+            false
+        }
+        _ => true,
+    });
+    paths
+}
+
 #[cfg(test)]
 mod tests {
     // Scanner tests would go here
@@ -0,0 +1,1450 @@
+//! Ranking and token-budget selection
+//!
+//! Scores every discovered file (path rules, churn, recency, entropy, PageRank), orders them
+//! along the dependency graph and picks what fits in `max_tokens`. With `ranking.focus`,
+//! a `diff` target or a `query`, PageRank is personalized so files near the focus, the
+//! changed files or the query matches outrank globally central ones.
+//!
+//! Under a budget, files are taken by value per token. A file that does not fit is retried
+//! at lower fidelity (standard, aggressive, summary-only) before it is dropped. Costs are
+//! measured on the rendered text, after compression and concept headers.
+//!
+//! With `pair_tests`, each test is placed right after the file it exercises and is only
+//! considered for the budget once that file is in.
+//!
+//! With `dedup`, near-duplicate files (MinHash over word shingles) are clustered and only
+//! the best-ranked member is kept in full; the others are listed as "similar to X (94%)",
+//! optionally with a unified diff against it.
+
+use crate::config::{AbyssConfig, GeneratedAction};
+use crate::core::render::{Fidelity, comment_style, render};
+use crate::core::{DropReason, DroppedFile};
+use crate::utils::dependencies::build_script_inputs;
+use crate::utils::generated::Marker;
+use crate::utils::git_stats::{get_diff_files, get_git_stats};
+use crate::utils::graph::DependencyGraph;
+use crate::utils::pairing::{pair_tests, place_tests};
+use crate::utils::query::QueryEngine;
+use crate::utils::rank::{FileScore, RankingProfile};
+use crate::utils::similarity::{MinHash, cluster, unified_diff};
+use crate::utils::source::SourceAnalysis;
+use crate::utils::tokens::{Tokenizer, count_tokens_with};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Ranked files and the budget decision for each of them.
+pub struct Selection {
+    /// Selected files with their repository roots, in output order
+    pub files: Vec<(PathBuf, PathBuf)>,
+    /// Files left out, with the reason
+    pub dropped: Vec<DroppedFile>,
+    /// Every candidate in output (topological) order
+    pub order: Vec<PathBuf>,
+    /// Score components per file
+    pub scores: HashMap<PathBuf, FileScore>,
+    /// Import graph of all candidates
+    pub graph: DependencyGraph,
+    /// First-pass analysis of every readable file
+    pub sources: HashMap<PathBuf, SourceAnalysis>,
+    /// Fidelity and token cost of each selected file
+    pub placements: HashMap<PathBuf, (Fidelity, usize)>,
+    /// Dependencies selected only because a selected file needs them -> that file
+    pub pulled_in: HashMap<PathBuf, PathBuf>,
+    /// Test files paired with the file they exercise (`pair_tests`) -> that file
+    pub subjects: HashMap<PathBuf, PathBuf>,
+    /// Near-duplicates (`dedup`), emitted as a listing against their representative
+    pub duplicates: HashMap<PathBuf, Duplicate>,
+    /// Generated files kept only as summaries (`generated`), with the banner found
+    pub generated: HashMap<PathBuf, Marker>,
+    roots: HashMap<PathBuf, PathBuf>,
+}
+
+/// A near-duplicate of a better-ranked file, listed against it instead of in full
+#[derive(Debug, Clone)]
+pub struct Duplicate {
+    /// The cluster's representative
+    pub of: PathBuf,
+    /// Estimated similarity to the representative, 0.0 to 1.0
+    pub similarity: f64,
+    /// What the output shows instead of the file's content
+    pub listing: String,
+}
+
+impl Duplicate {
+    pub fn percent(&self) -> u8 {
+        (self.similarity * 100.0).round() as u8
+    }
+}
+
+/// Scores, orders and budgets the discovered files.
+pub fn select_files(files: Vec<(PathBuf, PathBuf)>, config: &AbyssConfig) -> Selection {
+    // Manifests may have changed since the last scan (watch mode, TUI re-runs)
+    crate::utils::project::ProjectLayout::clear_cache();
+
+    // Intelligence: Build graph, calculate scores, and rank files before processing content
+    let mut git_stats_map = HashMap::new();
+    let roots: HashSet<_> = files.iter().map(|(_, root)| root.clone()).collect();
+    for root in roots {
+        let stats = get_git_stats(&root);
+        git_stats_map.insert(root, stats);
+    }
+
+    let mut graph = DependencyGraph::new();
+    let mut scores: HashMap<PathBuf, FileScore> = HashMap::new();
+
+    // Pre-calculate Heuristic, Churn & Recency
+    let profile = RankingProfile::new(&config.ranking);
+    let weights = &config.ranking.weights;
+    let now = crate::utils::git_stats::now();
+    for (path, root) in &files {
+        let mut score = FileScore {
+            heuristic: profile.score(path.strip_prefix(root).unwrap_or(path)),
+            ..Default::default()
+        };
+
+        if let Some(stats) = git_stats_map.get(root)
+            && let Some(s) = stats.get(path)
+        {
+            score.churn = std::cmp::min(s.churn_score * 5, 200) as i32;
+            score.recency = s.recency(now, config.ranking.recency_half_life);
+        }
+        scores.insert(path.clone(), score);
+    }
+
+    // Scan content for Entropy & Dependencies (Parallel).
+    // Each file is parsed once here; concepts, summaries and compression ranges are reused later.
+    struct FileAnalysis {
+        path: PathBuf,
+        root: PathBuf,
+        entropy: f64,
+        tokens: usize,
+        source: SourceAnalysis,
+        signature: Option<MinHash>,
+        generated: Option<Marker>,
+    }
+
+    let no_tokens = config.no_tokens;
+    // Candidates are ranked and fitted on estimates; only files going into the output
+    // are counted exactly
+    let tokenizer = config.tokenizer.unwrap_or_default();
+    let mut analyses: Vec<FileAnalysis> = files
+        .par_iter()
+        .filter_map(|(path, root)| {
+            let content = std::fs::read_to_string(path).ok()?;
+            let extension = path
+                .extension()
+                .and_then(|s| s.to_str())
+                .unwrap_or("")
+                .to_string();
+            let content = crate::utils::notebook::source_text(&content, &extension);
+
+            let entropy = crate::utils::rank::calculate_entropy(&content);
+            let mut source = SourceAnalysis::analyze(&content, &extension);
+            source.imports.extend(build_script_inputs(path, &content));
+            // Generated files `[generated]` keeps as summaries never go above that
+            let generated = crate::utils::generated::detect(&content)
+                .filter(|m| config.generated.action(*m) == GeneratedAction::Summary);
+            let tokens = if config.max_tokens.is_some() || generated.is_some() {
+                // Budgeting needs what the file will cost once rendered
+                let fidelity = if generated.is_some() {
+                    Fidelity::Summary
+                } else {
+                    Fidelity::Full
+                };
+                let rendered = render(content.to_string(), &source, &extension, config, fidelity);
+                tokenizer.estimate(&rendered)
+            } else if !no_tokens {
+                crate::utils::tokens::estimate_tokens(&content)
+            } else {
+                content.len() / 4
+            };
+
+            let signature = config.dedup.then(|| MinHash::of(&content)).flatten();
+
+            Some(FileAnalysis {
+                path: path.clone(),
+                root: root.clone(),
+                entropy,
+                tokens,
+                source,
+                signature,
+                generated,
+            })
+        })
+        .collect();
+
+    // Build graph atomically.
+    // Seed every file first: binary/media files have no text analysis but must still be ordered.
+    for (path, _) in &files {
+        graph.add_node(path.clone());
+    }
+    for analysis in &analyses {
+        graph.add_node(analysis.path.clone());
+
+        if let Some(s) = scores.get_mut(&analysis.path) {
+            s.entropy = analysis.entropy;
+            s.tokens = analysis.tokens;
+        }
+
+        for import in &analysis.source.imports {
+            for resolved in crate::utils::dependencies::resolve_import_targets(
+                import,
+                &analysis.path,
+                &analysis.root,
+            ) {
+                if crate::utils::dependencies::is_inline_include(import) {
+                    graph.attach(analysis.path.clone(), resolved);
+                } else {
+                    graph.add_edge(analysis.path.clone(), resolved);
+                }
+            }
+        }
+    }
+
+    let generated: HashMap<PathBuf, Marker> = analyses
+        .iter()
+        .filter_map(|a| Some((a.path.clone(), a.generated?)))
+        .collect();
+    let signatures: Vec<(PathBuf, MinHash)> = analyses
+        .iter_mut()
+        .filter_map(|a| Some((a.path.clone(), a.signature.take()?)))
+        .collect();
+    let sources: HashMap<PathBuf, SourceAnalysis> =
+        analyses.into_iter().map(|a| (a.path, a.source)).collect();
+
+    // PageRank, personalized to the focus files, changes and query hits if there are any
+    let seeds = rank_seeds(&files, &graph, config);
+    let page_ranks = if seeds.is_empty() {
+        graph.calculate_pagerank()
+    } else {
+        graph.personalized_pagerank(&seeds)
+    };
+    for (path, score) in &page_ranks {
+        if let Some(s) = scores.get_mut(path) {
+            s.pagerank = *score;
+        }
+    }
+
+    let roots: HashMap<PathBuf, PathBuf> = files.iter().cloned().collect();
+
+    // Near-duplicates are listed against the best-ranked member of their cluster
+    let duplicates = find_duplicates(signatures, &scores, &roots, config);
+    let mut copies_of: HashMap<&PathBuf, Vec<&PathBuf>> = HashMap::new();
+    for (copy, duplicate) in &duplicates {
+        copies_of.entry(&duplicate.of).or_default().push(copy);
+    }
+    for copies in copies_of.values_mut() {
+        copies.sort();
+    }
+
+    // Sort & Knapsack
+    let all_paths: Vec<PathBuf> = files.iter().map(|(p, _)| p.clone()).collect();
+    let order = crate::utils::rank::sort_files(&all_paths, &scores, &graph, weights);
+
+    // Tests follow their subject instead of sinking to the bottom
+    let subjects = if config.pair_tests {
+        pair_tests(&files, &graph)
+    } else {
+        HashMap::new()
+    };
+    let order = place_tests(order, &subjects);
+    let mut tests_of: HashMap<&PathBuf, Vec<&PathBuf>> = HashMap::new();
+    for (test, subject) in &subjects {
+        tests_of.entry(subject).or_default().push(test);
+    }
+    for tests in tests_of.values_mut() {
+        tests.sort();
+    }
+
+    // If max_tokens is set, pick the best value per token first, then output in topo order.
+    let mut dropped: Vec<DroppedFile> = Vec::new();
+    let mut placements = HashMap::new();
+    let mut pulled_in = HashMap::new();
+
+    if let Some(max_tokens) = config.max_tokens {
+        let mut remaining = max_tokens;
+        let mut costs = Costs {
+            scores: &scores,
+            sources: &sources,
+            generated: &generated,
+            config,
+            tokenizer,
+            exact: !no_tokens,
+            measured: HashMap::new(),
+            counted: HashMap::new(),
+        };
+        let density = |path: &PathBuf| {
+            let score = &scores[path];
+            score.final_score_with(weights).max(0.0) / score.tokens.max(1) as f64
+        };
+        // Paired tests and near-duplicates are only considered once their subject is in
+        let mut candidates: Vec<&PathBuf> = all_paths
+            .iter()
+            .filter(|path| !subjects.contains_key(*path) && !duplicates.contains_key(*path))
+            .collect();
+        candidates.sort_by(|a, b| {
+            density(b)
+                .partial_cmp(&density(a))
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.cmp(b))
+        });
+
+        for path in candidates {
+            if let Some(&(current, current_cost)) = placements.get(path) {
+                // Pulled in earlier as a dependency stub; upgrade it if the budget allows
+                let floor = costs.floor(path);
+                for fidelity in Fidelity::LADDER
+                    .into_iter()
+                    .take_while(|f| *f != current)
+                    .filter(|f| *f >= floor)
+                {
+                    let available = remaining + current_cost;
+                    if costs
+                        .at(path, fidelity)
+                        .is_none_or(|estimate| estimate > available)
+                    {
+                        continue;
+                    }
+                    let cost = costs.exact(path, fidelity);
+                    if cost <= available {
+                        remaining = available - cost;
+                        placements.insert(path.clone(), (fidelity, cost));
+                        pulled_in.remove(path);
+                        break;
+                    }
+                }
+                continue;
+            }
+
+            let stubs: Vec<(PathBuf, Fidelity, usize)> = if config.dependency_closure {
+                graph
+                    .transitive_dependencies(path)
+                    .into_iter()
+                    // Near-duplicates and paired tests come in with their representative
+                    // or subject instead
+                    .filter(|dep| {
+                        scores.contains_key(dep)
+                            && !placements.contains_key(dep)
+                            && !duplicates.contains_key(dep)
+                            && !subjects.contains_key(dep)
+                    })
+                    .map(|dep| {
+                        let (fidelity, cost) = costs.stub(&dep);
+                        (dep, fidelity, cost)
+                    })
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            let stub_cost: usize = stubs.iter().map(|(_, _, cost)| cost).sum();
+
+            // Retry at lower fidelity before dropping
+            match costs.fit(path, stub_cost, remaining) {
+                Ok((fidelity, cost)) => {
+                    remaining -= cost + stub_cost;
+                    placements.insert(path.clone(), (fidelity, cost));
+                    let mut placed = vec![path.clone()];
+                    for (dep, fidelity, cost) in stubs {
+                        dropped.retain(|d| d.path != dep);
+                        pulled_in.insert(dep.clone(), path.clone());
+                        placements.insert(dep.clone(), (fidelity, cost));
+                        placed.push(dep);
+                    }
+
+                    // Then the near-duplicates and tests of everything that just went in,
+                    // as far as they fit
+                    for subject in &placed {
+                        for &copy in copies_of.get(subject).into_iter().flatten() {
+                            if placements.contains_key(copy) {
+                                continue;
+                            }
+                            let cost = costs.count(&duplicates[copy].listing);
+                            if cost <= remaining {
+                                remaining -= cost;
+                                placements.insert(copy.clone(), (Fidelity::Summary, cost));
+                            } else {
+                                dropped.push(DroppedFile::new(
+                                    copy.clone(),
+                                    DropReason::OverBudget {
+                                        tokens: cost,
+                                        remaining,
+                                    },
+                                ));
+                            }
+                        }
+                        for &test in tests_of.get(subject).into_iter().flatten() {
+                            if placements.contains_key(test) || duplicates.contains_key(test) {
+                                continue;
+                            }
+                            match costs.fit(test, 0, remaining) {
+                                Ok((fidelity, cost)) => {
+                                    remaining -= cost;
+                                    placements.insert(test.clone(), (fidelity, cost));
+                                }
+                                Err(tokens) => dropped.push(DroppedFile::new(
+                                    test.clone(),
+                                    DropReason::OverBudget { tokens, remaining },
+                                )),
+                            }
+                        }
+                    }
+                }
+                Err(tokens) => dropped.push(DroppedFile::new(
+                    path.clone(),
+                    DropReason::OverBudget { tokens, remaining },
+                )),
+            }
+        }
+
+        let mut unlisted: Vec<(&PathBuf, &Duplicate)> = duplicates
+            .iter()
+            .filter(|(copy, _)| !placements.contains_key(*copy))
+            .filter(|(copy, _)| !dropped.iter().any(|d| &d.path == *copy))
+            .collect();
+        unlisted.sort_by(|a, b| a.0.cmp(b.0));
+        for (copy, duplicate) in unlisted {
+            let of = &duplicate.of;
+            dropped.push(DroppedFile::new(
+                copy.clone(),
+                DropReason::NearDuplicate {
+                    of: of.strip_prefix(&roots[of]).unwrap_or(of).to_path_buf(),
+                    similarity: duplicate.percent(),
+                },
+            ));
+        }
+
+        let mut unplaced: Vec<(&PathBuf, &PathBuf)> = subjects
+            .iter()
+            .filter(|(test, _)| !placements.contains_key(*test))
+            .filter(|(test, _)| !dropped.iter().any(|d| &d.path == *test))
+            .collect();
+        unplaced.sort();
+        for (test, subject) in unplaced {
+            let relative = subject.strip_prefix(&roots[subject]).unwrap_or(subject);
+            dropped.push(DroppedFile::new(
+                test.clone(),
+                DropReason::SubjectDropped(relative.to_path_buf()),
+            ));
+        }
+    } else {
+        placements = all_paths
+            .iter()
+            .map(|path| match duplicates.get(path) {
+                Some(duplicate) => {
+                    let tokens = crate::utils::tokens::estimate_tokens(&duplicate.listing);
+                    (path.clone(), (Fidelity::Summary, tokens))
+                }
+                None if generated.contains_key(path) => {
+                    (path.clone(), (Fidelity::Summary, scores[path].tokens))
+                }
+                None => (path.clone(), (Fidelity::Full, scores[path].tokens)),
+            })
+            .collect();
+    }
+
+    let files = order
+        .iter()
+        .filter(|path| placements.contains_key(*path))
+        .filter_map(|path| Some((path.clone(), roots.get(path)?.clone())))
+        .collect();
+
+    Selection {
+        files,
+        dropped,
+        order,
+        scores,
+        graph,
+        sources,
+        placements,
+        pulled_in,
+        subjects,
+        duplicates,
+        generated,
+        roots,
+    }
+}
+
+/// Clusters near-duplicates by final score (best first) and renders the listing of each copy
+fn find_duplicates(
+    mut signatures: Vec<(PathBuf, MinHash)>,
+    scores: &HashMap<PathBuf, FileScore>,
+    roots: &HashMap<PathBuf, PathBuf>,
+    config: &AbyssConfig,
+) -> HashMap<PathBuf, Duplicate> {
+    if !config.dedup {
+        return HashMap::new();
+    }
+    let weights = &config.ranking.weights;
+    let score = |path: &PathBuf| scores[path].final_score_with(weights);
+    signatures.sort_by(|(a, _), (b, _)| score(b).total_cmp(&score(a)).then_with(|| a.cmp(b)));
+    let relative = |path: &PathBuf| {
+        path.strip_prefix(&roots[path])
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    };
+
+    cluster(&signatures, config.dedup_threshold)
+        .into_iter()
+        .map(|(copy, (of, similarity))| {
+            let read = |path: &Path| std::fs::read_to_string(path).unwrap_or_default();
+            let (original, content) = (read(&of), read(&copy));
+            let mut duplicate = Duplicate {
+                of,
+                // The estimate can round up to a perfect match; only identical files are 100%
+                similarity: if original == content {
+                    1.0
+                } else {
+                    similarity.min(0.99)
+                },
+                listing: String::new(),
+            };
+            let extension = copy.extension().and_then(|s| s.to_str()).unwrap_or("");
+            let (prefix, suffix) = comment_style(extension);
+            let similar = format!(
+                "Similar to {} ({}%)",
+                relative(&duplicate.of),
+                duplicate.percent()
+            );
+
+            let diff = config
+                .dedup_diff
+                .then(|| {
+                    unified_diff(
+                        &original,
+                        &content,
+                        &relative(&duplicate.of),
+                        &relative(&copy),
+                    )
+                })
+                .flatten()
+                .filter(|diff| !diff.is_empty());
+            duplicate.listing = match diff {
+                Some(diff) => format!(
+                    "{} {}; diff against it:{}\n{}",
+                    prefix, similar, suffix, diff
+                ),
+                None => format!("{} {}; content omitted{}\n", prefix, similar, suffix),
+            };
+            (copy, duplicate)
+        })
+        .collect()
+}
+
+/// PageRank seeds: focus files, files changed against `config.diff` and query hits
+fn rank_seeds(
+    files: &[(PathBuf, PathBuf)],
+    graph: &DependencyGraph,
+    config: &AbyssConfig,
+) -> HashMap<PathBuf, f64> {
+    let mut seeds = focus_seeds(files, &config.ranking.focus);
+    if let Some(target) = &config.diff {
+        let candidates: HashSet<&PathBuf> = files.iter().map(|(path, _)| path).collect();
+        let roots: HashSet<&PathBuf> = files.iter().map(|(_, root)| root).collect();
+        for root in roots {
+            for changed in get_diff_files(root, target).unwrap_or_default() {
+                let path = root.join(changed);
+                if candidates.contains(&path) {
+                    seeds.insert(path, 1.0);
+                }
+            }
+        }
+    }
+    if let Some(query) = &config.query {
+        let paths: Vec<PathBuf> = files.iter().map(|(path, _)| path.clone()).collect();
+        let hits = QueryEngine::new(query, graph).seeds(&paths);
+        // Scaled so the best hit weighs as much as a focus file
+        let best = hits.values().copied().fold(0.0, f64::max);
+        for (path, score) in hits {
+            if best > 0.0 {
+                *seeds.entry(path).or_default() += score / best;
+            }
+        }
+    }
+    seeds
+}
+
+/// Files matching a `ranking.focus` glob (relative path or file name), as equal-weight seeds
+fn focus_seeds(files: &[(PathBuf, PathBuf)], patterns: &[String]) -> HashMap<PathBuf, f64> {
+    let patterns: Vec<glob::Pattern> = patterns
+        .iter()
+        .filter_map(|p| glob::Pattern::new(p).ok())
+        .collect();
+    if patterns.is_empty() {
+        return HashMap::new();
+    }
+    files
+        .iter()
+        .filter(|(path, root)| {
+            let relative = path.strip_prefix(root).unwrap_or(path);
+            patterns.iter().any(|p| {
+                p.matches_path(relative)
+                    || relative
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|name| p.matches(name))
+            })
+        })
+        .map(|(path, _)| (path.clone(), 1.0))
+        .collect()
+}
+
+/// Rendered token costs, measured on demand and remembered
+struct Costs<'a> {
+    scores: &'a HashMap<PathBuf, FileScore>,
+    sources: &'a HashMap<PathBuf, SourceAnalysis>,
+    generated: &'a HashMap<PathBuf, Marker>,
+    config: &'a AbyssConfig,
+    tokenizer: Tokenizer,
+    /// Count placed files exactly rather than keep the estimate
+    exact: bool,
+    /// Estimates
+    measured: HashMap<(PathBuf, Fidelity), Option<usize>>,
+    /// Exact counts of renderings that were placed or about to be
+    counted: HashMap<(PathBuf, Fidelity), usize>,
+}
+
+impl Costs<'_> {
+    /// Estimated cost of `path` at `fidelity`, or `None` if it has no text to compress.
+    fn at(&mut self, path: &PathBuf, fidelity: Fidelity) -> Option<usize> {
+        if fidelity == Fidelity::Full {
+            return Some(self.scores[path].tokens);
+        }
+        if let Some(cost) = self.measured.get(&(path.clone(), fidelity)) {
+            return *cost;
+        }
+        let cost = rendering(path, self.sources.get(path), self.config, fidelity)
+            .map(|text| self.tokenizer.estimate(&text));
+        self.measured.insert((path.clone(), fidelity), cost);
+        cost
+    }
+
+    /// Exact cost of `path` at `fidelity`; the estimate if it can't be rendered again.
+    fn exact(&mut self, path: &PathBuf, fidelity: Fidelity) -> usize {
+        let estimate = self.at(path, fidelity).unwrap_or(0);
+        if !self.exact {
+            return estimate;
+        }
+        if let Some(cost) = self.counted.get(&(path.clone(), fidelity)) {
+            return *cost;
+        }
+        let cost = rendering(path, self.sources.get(path), self.config, fidelity)
+            .map_or(estimate, |text| self.count(&text));
+        self.counted.insert((path.clone(), fidelity), cost);
+        cost
+    }
+
+    /// Exact token count of `text`, unless token counting is disabled
+    fn count(&self, text: &str) -> usize {
+        if !self.exact {
+            return self.tokenizer.estimate(text);
+        }
+        count_tokens_with(text, self.tokenizer).unwrap_or_else(|_| self.tokenizer.estimate(text))
+    }
+
+    /// Highest fidelity at which `path` fits in `remaining` next to `extra` tokens,
+    /// or the smallest total it could get down to.
+    fn fit(
+        &mut self,
+        path: &PathBuf,
+        extra: usize,
+        remaining: usize,
+    ) -> Result<(Fidelity, usize), usize> {
+        let mut smallest: Option<usize> = None;
+        let floor = self.floor(path);
+        for fidelity in Fidelity::LADDER.into_iter().filter(|f| *f >= floor) {
+            let Some(estimate) = self.at(path, fidelity) else {
+                break;
+            };
+            if smallest.is_some_and(|s| estimate >= s) {
+                continue;
+            }
+            smallest = Some(estimate);
+            if estimate + extra <= remaining {
+                let cost = self.exact(path, fidelity);
+                if cost + extra <= remaining {
+                    return Ok((fidelity, cost));
+                }
+                smallest = Some(cost);
+            }
+        }
+        Err(smallest.unwrap_or(0) + extra)
+    }
+
+    /// Most detailed fidelity `path` may be rendered at
+    fn floor(&self, path: &PathBuf) -> Fidelity {
+        if self.generated.contains_key(path) {
+            Fidelity::Summary
+        } else {
+            Fidelity::Full
+        }
+    }
+
+    /// Cheapest of the full rendering and the signature-only one, counted exactly
+    fn stub(&mut self, path: &PathBuf) -> (Fidelity, usize) {
+        let full = self.scores[path].tokens;
+        let fidelity = if self.floor(path) == Fidelity::Summary {
+            Fidelity::Summary
+        } else {
+            match self.at(path, Fidelity::Aggressive) {
+                Some(cost) if cost < full => Fidelity::Aggressive,
+                _ => Fidelity::Full,
+            }
+        };
+        (fidelity, self.exact(path, fidelity))
+    }
+}
+
+/// A file rendered at `fidelity`, or `None` if it has no text to compress.
+fn rendering(
+    path: &Path,
+    source: Option<&SourceAnalysis>,
+    config: &AbyssConfig,
+    fidelity: Fidelity,
+) -> Option<String> {
+    let source = source?;
+    let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("");
+    let content = std::fs::read_to_string(path).ok()?;
+    let content = crate::utils::notebook::source_text(&content, extension);
+    if !source.matches(&content) {
+        return None;
+    }
+    Some(render(
+        content.into_owned(),
+        source,
+        extension,
+        config,
+        fidelity,
+    ))
+}
+
+impl Selection {
+    /// Score breakdown and status of every candidate, followed by the files that
+    /// discovery filtered out (`Discovery::filtered`).
+    pub fn report(&self, config: &AbyssConfig, filtered: &[DroppedFile]) -> RankReport {
+        let weights = &config.ranking.weights;
+        let dropped: HashMap<&PathBuf, &DropReason> =
+            self.dropped.iter().map(|d| (&d.path, &d.reason)).collect();
+
+        let mut files: Vec<RankEntry> = self
+            .order
+            .iter()
+            .enumerate()
+            .map(|(i, path)| {
+                let score = self.scores.get(path).cloned().unwrap_or_default();
+                let (status, reason) = match (dropped.get(path), self.placements.get(path)) {
+                    (Some(reason), _) => (Status::Dropped, Some(reason.to_string())),
+                    (None, Some((_, cost))) if self.duplicates.contains_key(path) => {
+                        let duplicate = &self.duplicates[path];
+                        (
+                            Status::Kept,
+                            Some(format!(
+                                "similar to {} ({}%), listed in {} tokens",
+                                self.display_path(&duplicate.of, &config.path),
+                                duplicate.percent(),
+                                cost
+                            )),
+                        )
+                    }
+                    (None, Some((fidelity, cost))) if self.pulled_in.contains_key(path) => (
+                        Status::Kept,
+                        Some(format!(
+                            "dependency of {} ({}, {} tokens)",
+                            self.display_path(&self.pulled_in[path], &config.path),
+                            fidelity,
+                            cost
+                        )),
+                    ),
+                    (None, Some((fidelity, cost))) if self.subjects.contains_key(path) => (
+                        Status::Kept,
+                        Some(format!(
+                            "test of {} ({}, {} tokens)",
+                            self.display_path(&self.subjects[path], &config.path),
+                            fidelity,
+                            cost
+                        )),
+                    ),
+                    (None, Some((_, cost))) if self.generated.contains_key(path) => (
+                        Status::Kept,
+                        Some(format!(
+                            "generated ({} header), summary only ({} tokens)",
+                            self.generated[path], cost
+                        )),
+                    ),
+                    (None, Some((fidelity, cost))) if *fidelity != Fidelity::Full => (
+                        Status::Kept,
+                        Some(format!("compressed to {} ({} tokens)", fidelity, cost)),
+                    ),
+                    _ => (Status::Kept, None),
+                };
+                RankEntry {
+                    path: self.display_path(path, &config.path),
+                    position: Some(i + 1),
+                    score: score.final_score_with(weights),
+                    heuristic: score.heuristic,
+                    churn: score.churn,
+                    pagerank: score.pagerank,
+                    entropy: score.entropy,
+                    recency: score.recency,
+                    tokens: score.tokens,
+                    status,
+                    reason,
+                }
+            })
+            .collect();
+
+        files.extend(filtered.iter().map(|d| RankEntry {
+            path: self.display_path(&d.path, &config.path),
+            position: None,
+            score: 0.0,
+            heuristic: 0,
+            churn: 0,
+            pagerank: 0.0,
+            entropy: 0.0,
+            recency: 0.0,
+            tokens: 0,
+            status: Status::Filtered,
+            reason: Some(d.reason.to_string()),
+        }));
+
+        RankReport {
+            budget: config.max_tokens,
+            selected_tokens: self.placements.values().map(|(_, cost)| cost).sum(),
+            files,
+        }
+    }
+
+    fn display_path(&self, path: &Path, fallback_root: &Path) -> String {
+        let root = self
+            .roots
+            .get(path)
+            .map(PathBuf::as_path)
+            .unwrap_or(fallback_root);
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        path.strip_prefix(&root)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+}
+
+/// Whether a file made it into the output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Kept,
+    Dropped,
+    Filtered,
+}
+
+/// One row of the `--explain-rank` report
+#[derive(Debug, Clone, Serialize)]
+pub struct RankEntry {
+    pub path: String,
+    /// 1-based position in the output order; `None` for filtered files
+    pub position: Option<usize>,
+    pub score: f64,
+    pub heuristic: i32,
+    pub churn: i32,
+    pub pagerank: f64,
+    pub entropy: f64,
+    pub recency: f64,
+    pub tokens: usize,
+    pub status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Why each file was ranked where it was, and whether it was kept.
+#[derive(Debug, Clone, Serialize)]
+pub struct RankReport {
+    pub budget: Option<usize>,
+    /// Tokens of the kept files, at the fidelity they were kept at
+    pub selected_tokens: usize,
+    pub files: Vec<RankEntry>,
+}
+
+impl RankReport {
+    pub fn count(&self, status: Status) -> usize {
+        self.files.iter().filter(|f| f.status == status).count()
+    }
+
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl std::fmt::Display for RankReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Rank Report")?;
+        writeln!(f, "===========")?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{} kept, {} dropped, {} filtered",
+            self.count(Status::Kept),
+            self.count(Status::Dropped),
+            self.count(Status::Filtered)
+        )?;
+        match self.budget {
+            Some(budget) => writeln!(f, "Tokens: {} / {}", self.selected_tokens, budget)?,
+            None => writeln!(f, "Tokens: {} (no budget)", self.selected_tokens)?,
+        }
+        writeln!(f)?;
+
+        writeln!(
+            f,
+            "{:>5}  {:>8}  {:>5}  {:>5}  {:>8}  {:>7}  {:>7}  {:>7}  {:<8}  Path",
+            "#", "Score", "Heur", "Churn", "PageRank", "Entropy", "Recency", "Tokens", "Status"
+        )?;
+        for entry in &self.files {
+            let position = entry.position.map(|p| p.to_string()).unwrap_or_default();
+            let status = match entry.status {
+                Status::Kept => "kept",
+                Status::Dropped => "dropped",
+                Status::Filtered => "filtered",
+            };
+            if entry.status == Status::Filtered {
+                write!(f, "{:>5}  {:>59}  {:<8}  {}", "", "", status, entry.path)?;
+            } else {
+                write!(
+                    f,
+                    "{:>5}  {:>8.1}  {:>5}  {:>5}  {:>8.4}  {:>7.2}  {:>7.2}  {:>7}  {:<8}  {}",
+                    position,
+                    entry.score,
+                    entry.heuristic,
+                    entry.churn,
+                    entry.pagerank,
+                    entry.entropy,
+                    entry.recency,
+                    entry.tokens,
+                    status,
+                    entry.path
+                )?;
+            }
+            match &entry.reason {
+                Some(reason) => writeln!(f, " — {}", reason)?,
+                None => writeln!(f)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_budget_report() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::write(root.join("README.md"), "# Demo\n\nA small demo project.\n").unwrap();
+        fs::write(
+            root.join("main.rs"),
+            "mod util;\nfn main() { util::scale_0(1); }\n",
+        )
+        .unwrap();
+        let util: String = (0..30)
+            .map(|i| {
+                format!(
+                    "pub fn scale_{i}(x: i64) -> i64 {{\n    let y = x * {i} + 7;\n    if y > 100 {{ y - 100 }} else {{ y * 2 + {i} }}\n}}\n\n"
+                )
+            })
+            .collect();
+        fs::write(root.join("util.rs"), util).unwrap();
+        fs::write(root.join("debug.log"), "noise\n").unwrap();
+
+        let config = AbyssConfig {
+            path: root.clone(),
+            max_tokens: Some(900),
+            ignore_patterns: vec!["*.log".into()],
+            ..Default::default()
+        };
+        let discovery = crate::core::discover(&config, None).unwrap();
+        let files = discovery.files;
+        let selection = select_files(files.clone(), &config);
+
+        // util.rs does not fit in full and is kept with its bodies elided
+        assert_eq!(selection.files.len(), 3);
+        assert!(selection.dropped.is_empty());
+        let util_path = root.join("util.rs");
+        let (fidelity, cost) = selection.placements[&util_path];
+        assert_eq!(fidelity, Fidelity::Aggressive);
+        assert!(cost < selection.scores[&util_path].tokens);
+        let used: usize = selection.placements.values().map(|(_, c)| c).sum();
+        assert!(used <= 900);
+
+        let filtered = discovery.filtered;
+        let report = selection.report(&config, &filtered);
+        assert_eq!(report.count(Status::Kept), 3);
+        assert_eq!(report.count(Status::Filtered), 1);
+        assert_eq!(report.selected_tokens, used);
+
+        let util = report.files.iter().find(|f| f.path == "util.rs").unwrap();
+        assert!(
+            util.reason
+                .as_ref()
+                .unwrap()
+                .starts_with("compressed to aggressive")
+        );
+        let log = report.files.last().unwrap();
+        assert_eq!(log.path, "debug.log");
+        assert_eq!(log.reason.as_deref(), Some("ignored by '*.log'"));
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["budget"], 900);
+        assert_eq!(json["files"][0]["status"], "kept");
+
+        // Nothing left for util.rs once the small files are in, even as a summary
+        let tight = AbyssConfig {
+            max_tokens: Some(40),
+            ..config.clone()
+        };
+        let selection = select_files(files, &tight);
+        assert_eq!(selection.dropped.len(), 1);
+        assert_eq!(selection.dropped[0].path, util_path);
+        assert!(matches!(
+            selection.dropped[0].reason,
+            DropReason::OverBudget { .. }
+        ));
+        let report = selection.report(&tight, &[]);
+        assert!(report.to_string().contains("util.rs — over budget"));
+    }
+
+    #[test]
+    fn test_report_lists_only_candidates() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::write(root.join("main.py"), "import helper\n").unwrap();
+        fs::write(root.join("helper.py"), "def f():\n    pass\n").unwrap();
+
+        // helper.py is still an import target of main.py, but it is not a candidate
+        let config = AbyssConfig {
+            path: root.clone(),
+            ignore_patterns: vec!["helper.py".into()],
+            ..Default::default()
+        };
+        let discovery = crate::core::discover(&config, None).unwrap();
+        let files = discovery.files;
+        let selection = select_files(files, &config);
+        assert_eq!(selection.order, vec![root.join("main.py")]);
+
+        let filtered = discovery.filtered;
+        let report = selection.report(&config, &filtered);
+        assert_eq!(report.count(Status::Kept), 1);
+        assert_eq!(report.count(Status::Filtered), 1);
+        let helper: Vec<&RankEntry> = report
+            .files
+            .iter()
+            .filter(|f| f.path == "helper.py")
+            .collect();
+        assert_eq!(helper.len(), 1);
+        assert_eq!(helper[0].status, Status::Filtered);
+    }
+
+    #[test]
+    fn test_dependency_closure() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::write(
+            root.join("main.rs"),
+            "mod types;\nfn main() { let o = types::Order0::new(1); }\n",
+        )
+        .unwrap();
+        let types: String = (0..20)
+            .map(|i| {
+                format!(
+                    "pub struct Order{i} {{ pub id: u64 }}\n\nimpl Order{i} {{\n    pub fn new(id: u64) -> Self {{\n        let id = id * {i} + 1;\n        Self {{ id }}\n    }}\n}}\n\n"
+                )
+            })
+            .collect();
+        fs::write(root.join("types.rs"), types).unwrap();
+        let notes: String = (0..50)
+            .map(|i| format!("Note {i}: the quick brown fox jumps over the lazy dog.\n"))
+            .collect();
+        fs::write(root.join("NOTES.md"), notes).unwrap();
+
+        let config = AbyssConfig {
+            path: root.clone(),
+            max_tokens: Some(900),
+            ..Default::default()
+        };
+        let (files, _) = crate::core::discover_files(&config, None).unwrap();
+        let types_path = root.join("types.rs");
+        let main_path = root.join("main.rs");
+
+        // The notes are worth more per token, so main.rs loses the types it uses
+        let selection = select_files(files.clone(), &config);
+        assert!(selection.placements.contains_key(&main_path));
+        assert_eq!(selection.dropped.len(), 1);
+        assert_eq!(selection.dropped[0].path, types_path);
+
+        let closure = AbyssConfig {
+            dependency_closure: true,
+            ..config.clone()
+        };
+        let selection = select_files(files, &closure);
+        assert!(selection.dropped.is_empty());
+        assert_eq!(selection.placements[&main_path].0, Fidelity::Full);
+        assert_eq!(selection.placements[&types_path].0, Fidelity::Aggressive);
+        assert_eq!(selection.pulled_in[&types_path], main_path);
+        let used: usize = selection.placements.values().map(|(_, c)| c).sum();
+        assert!(used <= 900);
+
+        let report = selection.report(&closure, &[]);
+        let types = report.files.iter().find(|f| f.path == "types.rs").unwrap();
+        assert!(
+            types
+                .reason
+                .as_ref()
+                .unwrap()
+                .starts_with("dependency of main.rs (aggressive")
+        );
+    }
+
+    #[test]
+    fn test_focus_personalizes_pagerank() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        for handler in ["users", "orders", "search"] {
+            fs::write(root.join(format!("{handler}.py")), "import log\n").unwrap();
+        }
+        fs::write(root.join("billing.py"), "import log\nimport invoice\n").unwrap();
+        fs::write(root.join("invoice.py"), "TAX = 0.2\n").unwrap();
+        fs::write(root.join("log.py"), "LEVEL = 1\n").unwrap();
+
+        let config = AbyssConfig {
+            path: root.clone(),
+            ..Default::default()
+        };
+        let (files, _) = crate::core::discover_files(&config, None).unwrap();
+        let pagerank =
+            |selection: &Selection, name: &str| selection.scores[&root.join(name)].pagerank;
+
+        let selection = select_files(files.clone(), &config);
+        assert!(pagerank(&selection, "log.py") > pagerank(&selection, "billing.py"));
+
+        let mut focused = config.clone();
+        focused.ranking.focus = vec!["invoice.py".into()];
+        let selection = select_files(files, &focused);
+        assert!(pagerank(&selection, "billing.py") > pagerank(&selection, "log.py"));
+        assert!(pagerank(&selection, "log.py") > pagerank(&selection, "users.py"));
+    }
+
+    #[test]
+    fn test_diff_personalizes_pagerank() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        // Same size everywhere, so only the rank decides what fits
+        let padding = "# padding line for the budget\n".repeat(20);
+        let write = |name: &str, imports: &str| {
+            fs::write(root.join(name), format!("{imports}{padding}")).unwrap();
+        };
+        for handler in ["users", "orders", "search"] {
+            write(&format!("{handler}.py"), "import log\n");
+        }
+        write("billing.py", "import invoice\n");
+        write("invoice.py", "TAX = 0.2\n");
+        write("log.py", "LEVEL = 1\n");
+
+        let repo = git2::Repository::init(&root).unwrap();
+        let signature = git2::Signature::now("Dev", "dev@example.com").unwrap();
+        let commit = |message: &str| {
+            let mut index = repo.index().unwrap();
+            index
+                .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+                .unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            repo.commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parents,
+            )
+            .unwrap();
+        };
+        commit("initial");
+        write("billing.py", "import invoice\nRATE = 2\n");
+        commit("change billing");
+
+        let config = AbyssConfig {
+            path: root.clone(),
+            ..Default::default()
+        };
+        let (files, _) = crate::core::discover_files(&config, None).unwrap();
+        let pagerank =
+            |selection: &Selection, name: &str| selection.scores[&root.join(name)].pagerank;
+
+        let selection = select_files(files.clone(), &config);
+        assert!(pagerank(&selection, "log.py") > pagerank(&selection, "invoice.py"));
+
+        // Candidates are all files here; the changed file's dependency is what ranks
+        let changed = AbyssConfig {
+            diff: Some("HEAD~1".into()),
+            max_tokens: Some(300),
+            ..config.clone()
+        };
+        let selection = select_files(files, &changed);
+        assert!(pagerank(&selection, "invoice.py") > pagerank(&selection, "log.py"));
+        // Room for two files in full: the change and its dependency, not the hub
+        let fidelity = |name: &str| selection.placements.get(&root.join(name)).map(|p| p.0);
+        assert_eq!(fidelity("billing.py"), Some(Fidelity::Full));
+        assert_eq!(fidelity("invoice.py"), Some(Fidelity::Full));
+        assert_ne!(fidelity("log.py"), Some(Fidelity::Full));
+    }
+
+    #[test]
+    fn test_pair_tests() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::create_dir(root.join("tests")).unwrap();
+        fs::write(root.join("main.py"), "import cache\nimport report\n").unwrap();
+        fs::write(root.join("cache.py"), "SIZE = 10\n").unwrap();
+        let report: String = (0..40)
+            .map(|i| {
+                format!("def render_{i}(rows):\n    total = sum(rows) * {i}\n    return total\n\n")
+            })
+            .collect();
+        fs::write(root.join("report.py"), report).unwrap();
+        fs::write(
+            root.join("tests/test_cache.py"),
+            "def test_size():\n    pass\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("tests/test_report.py"),
+            "def test_render():\n    pass\n",
+        )
+        .unwrap();
+
+        let config = AbyssConfig {
+            path: root.clone(),
+            pair_tests: true,
+            ..Default::default()
+        };
+        let (files, _) = crate::core::discover_files(&config, None).unwrap();
+        let position = |selection: &Selection, name: &str| {
+            selection
+                .order
+                .iter()
+                .position(|p| p == &root.join(name))
+                .unwrap()
+        };
+
+        let selection = select_files(files.clone(), &config);
+        let cache = position(&selection, "cache.py");
+        assert_eq!(position(&selection, "tests/test_cache.py"), cache + 1);
+        let report = position(&selection, "report.py");
+        assert_eq!(position(&selection, "tests/test_report.py"), report + 1);
+
+        // Tests only go in with their subject
+        let budget = AbyssConfig {
+            max_tokens: Some(30),
+            ..config.clone()
+        };
+        let selection = select_files(files, &budget);
+        assert!(
+            selection
+                .placements
+                .contains_key(&root.join("tests/test_cache.py"))
+        );
+        let dropped: Vec<String> = selection
+            .dropped
+            .iter()
+            .map(|d| d.reason.to_string())
+            .collect();
+        assert!(dropped.contains(&"test of report.py, which was dropped".to_string()));
+        let report = selection.report(&budget, &[]);
+        let test = report
+            .files
+            .iter()
+            .find(|f| f.path == "tests/test_cache.py")
+            .unwrap();
+        assert!(
+            test.reason
+                .as_ref()
+                .unwrap()
+                .starts_with("test of cache.py")
+        );
+    }
+
+    #[test]
+    fn test_dedup_near_duplicates() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join("legacy")).unwrap();
+        let client = |extra: &str| -> String {
+            let methods: String = (0..20)
+                .map(|i| format!("    def get_{i}(self, key):\n        return self.session.get(self.base + '/items/{i}/' + key)\n\n"))
+                .collect();
+            format!(
+                "class Client:\n    def __init__(self, base):\n        self.base = base\n{extra}\n{methods}"
+            )
+        };
+        fs::write(root.join("client.py"), client("")).unwrap();
+        fs::write(
+            root.join("legacy/client.py"),
+            client("        self.retries = 3\n"),
+        )
+        .unwrap();
+        fs::write(
+            root.join("main.py"),
+            "import client\nprint(client.Client('x'))\n",
+        )
+        .unwrap();
+
+        let config = AbyssConfig {
+            path: root.clone(),
+            dedup: true,
+            dedup_diff: true,
+            ..Default::default()
+        };
+        let (files, _) = crate::core::discover_files(&config, None).unwrap();
+        let selection = select_files(files.clone(), &config);
+
+        // The legacy copy ranks lower and is listed against the original
+        assert_eq!(selection.duplicates.len(), 1);
+        let copy = &selection.duplicates[&root.join("legacy/client.py")];
+        assert_eq!(copy.of, root.join("client.py"));
+        assert!(copy.listing.starts_with("# Similar to client.py ("));
+        assert!(copy.listing.contains("+        self.retries = 3\n"));
+        let report = selection.report(&config, &[]);
+        let entry = report
+            .files
+            .iter()
+            .find(|f| f.path == "legacy/client.py")
+            .unwrap();
+        assert!(
+            entry
+                .reason
+                .as_ref()
+                .unwrap()
+                .starts_with("similar to client.py (")
+        );
+
+        // Under a budget the listing is what the copy costs
+        let budget = AbyssConfig {
+            max_tokens: Some(5000),
+            ..config.clone()
+        };
+        let selection = select_files(files, &budget);
+        let (fidelity, cost) = selection.placements[&root.join("legacy/client.py")];
+        assert_eq!(fidelity, Fidelity::Summary);
+        assert!(cost < selection.scores[&root.join("legacy/client.py")].tokens);
+    }
+
+    #[test]
+    fn test_dedup_with_dependency_closure() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join("legacy")).unwrap();
+        let client = |extra: &str| -> String {
+            let methods: String = (0..20)
+                .map(|i| format!("    def get_{i}(self, key):\n        return self.session.get(self.base + '/items/{i}/' + key)\n\n"))
+                .collect();
+            format!(
+                "class Client:\n    def __init__(self, base):\n        self.base = base\n{extra}\n{methods}"
+            )
+        };
+        fs::write(root.join("client.py"), client("")).unwrap();
+        fs::write(
+            root.join("legacy/client.py"),
+            client("        self.retries = 3\n"),
+        )
+        .unwrap();
+        fs::write(
+            root.join("main.py"),
+            "import client\nimport legacy.client\nprint(client.Client('x'))\n",
+        )
+        .unwrap();
+
+        let config = AbyssConfig {
+            path: root.clone(),
+            dedup: true,
+            dependency_closure: true,
+            max_tokens: Some(5000),
+            ..Default::default()
+        };
+        let (files, _) = crate::core::discover_files(&config, None).unwrap();
+        let selection = select_files(files, &config);
+
+        // main.py needs both copies, but the legacy one stays a listing, not a stub
+        let legacy = root.join("legacy/client.py");
+        assert!(selection.duplicates.contains_key(&legacy));
+        assert!(!selection.pulled_in.contains_key(&legacy));
+        let (fidelity, cost) = selection.placements[&legacy];
+        assert_eq!(fidelity, Fidelity::Summary);
+        assert_eq!(
+            cost,
+            count_tokens_with(&selection.duplicates[&legacy].listing, Tokenizer::default())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_generated_files() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::write(root.join("main.go"), "package main\n\nfunc main() {}\n").unwrap();
+        fs::write(
+            root.join("kind_string.go"),
+            "// Code generated by \"stringer -type=Kind\"; DO NOT EDIT.\n\npackage main\n",
+        )
+        .unwrap();
+        let schema: String = (0..40).map(|i| format!("field_{i}: string\n")).collect();
+        fs::write(
+            root.join("schema.yaml"),
+            format!("# Rendered from schema.json, DO NOT EDIT\n{schema}"),
+        )
+        .unwrap();
+
+        let config = AbyssConfig {
+            path: root.clone(),
+            ..Default::default()
+        };
+        let (files, dropped) = crate::core::discover_files(&config, None).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].path, root.join("kind_string.go"));
+        assert_eq!(
+            dropped[0].reason.to_string(),
+            "generated ('Code generated ... DO NOT EDIT.' header)"
+        );
+
+        // A bare DO NOT EDIT is kept, but only as a summary
+        let selection = select_files(files, &config);
+        let schema = root.join("schema.yaml");
+        assert_eq!(selection.placements[&schema].0, Fidelity::Summary);
+        let report = selection.report(&config, &dropped);
+        let entry = report
+            .files
+            .iter()
+            .find(|f| f.path == "schema.yaml")
+            .unwrap();
+        assert!(
+            entry
+                .reason
+                .as_ref()
+                .unwrap()
+                .starts_with("generated ('DO NOT EDIT' header)")
+        );
+
+        let mut keep = config.clone();
+        keep.generated.set("all=keep").unwrap();
+        let (files, dropped) = crate::core::discover_files(&keep, None).unwrap();
+        assert_eq!((files.len(), dropped.len()), (3, 0));
+        let selection = select_files(files, &keep);
+        assert_eq!(selection.placements[&schema].0, Fidelity::Full);
+        assert!(keep.generated.set("go=delete").is_err());
+    }
+}
@@ -19,17 +19,74 @@ pub enum ScanEvent {
     Error(String),
 }
 
+/// Why a file is not part of the output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DropReason {
+    /// Did not fit in what was left of `max_tokens`
+    OverBudget { tokens: usize, remaining: usize },
+    /// Excluded by an ignore pattern (config, CLI or `.abyssignore`)
+    Ignored(String),
+    /// No include pattern matched
+    NotIncluded,
+    /// Larger than `max_file_size`
+    TooLarge(u64),
+    /// A test whose subject (relative path) was not selected
+    SubjectDropped(PathBuf),
+    /// Header marks it as generated (the banner found)
+    Generated(String),
+    /// A near-duplicate (percent similar) of a file that was dropped
+    NearDuplicate { of: PathBuf, similarity: u8 },
+}
+
+impl std::fmt::Display for DropReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DropReason::OverBudget { tokens, remaining } => write!(
+                f,
+                "over budget ({} tokens, {} remaining)",
+                tokens, remaining
+            ),
+            DropReason::Ignored(pattern) => write!(f, "ignored by '{}'", pattern),
+            DropReason::NotIncluded => write!(f, "not matched by include patterns"),
+            DropReason::TooLarge(bytes) => write!(f, "larger than max size ({} bytes)", bytes),
+            DropReason::SubjectDropped(subject) => {
+                write!(f, "test of {}, which was dropped", subject.display())
+            }
+            DropReason::Generated(marker) => write!(f, "generated ({} header)", marker),
+            DropReason::NearDuplicate { of, similarity } => write!(
+                f,
+                "similar to {} ({}%), which was dropped",
+                of.display(),
+                similarity
+            ),
+        }
+    }
+}
+
+/// A file left out of the output, and why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DroppedFile {
+    pub path: PathBuf,
+    pub reason: DropReason,
+}
+
+impl DroppedFile {
+    pub fn new(path: PathBuf, reason: DropReason) -> Self {
+        Self { path, reason }
+    }
+}
+
 /// Result of file discovery
 #[derive(Debug, Clone)]
 pub struct DiscoveryResult {
     /// Files discovered with their repository roots
     pub files: Vec<(PathBuf, PathBuf)>,
     /// Files that were dropped (e.g., too large)
-    pub dropped: Vec<PathBuf>,
+    pub dropped: Vec<DroppedFile>,
 }
 
 impl DiscoveryResult {
-    pub fn new(files: Vec<(PathBuf, PathBuf)>, dropped: Vec<PathBuf>) -> Self {
+    pub fn new(files: Vec<(PathBuf, PathBuf)>, dropped: Vec<DroppedFile>) -> Self {
         Self { files, dropped }
     }
 
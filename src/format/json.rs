@@ -5,7 +5,8 @@ use anyhow::Result;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
-use super::{Formatter, HeaderContext};
+use super::{Formatter, HeaderContext, OwnerGroup};
+use crate::core::DroppedFile;
 
 pub struct JsonFormatter {
     first_file: bool,
@@ -20,9 +21,17 @@ impl Default for JsonFormatter {
 #[derive(serde::Serialize)]
 struct FileEntry<'a> {
     path: String,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    owners: &'a [String],
     content: &'a str,
 }
 
+#[derive(serde::Serialize)]
+struct DroppedEntry {
+    path: String,
+    reason: String,
+}
+
 impl JsonFormatter {
     pub fn new() -> Self {
         Self { first_file: true }
@@ -63,12 +72,43 @@ impl Formatter for JsonFormatter {
         Ok(())
     }
 
+    fn write_grouped_structure(
+        &mut self,
+        output: &mut dyn Write,
+        groups: &[OwnerGroup],
+        repo_root: &Path,
+    ) -> Result<()> {
+        // An object keyed by owners instead of the flat list
+        let structure: serde_json::Map<String, serde_json::Value> = groups
+            .iter()
+            .map(|group| {
+                let files = group
+                    .files
+                    .iter()
+                    .map(|p| {
+                        let relative = p.strip_prefix(repo_root).unwrap_or(p);
+                        serde_json::Value::String(relative.display().to_string())
+                    })
+                    .collect();
+                (group.owners.clone(), serde_json::Value::Array(files))
+            })
+            .collect();
+        writeln!(
+            output,
+            "  \"directory_structure\": {},",
+            serde_json::to_string(&structure)?
+        )?;
+        writeln!(output, "  \"files\": [")?;
+        Ok(())
+    }
+
     fn write_file(
         &mut self,
         output: &mut dyn Write,
         path: &Path,
         content: &str,
         _summary: Option<&str>,
+        owners: &[String],
         repo_root: &Path,
     ) -> Result<()> {
         if !self.first_file {
@@ -82,6 +122,7 @@ impl Formatter for JsonFormatter {
         // We output objects one by one to support streaming large datasets (O(1) memory)
         let entry = FileEntry {
             path: relative.display().to_string(),
+            owners,
             content,
         };
 
@@ -92,38 +133,22 @@ impl Formatter for JsonFormatter {
         Ok(())
     }
 
-    fn write_footer(&mut self, output: &mut dyn Write, dropped: &[PathBuf]) -> Result<()> {
+    fn write_footer(&mut self, output: &mut dyn Write, dropped: &[DroppedFile]) -> Result<()> {
         writeln!(output)?;
-        writeln!(output, "  ],")?;
+        write!(output, "  ]")?;
 
         if !dropped.is_empty() {
+            writeln!(output, ",")?;
             writeln!(output, "  \"dropped_files\": [")?;
-            for (i, path) in dropped.iter().enumerate() {
-                let comma = if i < dropped.len() - 1 { "," } else { "" };
-                writeln!(
-                    output,
-                    "    {}{}",
-                    serde_json::to_string(&path.display().to_string())?,
-                    comma
-                )?;
-            }
-            writeln!(output, "  ]")?;
-        }
-
-        // Append dropped_files if present
-        if !dropped.is_empty() {
-            write!(output, ",")?;
-            writeln!(output, "\n  \"dropped_files\": [")?;
-            for (i, path) in dropped.iter().enumerate() {
+            for (i, file) in dropped.iter().enumerate() {
                 let comma = if i < dropped.len() - 1 { "," } else { "" };
-                writeln!(
-                    output,
-                    "    {}{}",
-                    serde_json::to_string(&path.display().to_string())?,
-                    comma
-                )?;
+                let entry = DroppedEntry {
+                    path: file.path.display().to_string(),
+                    reason: file.reason.to_string(),
+                };
+                writeln!(output, "    {}{}", serde_json::to_string(&entry)?, comma)?;
             }
-            writeln!(output, "  ]")?;
+            write!(output, "  ]")?;
         }
 
         writeln!(output, "\n}}")?;
@@ -164,16 +189,81 @@ mod tests {
                 &PathBuf::from("/repo/src/main.rs"),
                 "fn main() {}",
                 None,
+                &[],
                 &root,
             )
             .unwrap();
 
-        writer.write_footer(&mut output, &[]).unwrap();
+        let dropped = [DroppedFile::new(
+            PathBuf::from("src/big.rs"),
+            crate::core::DropReason::OverBudget {
+                tokens: 900,
+                remaining: 100,
+            },
+        )];
+        writer.write_footer(&mut output, &dropped).unwrap();
 
         let result = String::from_utf8(output).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(parsed["dropped_files"][0]["path"], "src/big.rs");
+        assert_eq!(
+            parsed["dropped_files"][0]["reason"],
+            "over budget (900 tokens, 100 remaining)"
+        );
+        assert_eq!(parsed["directory_structure"][0], "src/main.rs");
+        assert!(parsed["files"][0].get("owners").is_none());
         assert!(result.contains("\"token_count\": 100"));
         assert!(result.contains("src/main.rs"));
         assert!(result.contains("\"path\":\"src/main.rs\""));
         assert!(result.contains("\"content\":\"fn main() {}\""));
     }
+
+    #[test]
+    fn test_json_grouped_structure() {
+        let mut writer = JsonFormatter::new();
+        let root = PathBuf::from("/repo");
+        let mut output = Vec::new();
+
+        writer
+            .write_header(
+                &mut output,
+                HeaderContext {
+                    token_count: None,
+                    prompt: &None,
+                    graph: None,
+                    overview: None,
+                },
+            )
+            .unwrap();
+        let groups = [
+            OwnerGroup {
+                owners: "@org/core".to_string(),
+                files: vec![PathBuf::from("/repo/src/main.rs")],
+            },
+            OwnerGroup {
+                owners: "@org/docs".to_string(),
+                files: vec![PathBuf::from("/repo/README.md")],
+            },
+        ];
+        writer
+            .write_grouped_structure(&mut output, &groups, &root)
+            .unwrap();
+        writer
+            .write_file(
+                &mut output,
+                &PathBuf::from("/repo/src/main.rs"),
+                "fn main() {}",
+                None,
+                &["@org/core".to_string()],
+                &root,
+            )
+            .unwrap();
+        writer.write_footer(&mut output, &[]).unwrap();
+
+        let result = String::from_utf8(output).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(parsed["directory_structure"]["@org/core"][0], "src/main.rs");
+        assert_eq!(parsed["directory_structure"]["@org/docs"][0], "README.md");
+        assert_eq!(parsed["files"][0]["owners"][0], "@org/core");
+    }
 }
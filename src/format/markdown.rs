@@ -4,7 +4,8 @@ use anyhow::Result;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
-use super::{Formatter, HeaderContext};
+use super::{Formatter, HeaderContext, OwnerGroup};
+use crate::core::DroppedFile;
 
 pub struct MarkdownFormatter;
 
@@ -40,6 +41,15 @@ impl Formatter for MarkdownFormatter {
                 }
                 writeln!(output)?;
             }
+            if !overview.cycles.is_empty() {
+                writeln!(output, "### Dependency Cycles")?;
+                for cycle in &overview.cycles {
+                    let members: Vec<String> =
+                        cycle.iter().map(|p| format!("`{}`", p.display())).collect();
+                    writeln!(output, "- {}", members.join(" ↔ "))?;
+                }
+                writeln!(output)?;
+            }
         }
 
         if let Some(g) = context.graph {
@@ -81,12 +91,35 @@ impl Formatter for MarkdownFormatter {
         Ok(())
     }
 
+    fn write_grouped_structure(
+        &mut self,
+        output: &mut dyn Write,
+        groups: &[OwnerGroup],
+        repo_root: &Path,
+    ) -> Result<()> {
+        writeln!(output, "## Directory Structure")?;
+        writeln!(output)?;
+        for group in groups {
+            writeln!(output, "### {}", group.owners)?;
+            writeln!(output)?;
+            writeln!(output, "```")?;
+            for path in &group.files {
+                let relative = path.strip_prefix(repo_root).unwrap_or(path);
+                writeln!(output, "{}", relative.display())?;
+            }
+            writeln!(output, "```")?;
+            writeln!(output)?;
+        }
+        Ok(())
+    }
+
     fn write_file(
         &mut self,
         output: &mut dyn Write,
         path: &Path,
         content: &str,
         summary: Option<&str>,
+        owners: &[String],
         repo_root: &Path,
     ) -> Result<()> {
         let relative = path.strip_prefix(repo_root).unwrap_or(path);
@@ -95,13 +128,17 @@ impl Formatter for MarkdownFormatter {
         // Map extensions to markdown language hints
         let lang = match extension {
             "rs" => "rust",
-            "py" => "python",
+            "py" | "ipynb" => "python",
             "js" => "javascript",
             "ts" => "typescript",
             "go" => "go",
             "c" | "h" => "c",
             "cpp" | "hpp" | "cc" => "cpp",
             "java" => "java",
+            "kt" | "kts" => "kotlin",
+            "vue" => "vue",
+            "svelte" => "svelte",
+            "astro" => "astro",
             "rb" => "ruby",
             "sh" => "bash",
             "yml" | "yaml" => "yaml",
@@ -118,6 +155,9 @@ impl Formatter for MarkdownFormatter {
         if let Some(s) = summary {
             writeln!(output, "> *summary: {}*", s)?;
         }
+        if !owners.is_empty() {
+            writeln!(output, "> *owners: {}*", owners.join(", "))?;
+        }
         writeln!(output)?;
         writeln!(output, "```{}", lang)?;
         writeln!(output, "{}", content)?;
@@ -126,16 +166,13 @@ impl Formatter for MarkdownFormatter {
         Ok(())
     }
 
-    fn write_footer(&mut self, output: &mut dyn Write, dropped: &[PathBuf]) -> Result<()> {
+    fn write_footer(&mut self, output: &mut dyn Write, dropped: &[DroppedFile]) -> Result<()> {
         if !dropped.is_empty() {
             writeln!(output, "## Dropped Files")?;
-            writeln!(
-                output,
-                "The following files were excluded to fit within the token limit:"
-            )?;
+            writeln!(output, "The following files were excluded:")?;
             writeln!(output)?;
-            for path in dropped {
-                writeln!(output, "- {}", path.display())?;
+            for file in dropped {
+                writeln!(output, "- {} — {}", file.path.display(), file.reason)?;
             }
             writeln!(output)?;
         }
@@ -157,12 +194,20 @@ mod tests {
         let mut formatter = MarkdownFormatter;
 
         formatter
-            .write_file(&mut output, &path, "fn main() {}", None, &root)
+            .write_file(
+                &mut output,
+                &path,
+                "fn main() {}",
+                None,
+                &["@org/core".to_string()],
+                &root,
+            )
             .unwrap();
 
         let result = String::from_utf8(output).unwrap();
         assert!(result.contains("## src/main.rs"));
         assert!(result.contains("```rust"));
+        assert!(result.contains("> *owners: @org/core*"));
         assert!(result.contains("fn main() {}"));
     }
 }
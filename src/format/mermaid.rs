@@ -98,12 +98,13 @@ pub fn generate_diagram(graph: &DependencyGraph, root: &Path) -> String {
         for file_path in &node.files {
             if let Some(id) = path_to_id.get(file_path) {
                 let name = file_path.file_name().unwrap_or_default().to_string_lossy();
+                let clean_name = name.replace('"', "'");
                 // Style by extension
                 let ext = file_path.extension().and_then(|s| s.to_str()).unwrap_or("");
                 let style_class = match ext {
                     "rs" => "rust",
-                    "py" => "python",
-                    "js" | "ts" | "jsx" | "tsx" => "js",
+                    "py" | "ipynb" => "python",
+                    "js" | "ts" | "jsx" | "tsx" | "vue" | "svelte" | "astro" => "js",
                     "html" | "css" | "scss" => "web",
                     _ => "other",
                 };
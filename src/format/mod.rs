@@ -13,6 +13,7 @@ use std::path::{Path, PathBuf};
 pub use self::xml::*;
 
 use crate::config::OutputFormat;
+use crate::core::DroppedFile;
 
 /// Escapes special XML characters for use in attribute values
 fn escape_xml_attr(s: &str) -> String {
@@ -28,8 +29,21 @@ pub struct RepoOverview {
     pub purpose: Option<String>,
     pub key_files: Vec<(PathBuf, String)>,
     pub changes: Option<Vec<String>>, // New field for recent commits
+    /// Import cycles among the included files, relative to the repository root
+    pub cycles: Vec<Vec<PathBuf>>,
 }
 
+/// Files sharing the same CODEOWNERS owners, for `--group-by-owner`
+#[derive(Debug, Clone)]
+pub struct OwnerGroup {
+    /// Space-separated owners, or `UNOWNED`
+    pub owners: String,
+    pub files: Vec<PathBuf>,
+}
+
+/// Group label for files without owners
+pub const UNOWNED: &str = "(unowned)";
+
 pub struct HeaderContext<'a> {
     pub token_count: Option<usize>,
     pub prompt: &'a Option<String>,
@@ -47,16 +61,26 @@ pub trait Formatter {
         repo_root: &Path,
     ) -> Result<()>;
 
+    /// Directory structure split by owner; replaces `write_directory_structure`.
+    fn write_grouped_structure(
+        &mut self,
+        output: &mut dyn Write,
+        groups: &[OwnerGroup],
+        repo_root: &Path,
+    ) -> Result<()>;
+
+    /// `owners` are the file's CODEOWNERS entries, empty if unknown.
     fn write_file(
         &mut self,
         output: &mut dyn Write,
         path: &Path,
         content: &str,
         summary: Option<&str>,
+        owners: &[String],
         repo_root: &Path,
     ) -> Result<()>;
 
-    fn write_footer(&mut self, output: &mut dyn Write, dropped: &[PathBuf]) -> Result<()>;
+    fn write_footer(&mut self, output: &mut dyn Write, dropped: &[DroppedFile]) -> Result<()>;
 }
 
 pub fn create_formatter(format: OutputFormat) -> Box<dyn Formatter> {
@@ -103,6 +127,17 @@ pub mod xml {
                     }
                     writeln!(output, "    </recent_changes>")?;
                 }
+                if !overview.cycles.is_empty() {
+                    writeln!(output, "    <dependency_cycles>")?;
+                    for cycle in &overview.cycles {
+                        writeln!(output, "        <cycle>")?;
+                        for path in cycle {
+                            writeln!(output, "            <file>{}</file>", path.display())?;
+                        }
+                        writeln!(output, "        </cycle>")?;
+                    }
+                    writeln!(output, "    </dependency_cycles>")?;
+                }
                 writeln!(output, "</executive_summary>")?;
             }
 
@@ -142,26 +177,48 @@ pub mod xml {
             Ok(())
         }
 
+        fn write_grouped_structure(
+            &mut self,
+            output: &mut dyn Write,
+            groups: &[OwnerGroup],
+            repo_root: &Path,
+        ) -> Result<()> {
+            writeln!(output, "<directory_structure>")?;
+            for group in groups {
+                writeln!(
+                    output,
+                    "<owner name=\"{}\">",
+                    escape_xml_attr(&group.owners)
+                )?;
+                for path in &group.files {
+                    let relative = path.strip_prefix(repo_root).unwrap_or(path);
+                    writeln!(output, "{}", relative.display())?;
+                }
+                writeln!(output, "</owner>")?;
+            }
+            writeln!(output, "</directory_structure>")?;
+            Ok(())
+        }
+
         fn write_file(
             &mut self,
             output: &mut dyn Write,
             path: &Path,
             content: &str,
             summary: Option<&str>,
+            owners: &[String],
             repo_root: &Path,
         ) -> Result<()> {
             let relative = path.strip_prefix(repo_root).unwrap_or(path);
 
+            write!(output, "<file path=\"{}\"", relative.display())?;
             if let Some(s) = summary {
-                writeln!(
-                    output,
-                    "<file path=\"{}\" summary=\"{}\">",
-                    relative.display(),
-                    escape_xml_attr(s)
-                )?;
-            } else {
-                writeln!(output, "<file path=\"{}\">", relative.display())?;
+                write!(output, " summary=\"{}\"", escape_xml_attr(s))?;
+            }
+            if !owners.is_empty() {
+                write!(output, " owners=\"{}\"", escape_xml_attr(&owners.join(" ")))?;
             }
+            writeln!(output, ">")?;
             writeln!(output, "    <![CDATA[")?;
             // Escape CDATA terminators if present
             let escaped = content.replace("]]>", "]]]]><![CDATA[>");
@@ -171,11 +228,16 @@ pub mod xml {
             Ok(())
         }
 
-        fn write_footer(&mut self, output: &mut dyn Write, dropped: &[PathBuf]) -> Result<()> {
+        fn write_footer(&mut self, output: &mut dyn Write, dropped: &[DroppedFile]) -> Result<()> {
             if !dropped.is_empty() {
                 writeln!(output, "<dropped_files>")?;
-                for path in dropped {
-                    writeln!(output, "    <file>{}</file>", path.display())?;
+                for file in dropped {
+                    writeln!(
+                        output,
+                        "    <file reason=\"{}\">{}</file>",
+                        escape_xml_attr(&file.reason.to_string()),
+                        file.path.display()
+                    )?;
                 }
                 writeln!(output, "</dropped_files>")?;
             }
@@ -201,7 +263,7 @@ pub mod xml {
             },
         )
     }
-    pub fn write_footer(output: &mut impl Write, dropped: &[PathBuf]) -> Result<()> {
+    pub fn write_footer(output: &mut impl Write, dropped: &[DroppedFile]) -> Result<()> {
         XmlFormatter.write_footer(output, dropped)
     }
     pub fn write_file(
@@ -210,7 +272,7 @@ pub mod xml {
         content: &str,
         root: &Path,
     ) -> Result<()> {
-        XmlFormatter.write_file(output, path, content, None, root)
+        XmlFormatter.write_file(output, path, content, None, &[], root)
     }
     pub fn write_directory_structure(o: &mut impl Write, f: &[PathBuf], r: &Path) -> Result<()> {
         XmlFormatter.write_directory_structure(o, f, r)
@@ -4,7 +4,8 @@ use anyhow::Result;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
-use super::{Formatter, HeaderContext};
+use super::{Formatter, HeaderContext, OwnerGroup};
+use crate::core::DroppedFile;
 
 pub struct PlainFormatter;
 
@@ -38,12 +39,31 @@ impl Formatter for PlainFormatter {
         Ok(())
     }
 
+    fn write_grouped_structure(
+        &mut self,
+        output: &mut dyn Write,
+        groups: &[OwnerGroup],
+        repo_root: &Path,
+    ) -> Result<()> {
+        writeln!(output, "=== DIRECTORY STRUCTURE ===")?;
+        for group in groups {
+            writeln!(output, "[{}]", group.owners)?;
+            for path in &group.files {
+                let relative = path.strip_prefix(repo_root).unwrap_or(path);
+                writeln!(output, "{}", relative.display())?;
+            }
+        }
+        writeln!(output)?;
+        Ok(())
+    }
+
     fn write_file(
         &mut self,
         output: &mut dyn Write,
         path: &Path,
         content: &str,
         summary: Option<&str>,
+        owners: &[String],
         repo_root: &Path,
     ) -> Result<()> {
         let relative = path.strip_prefix(repo_root).unwrap_or(path);
@@ -52,16 +72,19 @@ impl Formatter for PlainFormatter {
         if let Some(s) = summary {
             writeln!(output, "Summary: {}", s)?;
         }
+        if !owners.is_empty() {
+            writeln!(output, "Owners: {}", owners.join(" "))?;
+        }
         writeln!(output, "{}", content)?;
         writeln!(output)?;
         Ok(())
     }
 
-    fn write_footer(&mut self, output: &mut dyn Write, dropped: &[PathBuf]) -> Result<()> {
+    fn write_footer(&mut self, output: &mut dyn Write, dropped: &[DroppedFile]) -> Result<()> {
         if !dropped.is_empty() {
             writeln!(output, "=== DROPPED FILES ===")?;
-            for path in dropped {
-                writeln!(output, "- {}", path.display())?;
+            for file in dropped {
+                writeln!(output, "- {} ({})", file.path.display(), file.reason)?;
             }
             writeln!(output)?;
         }
@@ -82,7 +105,7 @@ mod tests {
         let mut formatter = PlainFormatter;
 
         formatter
-            .write_file(&mut output, &path, "fn main() {}", None, &root)
+            .write_file(&mut output, &path, "fn main() {}", None, &[], &root)
             .unwrap();
 
         let result = String::from_utf8(output).unwrap();
@@ -1,6 +1,8 @@
+use crate::core::{DropReason, DroppedFile};
 use anyhow::Result;
 use ignore::WalkBuilder;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 /// Configuration for directory walking
 #[derive(Default)]
@@ -22,6 +24,17 @@ pub fn walk_directory(path: &Path, ignore_patterns: &[String]) -> Result<Vec<Pat
 }
 
 pub fn walk_directory_with_config(path: &Path, config: WalkConfig) -> Result<Vec<PathBuf>> {
+    walk_directory_with_filtered(path, config).map(|(files, _)| files)
+}
+
+/// Walks `path` like `walk_directory_with_config`, and also returns the files it left out
+/// because of ignore patterns, include patterns or size, with the reason. Files hidden by
+/// `.gitignore` are not listed, and an ignored directory is reported once instead of file
+/// by file.
+pub fn walk_directory_with_filtered(
+    path: &Path,
+    config: WalkConfig,
+) -> Result<(Vec<PathBuf>, Vec<DroppedFile>)> {
     let mut builder = WalkBuilder::new(path);
 
     // Add custom ignore patterns
@@ -34,7 +47,40 @@ pub fn walk_directory_with_config(path: &Path, config: WalkConfig) -> Result<Vec
         override_builder.add(&format!("!{}", pattern))?;
     }
     let overrides = override_builder.build()?;
-    builder.overrides(overrides);
+    // One override per pattern, to name the one that matched
+    let single: Vec<(String, ignore::overrides::Override)> = config
+        .ignore_patterns
+        .iter()
+        .map(|pattern| {
+            let mut builder = ignore::overrides::OverrideBuilder::new(path);
+            builder.add(&format!("!{}", pattern))?;
+            Ok((pattern.clone(), builder.build()?))
+        })
+        .collect::<Result<_>>()?;
+
+    // Applied in `filter_entry` rather than `builder.overrides` so the matching pattern
+    // can be recorded
+    let filtered = Arc::new(Mutex::new(Vec::new()));
+    let recorder = Arc::clone(&filtered);
+    builder.filter_entry(move |entry| {
+        let is_dir = entry.file_type().is_some_and(|ft| ft.is_dir());
+        if !overrides.matched(entry.path(), is_dir).is_ignore() {
+            return true;
+        }
+        let pattern = single
+            .iter()
+            .find(|(_, o)| o.matched(entry.path(), is_dir).is_ignore())
+            .map(|(pattern, _)| pattern.clone())
+            .unwrap_or_default();
+        recorder
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(DroppedFile::new(
+                entry.path().to_path_buf(),
+                DropReason::Ignored(pattern),
+            ));
+        false
+    });
 
     // Standard gitignore is on by default.
     builder.standard_filters(true);
@@ -63,11 +109,14 @@ pub fn walk_directory_with_config(path: &Path, config: WalkConfig) -> Result<Vec
                     let file_path = entry.path();
 
                     // Max file size check
-                    if config
-                        .max_file_size
-                        .is_some_and(|s| file_path.metadata().is_ok_and(|m| m.len() as usize > s))
-                    {
-                        continue;
+                    if let Some(max) = config.max_file_size {
+                        let size = file_path.metadata().map(|m| m.len()).unwrap_or(0);
+                        if size as usize > max {
+                            filtered.lock().unwrap_or_else(|e| e.into_inner()).push(
+                                DroppedFile::new(entry.into_path(), DropReason::TooLarge(size)),
+                            );
+                            continue;
+                        }
                     }
 
                     // Include pattern check
@@ -80,7 +129,12 @@ pub fn walk_directory_with_config(path: &Path, config: WalkConfig) -> Result<Vec
                                     .is_some_and(|name| m.matches(name))
                         });
                         if !matches {
-                            continue; // Skip files not matching include patterns
+                            // Skip files not matching include patterns
+                            filtered
+                                .lock()
+                                .unwrap_or_else(|e| e.into_inner())
+                                .push(DroppedFile::new(entry.into_path(), DropReason::NotIncluded));
+                            continue;
                         }
                     }
 
@@ -92,7 +146,9 @@ pub fn walk_directory_with_config(path: &Path, config: WalkConfig) -> Result<Vec
     }
 
     files.sort();
-    Ok(files)
+    let mut filtered = std::mem::take(&mut *filtered.lock().unwrap_or_else(|e| e.into_inner()));
+    filtered.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok((files, filtered))
 }
 
 #[cfg(test)]
@@ -149,4 +205,43 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_filtered_files_reasons() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+
+        std::fs::create_dir_all(root.join("logs"))?;
+        File::create(root.join("logs/a.log"))?;
+        File::create(root.join("logs/b.log"))?;
+        File::create(root.join("main.rs"))?;
+        File::create(root.join("notes.txt"))?;
+        std::fs::write(root.join("big.rs"), "x".repeat(100))?;
+
+        let ignore = vec!["logs/".to_string()];
+        let include = vec!["*.rs".to_string()];
+        let config = WalkConfig {
+            ignore_patterns: &ignore,
+            include_patterns: &include,
+            max_file_size: Some(50),
+            ..Default::default()
+        };
+        let (files, filtered) = walk_directory_with_filtered(root, config)?;
+        assert_eq!(files, vec![root.join("main.rs")]);
+        let filtered: Vec<_> = filtered
+            .into_iter()
+            .map(|d| (d.path.strip_prefix(root).unwrap().to_path_buf(), d.reason))
+            .collect();
+
+        assert_eq!(
+            filtered,
+            vec![
+                (PathBuf::from("big.rs"), DropReason::TooLarge(100)),
+                (PathBuf::from("logs"), DropReason::Ignored("logs/".into())),
+                (PathBuf::from("notes.txt"), DropReason::NotIncluded),
+            ]
+        );
+
+        Ok(())
+    }
 }
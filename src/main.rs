@@ -1,5 +1,6 @@
 use abyss::config::OutputFormat;
 use abyss::git::{clone_repo, is_remote_url};
+use abyss::utils::tokens::Tokenizer;
 use abyss::{AbyssConfig, CompressionMode, run};
 use anyhow::Result;
 use clap::{Parser, ValueEnum};
@@ -112,10 +113,67 @@ struct Args {
     #[arg(long)]
     diff: Option<String>,
 
+    /// Only files changed since a date or age (e.g. "2024-05-01", "2w", "30 days")
+    #[arg(long, value_name = "DATE")]
+    since: Option<String>,
+
+    /// Only files with a commit by this author (name or email, case-insensitive substring)
+    #[arg(long, value_name = "NAME")]
+    author: Option<String>,
+
+    /// Only files owned by this CODEOWNERS owner (e.g. "@org/payments")
+    #[arg(long, value_name = "OWNER")]
+    owner: Option<String>,
+
+    /// Group the directory structure by CODEOWNERS owner
+    #[arg(long)]
+    group_by_owner: bool,
+
     /// Maximum tokens to include in output (e.g. 128000)
     #[arg(long)]
     max_tokens: Option<usize>,
 
+    /// Tokenizer to count with: o200k_base, cl100k_base, p50k_base, or chars:<N>
+    /// (N characters per token) [default: the model's, else cl100k_base]
+    #[arg(long, value_name = "NAME")]
+    tokenizer: Option<String>,
+
+    /// Model preset (gpt, claude, gemini or a [models] entry in abyss.toml); sets the
+    /// budget (context window minus reserved output) and tokenizer unless given explicitly
+    #[arg(long, value_name = "NAME")]
+    model: Option<String>,
+
+    /// With --max-tokens, also include what each selected file depends on
+    /// (at least as signature-only stubs, counted against the budget)
+    #[arg(long)]
+    with_deps: bool,
+
+    /// Place each test file right after the file it exercises (found via imports, then naming)
+    #[arg(long)]
+    pair_tests: bool,
+
+    /// What to do with generated files, per header banner: MARKER=ACTION (repeatable).
+    /// Markers: go, protobuf, tagged, do_not_edit, all. Actions: exclude, summary, keep
+    #[arg(long, value_name = "MARKER=ACTION")]
+    generated: Vec<String>,
+
+    /// Emit only the best-ranked file of each near-duplicate cluster; list the rest as "similar to X"
+    #[arg(long)]
+    dedup: bool,
+
+    /// Similarity (0-1) at which files count as near-duplicates [default: 0.85]
+    #[arg(long, value_name = "RATIO")]
+    dedup_threshold: Option<f64>,
+
+    /// With --dedup, list each near-duplicate as a unified diff against its representative
+    #[arg(long)]
+    dedup_diff: bool,
+
+    /// Rank files by proximity to these (glob, repeatable) instead of global centrality
+    /// Example: --focus "src/billing/*"
+    #[arg(long, value_name = "PATTERN")]
+    focus: Vec<String>,
+
     /// Enable dependency graph generation
     #[arg(long)]
     graph: bool,
@@ -124,15 +182,15 @@ struct Args {
     #[arg(long, value_name = "SHELL")]
     completions: Option<String>,
 
-    /// ChatGPT preset (128K tokens)
+    /// ChatGPT preset: 128K tokens, nothing reserved (--model gpt reserves 16K for the answer)
     #[arg(long)]
     gpt: bool,
 
-    /// Claude preset (200K tokens)
+    /// Claude preset: 200K tokens, nothing reserved (--model claude reserves 8K)
     #[arg(long)]
     claude: bool,
 
-    /// Gemini preset (1M tokens)
+    /// Gemini preset: 1M tokens, nothing reserved (--model gemini reserves 8K)
     #[arg(long)]
     gemini: bool,
 
@@ -153,6 +211,20 @@ struct Args {
     #[arg(long)]
     show_impact: bool,
 
+    /// Show a symbol's definitions, what it references and everything that uses it
+    /// Example: --symbol "Cache::update"
+    #[arg(long, value_name = "NAME")]
+    symbol: Option<String>,
+
+    /// Show each file's score breakdown, position and whether it was kept, then exit
+    /// Formats: table (default), json
+    #[arg(long, value_name = "FORMAT", num_args = 0..=1, default_missing_value = "table")]
+    explain_rank: Option<String>,
+
+    /// List groups of files that import each other (dependency cycles), then exit
+    #[arg(long)]
+    report_cycles: bool,
+
     /// Output in Cursor-compatible JSON format
     #[arg(long)]
     cursor: bool,
@@ -175,6 +247,10 @@ struct Args {
     /// Explain the semantics of the diff
     #[arg(long)]
     explain_diff: bool,
+
+    /// Add a C/C++ include directory for resolving `#include` (repeatable)
+    #[arg(long, value_name = "DIR")]
+    include_dir: Vec<PathBuf>,
 }
 
 fn main() -> Result<()> {
@@ -225,6 +301,9 @@ fn main() -> Result<()> {
     if !args.include.is_empty() {
         config.include_patterns = args.include;
     }
+    if !args.include_dir.is_empty() {
+        config.include_dirs.extend(args.include_dir);
+    }
     if let Some(s) = args.max_size {
         config.max_file_size = Some(s);
     }
@@ -258,20 +337,48 @@ fn main() -> Result<()> {
     if let Some(d) = args.diff {
         config.diff = Some(d);
     }
+    if args.query.is_some() {
+        config.query = args.query.clone();
+    }
+    if args.since.is_some() {
+        config.since = args.since;
+    }
+    if let Some(since) = &config.since {
+        abyss::utils::git_stats::parse_since(since, 0)?;
+    }
+    if args.author.is_some() {
+        config.author = args.author;
+    }
+    if args.owner.is_some() {
+        config.owner = args.owner;
+    }
+    if args.group_by_owner {
+        config.group_by_owner = true;
+    }
 
     if let Some(mt) = args.max_tokens {
         config.max_tokens = Some(mt);
     }
 
-    // Direct model preset flags
-    if args.gpt {
-        config.max_tokens = Some(128_000);
-    } else if args.claude {
-        config.max_tokens = Some(200_000);
-    } else if args.gemini {
-        config.max_tokens = Some(1_000_000);
+    if let Some(name) = &args.tokenizer {
+        config.tokenizer = Some(Tokenizer::parse(name)?);
     }
 
+    // Model presets fill in the budget and tokenizer not given explicitly
+    let legacy = [
+        (args.gpt, "gpt", 128_000),
+        (args.claude, "claude", 200_000),
+        (args.gemini, "gemini", 1_000_000),
+    ];
+    if args.model.is_some() {
+        config.model = args.model;
+    } else if let Some((_, name, budget)) = legacy.into_iter().find(|(set, _, _)| *set) {
+        // The old flags keep their old budget: the whole window, nothing reserved
+        config.model = Some(name.to_string());
+        config.max_tokens.get_or_insert(budget);
+    }
+    config.apply_model()?;
+
     // Environment variable fallback
     if config.max_tokens.is_none()
         && let Some(tokens) = std::env::var("ABYSS_MAX_TOKENS")
@@ -281,6 +388,35 @@ fn main() -> Result<()> {
         config.max_tokens = Some(tokens);
     }
 
+    if args.with_deps {
+        config.dependency_closure = true;
+    }
+    if args.pair_tests {
+        config.pair_tests = true;
+    }
+    for spec in &args.generated {
+        config.generated.set(spec)?;
+    }
+    if args.dedup || args.dedup_diff {
+        config.dedup = true;
+    }
+    if args.dedup_diff {
+        config.dedup_diff = true;
+    }
+    if let Some(threshold) = args.dedup_threshold {
+        if !(threshold > 0.0 && threshold <= 1.0) {
+            anyhow::bail!(
+                "--dedup-threshold must be between 0 and 1, got {}",
+                threshold
+            );
+        }
+        config.dedup_threshold = threshold;
+    }
+
+    if !args.focus.is_empty() {
+        config.ranking.focus = args.focus;
+    }
+
     if args.graph {
         config.graph = true;
     }
@@ -332,6 +468,9 @@ fn main() -> Result<()> {
     config.is_remote = _temp_dir.is_some();
     // hold temp_dir until end of scope
 
+    // Language overrides and include dirs are process-wide; set them once for every mode
+    config.configure_analysis();
+
     // Handle dry-run (pre-flight analysis)
     if args.dry_run {
         use abyss::runner::discover_files;
@@ -375,22 +514,21 @@ fn main() -> Result<()> {
     // Handle query-driven context
     if let Some(query_str) = &args.query {
         use abyss::runner::discover_files;
-        use abyss::utils::graph::DependencyGraph;
+        use abyss::utils::dependencies::build_dependency_graph;
         use abyss::utils::query::QueryEngine;
+        use abyss::utils::symbols::SymbolGraph;
         use std::collections::HashMap;
 
         let (files_res, _dropped) = discover_files(&config, None)?;
         let files: Vec<PathBuf> = files_res.into_iter().map(|(p, _)| p).collect();
         let root = &config.path; // Use config path as main root for display logic
 
-        // Build dependency graph for PageRank
-        let mut graph = DependencyGraph::new();
-        for path in &files {
-            graph.add_node(path.clone());
-        }
+        // Build dependency graph for PageRank, and symbols for definition matches
+        let graph = build_dependency_graph(&files, root);
+        let symbols = SymbolGraph::build(&files, &graph);
 
         // Create query engine
-        let engine = QueryEngine::new(query_str, &graph);
+        let engine = QueryEngine::new(query_str, &graph).with_symbols(&symbols);
 
         println!("Query: \"{}\"", query_str);
         println!("Keywords: {:?}", engine.keywords());
@@ -435,12 +573,116 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
+    // Handle symbol lookup
+    if let Some(name) = &args.symbol {
+        use abyss::runner::discover_files;
+        use abyss::utils::dependencies::build_dependency_graph;
+        use abyss::utils::impact::ImpactAnalyzer;
+        use abyss::utils::symbols::SymbolGraph;
+
+        let (files_res, _dropped) = discover_files(&config, None)?;
+        let files: Vec<PathBuf> = files_res.into_iter().map(|(p, _)| p).collect();
+        let root = &config.path;
+
+        let graph = build_dependency_graph(&files, root);
+        let symbols = SymbolGraph::build(&files, &graph);
+        let ids = symbols.find(name);
+        let display_root = root.canonicalize().unwrap_or_else(|_| root.clone());
+        if ids.is_empty() {
+            println!("No definition of '{}' found", name);
+            return Ok(());
+        }
+
+        let describe = |id: usize| {
+            let symbol = symbols.symbol(id);
+            let rel_path = symbol
+                .file
+                .strip_prefix(&display_root)
+                .unwrap_or(&symbol.file);
+            format!(
+                "{} {} ({}:{})",
+                symbol.kind,
+                symbol.qualified_name(),
+                rel_path.display(),
+                symbol.line
+            )
+        };
+
+        println!("Symbol: {}", name);
+        for &id in &ids {
+            println!("  {}", describe(id));
+            for reference in symbols.references(id) {
+                println!("    -> {}", describe(reference));
+            }
+            for referrer in symbols.referrers(id) {
+                println!("    <- {}", describe(referrer));
+            }
+        }
+        println!();
+
+        let analyzer = ImpactAnalyzer::new(&graph).with_symbols(&symbols);
+        if let Some(analysis) = analyzer.analyze_symbol(name, &files) {
+            println!("{}", analysis);
+        }
+        return Ok(());
+    }
+
+    // Handle rank report
+    if let Some(format) = &args.explain_rank {
+        use abyss::core::{discover, select_files};
+
+        let discovery = discover(&config, None)?;
+        let selection = select_files(discovery.files, &config);
+        let mut filtered = discovery.filtered;
+        filtered.extend(discovery.dropped);
+        let report = selection.report(&config, &filtered);
+
+        match format.to_lowercase().as_str() {
+            "json" => println!("{}", report.to_json()?),
+            "table" => print!("{}", report),
+            _ => {
+                eprintln!("Invalid report format: {}. Use: table, json", format);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    // Handle cycle report
+    if args.report_cycles {
+        use abyss::runner::discover_files;
+        use abyss::utils::dependencies::build_dependency_graph;
+
+        let (files_res, _dropped) = discover_files(&config, None)?;
+        let files: Vec<PathBuf> = files_res.into_iter().map(|(p, _)| p).collect();
+        let root = &config.path;
+
+        let cycles = build_dependency_graph(&files, root).cycles();
+        if cycles.is_empty() {
+            println!("No dependency cycles found in {} files", files.len());
+            return Ok(());
+        }
+
+        let display_root = root.canonicalize().unwrap_or_else(|_| root.clone());
+        println!("Dependency Cycles");
+        println!("=================");
+        for (i, cycle) in cycles.iter().enumerate() {
+            println!("\n{}. {} files", i + 1, cycle.len());
+            for path in cycle {
+                let rel_path = path.strip_prefix(&display_root).unwrap_or(path);
+                println!("   {}", rel_path.display());
+            }
+        }
+        return Ok(());
+    }
+
     // Handle impact analysis
     if args.show_impact {
         use abyss::runner::discover_files;
         use abyss::utils::git_stats::get_diff_files;
         use abyss::utils::graph::DependencyGraph;
         use abyss::utils::impact::ImpactAnalyzer;
+        use abyss::utils::symbols::SymbolGraph;
 
         let (files_res, _dropped) = discover_files(&config, None)?;
         let files: Vec<PathBuf> = files_res.into_iter().map(|(p, _)| p).collect();
@@ -451,12 +693,11 @@ fn main() -> Result<()> {
         for path in &files {
             graph.add_node(path.clone());
             if let Ok(content) = std::fs::read_to_string(path) {
-                let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("");
-                let imports = abyss::utils::dependencies::extract_imports(&content, extension);
+                let imports = abyss::utils::dependencies::file_imports(path, &content);
                 for import in imports {
                     // Try to resolve using CLI root. For complex multi-repo, this visualization might be limited.
-                    if let Some(dep_path) =
-                        abyss::utils::dependencies::resolve_import(&import, path, root)
+                    for dep_path in
+                        abyss::utils::dependencies::resolve_import_targets(&import, path, root)
                     {
                         graph.add_edge(path.clone(), dep_path);
                     }
@@ -479,16 +720,15 @@ fn main() -> Result<()> {
             return Ok(());
         }
 
-        // Run impact analysis
-        let analyzer = ImpactAnalyzer::new(&graph);
+        // Run impact analysis; symbol references also count as dependencies
+        let symbols = SymbolGraph::build(&files, &graph);
+        let analyzer = ImpactAnalyzer::new(&graph).with_symbols(&symbols);
         let analysis = analyzer.analyze(&changed_files, &files);
 
         println!("{}", analysis);
         return Ok(());
     }
 
-
-
     if args.explain_diff {
         use abyss::utils::diff_explainer::DiffExplainer;
         match DiffExplainer::explain(&config) {
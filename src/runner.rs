@@ -1,17 +1,17 @@
-use crate::config::{AbyssConfig, CompressionMode};
+use crate::config::AbyssConfig;
 // Re-export ScanEvent from core for backward compatibility
 pub use crate::core::ScanEvent;
 
-use crate::utils::ast::compress_ast;
+use crate::core::DroppedFile;
+use crate::core::render::{Fidelity, render};
 use crate::utils::clipboard::copy_to_clipboard;
-use crate::utils::compression::compress_content;
-use crate::utils::concepts::extract_concepts;
-use crate::utils::git_stats::get_git_stats;
-use crate::utils::tokens::count_tokens;
+use crate::utils::codeowners::CodeOwners;
+use crate::utils::source::SourceAnalysis;
+use crate::utils::tokens::count_tokens_with;
 use anyhow::Result;
 use crossbeam_channel::Sender;
 use rayon::prelude::*;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs as std_fs;
 use std::fs::File;
 use std::path::{Path, PathBuf};
@@ -201,11 +201,12 @@ impl OutputState {
         path: &std::path::Path,
         content: &str,
         summary: Option<&str>,
+        owners: &[String],
         repo_root: &std::path::Path,
         tokens: usize,
     ) -> Result<()> {
         self.formatter
-            .write_file(&mut self.file, path, content, summary, repo_root)?;
+            .write_file(&mut self.file, path, content, summary, owners, repo_root)?;
         self.current_tokens += tokens;
 
         if let Some(files) = &mut self.bundle_files {
@@ -215,7 +216,7 @@ impl OutputState {
         Ok(())
     }
 
-    fn finish(&mut self, dropped: &[PathBuf]) -> Result<()> {
+    fn finish(&mut self, dropped: &[DroppedFile]) -> Result<()> {
         self.formatter.write_footer(&mut self.file, dropped)?;
         Ok(())
     }
@@ -228,16 +229,26 @@ impl OutputState {
         self.formatter
             .write_directory_structure(&mut self.file, paths, root)
     }
+
+    fn write_grouped_structure(
+        &mut self,
+        groups: &[crate::format::OwnerGroup],
+        root: &std::path::Path,
+    ) -> Result<()> {
+        self.formatter
+            .write_grouped_structure(&mut self.file, groups, root)
+    }
 }
 
 /// Discovers and sorts files according to configuration
 ///
 /// This is a re-export for backward compatibility. The actual implementation
 /// is in `core::scanner::discover_files`.
+#[allow(clippy::type_complexity)]
 pub fn discover_files(
     config: &AbyssConfig,
     tx: Option<Sender<ScanEvent>>,
-) -> Result<(Vec<(PathBuf, PathBuf)>, Vec<PathBuf>)> {
+) -> Result<(Vec<(PathBuf, PathBuf)>, Vec<DroppedFile>)> {
     crate::core::scanner::discover_files(config, tx)
 }
 
@@ -249,7 +260,7 @@ pub fn run_scan(config: AbyssConfig, sender: Option<Sender<ScanEvent>>) -> Resul
 /// Processes the selected files and generates output
 pub fn process_files(
     mut files: Vec<(PathBuf, PathBuf)>,
-    mut dropped_files: Vec<PathBuf>,
+    mut dropped_files: Vec<DroppedFile>,
     config: AbyssConfig,
     tx: Option<Sender<ScanEvent>>,
 ) -> Result<()> {
@@ -259,150 +270,13 @@ pub fn process_files(
         }
     };
 
-    // Intelligence: Build graph, calculate scores, and rank files before processing content
-    let mut git_stats_map = HashMap::new();
-    let roots: std::collections::HashSet<_> = files.iter().map(|(_, root)| root.clone()).collect();
-    for root in roots {
-        let stats = get_git_stats(&root);
-        git_stats_map.insert(root, stats);
-    }
-
-    let mut graph = crate::utils::graph::DependencyGraph::new();
-    let mut scores: HashMap<PathBuf, crate::utils::rank::FileScore> = HashMap::new();
-
-    // Pre-calculate Heuristic & Churn
-    for (path, root) in &files {
-        let mut score = crate::utils::rank::FileScore {
-            heuristic: crate::utils::rank::heuristic_score(path),
-            ..Default::default()
-        };
-
-        if let Some(stats) = git_stats_map.get(root)
-            && let Some(s) = stats.get(path)
-        {
-            score.churn = std::cmp::min(s.churn_score * 5, 200) as i32;
-        }
-        scores.insert(path.clone(), score);
-    }
-
-    // Scan content for Entropy & Dependencies (Parallel)
-    struct FileAnalysis {
-        path: PathBuf,
-        root: PathBuf,
-        entropy: f64,
-        tokens: usize,
-        imports: Vec<String>,
-        #[allow(dead_code)]
-        extension: String,
-        #[allow(dead_code)]
-        content: String,
-    }
-
-    let no_tokens = config.no_tokens;
-    let analyses: Vec<FileAnalysis> = files
-        .par_iter()
-        .filter_map(|(path, root)| {
-            let content = std_fs::read_to_string(path).ok()?;
-            let extension = path
-                .extension()
-                .and_then(|s| s.to_str())
-                .unwrap_or("")
-                .to_string();
-
-            let entropy = crate::utils::rank::calculate_entropy(&content);
-            let tokens = if !no_tokens {
-                crate::utils::tokens::estimate_tokens(&content)
-            } else {
-                content.len() / 4
-            };
-            let imports = crate::utils::dependencies::extract_imports(&content, &extension);
-
-            Some(FileAnalysis {
-                path: path.clone(),
-                root: root.clone(),
-                entropy,
-                tokens,
-                imports,
-                extension,
-                content,
-            })
-        })
-        .collect();
-
-    // Build graph atomically
-    for analysis in &analyses {
-        graph.add_node(analysis.path.clone());
-
-        if let Some(s) = scores.get_mut(&analysis.path) {
-            s.entropy = analysis.entropy;
-            s.tokens = analysis.tokens;
-        }
-
-        for import in &analysis.imports {
-            if let Some(resolved) =
-                crate::utils::dependencies::resolve_import(import, &analysis.path, &analysis.root)
-            {
-                graph.add_edge(analysis.path.clone(), resolved);
-            }
-        }
-    }
-
-    // PageRank
-    let page_ranks = graph.calculate_pagerank();
-    for (path, score) in &page_ranks {
-        if let Some(s) = scores.get_mut(path) {
-            s.pagerank = *score;
-        }
-    }
-
-    // Sort & Knapsack
-    let all_paths: Vec<PathBuf> = files.iter().map(|(p, _)| p.clone()).collect();
-    let sorted_paths = crate::utils::rank::sort_files(&all_paths, &scores, &graph);
-
-    // Re-order and Filter
-    let mut final_files = Vec::new();
-    let mut current_total_tokens = 0;
-
-    let max_tokens = config.max_tokens.unwrap_or(usize::MAX);
-
-    // Create candidate list (sorted by topological order, but we want to filter by priority if budget constrained)
-    // Actually, knapsack logic was to prioritize high score items.
-    // If max_tokens is set, we sort by score first to select candidates, then output in topo order.
-
-    let mut selected_set = std::collections::HashSet::new();
-
-    if config.max_tokens.is_some() {
-        let mut candidates: Vec<&PathBuf> = all_paths.iter().collect();
-        candidates.sort_by(|a, b| {
-            let score_a = scores.get(*a).map(|s| s.final_score()).unwrap_or(0.0);
-            let score_b = scores.get(*b).map(|s| s.final_score()).unwrap_or(0.0);
-            score_b
-                .partial_cmp(&score_a)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-
-        for path in candidates {
-            let t = scores.get(path).map(|s| s.tokens).unwrap_or(0);
-            if current_total_tokens + t <= max_tokens {
-                selected_set.insert(path.clone());
-                current_total_tokens += t;
-            } else {
-                dropped_files.push(path.clone());
-            }
-        }
-    } else {
-        selected_set = all_paths.iter().cloned().collect();
-    }
-
-    for path in sorted_paths {
-        if selected_set.contains(&path)
-            && let Some((_, root)) = files.iter().find(|(p, _)| p == &path)
-        {
-            final_files.push((path, root.clone()));
-        }
-    }
-
-    files = final_files;
+    let selection = crate::core::select_files(files, &config);
+    dropped_files.extend(selection.dropped);
+    let graph = selection.graph;
+    let sources = selection.sources;
+    let placements = selection.placements;
+    let duplicates = selection.duplicates;
+    files = selection.files;
     notify(ScanEvent::FilesFound(files.len()));
 
     // 2. Setup Streaming and Output
@@ -420,6 +294,9 @@ pub fn process_files(
     let total_tokens_ref = &total_tokens_atomic;
     let cache_ref = &cache;
     let config_sig_ref = &config_sig;
+    let sources_ref = &sources;
+    let placements_ref = &placements;
+    let duplicates_ref = &duplicates;
 
     std::thread::scope(|s| {
         s.spawn(move || {
@@ -449,6 +326,15 @@ pub fn process_files(
                                 return;
                             }
                         }
+                    } else if extension == "ipynb" {
+                        match crate::utils::notebook::extract_text(path) {
+                            Ok(text) => content = text,
+                            Err(e) => {
+                                eprintln!("Failed to extract notebook: {}", e);
+                                let _ = data_tx.send((index, None));
+                                return;
+                            }
+                        }
                     } else if ["png", "jpg", "jpeg", "gif", "webp", "bmp", "ico", "tiff"]
                         .contains(&extension.as_str())
                     {
@@ -477,12 +363,21 @@ pub fn process_files(
                         }
                     }
 
+                    let fidelity = placements_ref
+                        .get(path)
+                        .map_or(Fidelity::Full, |(fidelity, _)| *fidelity);
+                    let duplicate = duplicates_ref.get(path);
+                    // Budget fallbacks render the same file differently
+                    let signature = match duplicate {
+                        Some(duplicate) => format!("{} {}", config_sig_ref, duplicate.listing),
+                        None => format!("{} {}", config_sig_ref, fidelity),
+                    };
+
                     let modified_time = get_modified_time(path).unwrap_or(0);
                     let mut cached_entry = None;
 
                     if modified_time > 0 {
-                        let hash =
-                            crate::utils::cache::Cache::compute_hash(&content, config_sig_ref);
+                        let hash = crate::utils::cache::Cache::compute_hash(&content, &signature);
                         #[allow(clippy::collapsible_if)]
                         if let Some(entry) = cache_ref.get(&path.to_string_lossy()) {
                             if entry.modified == modified_time && entry.hash == hash {
@@ -496,55 +391,37 @@ pub fn process_files(
                     }
 
                     let extension_str = path.extension().and_then(|s| s.to_str()).unwrap_or("");
-                    let concepts = extract_concepts(&content, extension_str);
 
-                    match config_ref.compression {
-                        CompressionMode::Simple => {
-                            content = compress_content(&content);
+                    // Reuse the first-pass parse unless the text changed (redaction, PDFs, images)
+                    let reanalyzed;
+                    let source = match sources_ref.get(path) {
+                        Some(source) if source.matches(&content) => source,
+                        _ => {
+                            reanalyzed = SourceAnalysis::analyze(&content, extension_str);
+                            &reanalyzed
                         }
-                        CompressionMode::Smart => {
-                            content = compress_ast(&content, extension_str);
+                    };
+                    content = match duplicate {
+                        // Listed against the file it resembles instead of in full
+                        Some(duplicate) if config_ref.redact => {
+                            crate::utils::privacy::redact_content(&duplicate.listing)
                         }
-                        CompressionMode::None => {}
-                    }
-
-                    if !config_ref
-                        .compression_level
-                        .to_compression_mode()
-                        .eq(&CompressionMode::None)
-                    {
-                        content = crate::utils::compression::compress_by_level(
-                            &content,
-                            config_ref.compression_level,
-                            extension_str,
-                        );
-                    }
-
-                    if !concepts.is_empty() {
-                        let concept_str = concepts.join(", ");
-                        let (prefix, suffix) = match extension_str {
-                            "py" | "rb" | "sh" | "yaml" | "toml" | "dockerfile" | "makefile" => {
-                                ("#", "")
-                            }
-                            "html" | "xml" | "md" => ("<!--", " -->"),
-                            _ => ("//", ""),
-                        };
-                        content = format!(
-                            "{} Concepts: {}{}\n{}",
-                            prefix, concept_str, suffix, content
-                        );
-                    }
+                        Some(duplicate) => duplicate.listing.clone(),
+                        None => render(content, source, extension_str, config_ref, fidelity),
+                    };
 
                     let count = if !config_ref.no_tokens {
                         if let Some(tokens) = cached_entry {
                             tokens
-                        } else if let Ok(c) = count_tokens(&content) {
+                        } else if let Ok(c) =
+                            count_tokens_with(&content, config_ref.tokenizer.unwrap_or_default())
+                        {
                             if modified_time > 0 {
                                 let hash = crate::utils::cache::Cache::compute_hash(
                                     &String::from_utf8_lossy(
                                         &std_fs::read(path).unwrap_or_default(),
                                     ),
-                                    config_sig_ref,
+                                    &signature,
                                 );
                                 cache_ref.update(
                                     path.to_string_lossy().to_string(),
@@ -568,7 +445,7 @@ pub fn process_files(
                         notify_ref(ScanEvent::TokenCountUpdate(current));
                     }
 
-                    let summary = crate::utils::summary::summarize_content(&content, extension_str);
+                    let summary = source.summary.clone();
 
                     notify_ref(ScanEvent::FileProcessed(path.clone()));
                     let _ = data_tx.send((index, Some((path.clone(), content, summary, count))));
@@ -584,10 +461,8 @@ pub fn process_files(
             let paths_only: Vec<PathBuf> = files.iter().map(|(p, _)| p.clone()).collect();
             // Root path is tricky. Use first root or config path.
             let display_root = &config.path;
-            let graph =
-                crate::utils::dependencies::build_dependency_graph(&paths_only, display_root);
             Some(crate::format::mermaid::generate_diagram(
-                &graph,
+                &graph.subgraph(&paths_only),
                 display_root,
             ))
         } else {
@@ -601,7 +476,11 @@ pub fn process_files(
         for (path, _) in files.iter().take(5) {
             let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("");
             if let Ok(content) = std_fs::read_to_string(path) {
-                if let Some(s) = crate::utils::summary::summarize_content(&content, extension) {
+                let summary = match sources.get(path) {
+                    Some(source) if source.matches(&content) => source.summary.clone(),
+                    _ => crate::utils::summary::summarize_content(&content, extension),
+                };
+                if let Some(s) = summary {
                     key_files.push((path.clone(), s));
                 }
 
@@ -616,11 +495,33 @@ pub fn process_files(
             }
         }
 
-        let overview = if !key_files.is_empty() || purpose.is_some() {
+        let paths_only: Vec<PathBuf> = files.iter().map(|(p, _)| p.clone()).collect();
+        let display_root = config
+            .path
+            .canonicalize()
+            .unwrap_or_else(|_| config.path.clone());
+        let cycles: Vec<Vec<PathBuf>> = graph
+            .subgraph(&paths_only)
+            .cycles()
+            .into_iter()
+            .map(|cycle| {
+                cycle
+                    .into_iter()
+                    .map(|p| {
+                        p.strip_prefix(&display_root)
+                            .map(Path::to_path_buf)
+                            .unwrap_or(p)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let overview = if !key_files.is_empty() || purpose.is_some() || !cycles.is_empty() {
             Some(crate::format::RepoOverview {
                 purpose,
                 key_files,
                 changes: None,
+                cycles,
             })
         } else {
             None
@@ -635,11 +536,46 @@ pub fn process_files(
                 }
             };
 
+        // CODEOWNERS of each repository, for annotations and grouping
+        let roots: HashSet<&PathBuf> = files.iter().map(|(_, root)| root).collect();
+        let codeowners: HashMap<&PathBuf, CodeOwners> = roots
+            .into_iter()
+            .filter_map(|root| Some((root, CodeOwners::load(root)?)))
+            .collect();
+        let owners_of = |path: &PathBuf, root: &PathBuf| -> Vec<String> {
+            codeowners
+                .get(root)
+                .map(|o| o.owners(path.strip_prefix(root).unwrap_or(path)).to_vec())
+                .unwrap_or_default()
+        };
+
         // Write dir structure
         let paths_only: Vec<PathBuf> = files.iter().map(|(p, _)| p.clone()).collect();
         // Use config.path as root for structure display if single repo, else common ancestor?
         // Simpler: Just use config.path.
-        if let Err(e) = out_state.write_directory_structure(&paths_only, &config.path) {
+        let written = if config.group_by_owner {
+            let mut groups: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+            for (path, root) in &files {
+                let owners = owners_of(path, root);
+                let label = if owners.is_empty() {
+                    crate::format::UNOWNED.to_string()
+                } else {
+                    owners.join(" ")
+                };
+                groups.entry(label).or_default().push(path.clone());
+            }
+            // Owned groups by name, unowned files last
+            let unowned = groups.remove(crate::format::UNOWNED);
+            let groups: Vec<crate::format::OwnerGroup> = groups
+                .into_iter()
+                .chain(unowned.map(|files| (crate::format::UNOWNED.to_string(), files)))
+                .map(|(owners, files)| crate::format::OwnerGroup { owners, files })
+                .collect();
+            out_state.write_grouped_structure(&groups, &config.path)
+        } else {
+            out_state.write_directory_structure(&paths_only, &config.path)
+        };
+        if let Err(e) = written {
             notify(ScanEvent::Error(e.to_string()));
             return;
         }
@@ -658,7 +594,10 @@ pub fn process_files(
                     let root = files[next_idx].1.clone();
 
                     let summary_ref = summary.as_deref();
-                    if let Err(e) = out_state.write(&path, &content, summary_ref, &root, tokens) {
+                    let owners = owners_of(&path, &root);
+                    if let Err(e) =
+                        out_state.write(&path, &content, summary_ref, &owners, &root, tokens)
+                    {
                         notify(ScanEvent::Error(e.to_string()));
                     }
                 } else {
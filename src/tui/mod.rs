@@ -155,7 +155,9 @@ fn run_app<B: ratatui::backend::Backend>(
                                 }
                             }
                             KeyCode::Tab => state.next_tab(),
-                            KeyCode::Char('o') => {
+                            KeyCode::Char('o') =>
+                            {
+                                #[allow(clippy::collapsible_match)]
                                 if state.step == AppStep::Done {
                                     let path = state.config.output.display().to_string();
                                     #[cfg(target_os = "macos")]
@@ -224,7 +226,7 @@ fn run_app<B: ratatui::backend::Backend>(
                                     state.toggle_expand();
                                 }
                                 KeyCode::Enter => {
-                                    #[allow(clippy::collapsible_if)]
+                                    #[allow(clippy::collapsible_if, clippy::collapsible_match)]
                                     if state.step == AppStep::FileSelection {
                                         if let Some(files_with_roots) = &pending_discovery {
                                             // Transition to Processing
@@ -37,7 +37,7 @@ mod tests {
             let mut file = fs::File::create(&ignore_path).unwrap();
             writeln!(file, "# Comment line").unwrap();
             writeln!(file, "*.test.rs").unwrap();
-            writeln!(file, "").unwrap();
+            writeln!(file).unwrap();
             writeln!(file, "mock_*").unwrap();
             writeln!(file, "  # Indented comment  ").unwrap();
             writeln!(file, "  spaced_pattern  ").unwrap();
@@ -1,69 +1,34 @@
+use crate::utils::language::LanguageSupport;
 use streaming_iterator::StreamingIterator;
-use tree_sitter::{Parser, Query, QueryCursor};
+use tree_sitter::QueryCursor;
 
 pub fn extract_concepts(content: &str, extension: &str) -> Vec<String> {
-    let mut concepts = Vec::new();
-    let mut parser = Parser::new();
-
-    let (language, query_str) = match extension {
-        "rs" => (
-            tree_sitter_rust::LANGUAGE.into(),
-            r#"
-            (struct_item name: (_) @name)
-            (enum_item name: (_) @name)
-            (trait_item name: (_) @name)
-            (impl_item type: (_) @name)
-            (function_item name: (_) @name)
-            (mod_item name: (_) @name)
-            "#,
-        ),
-        "py" => (
-            tree_sitter_python::LANGUAGE.into(),
-            r#"
-            (class_definition name: (_) @name)
-            (function_definition name: (_) @name)
-            "#,
-        ),
-        "js" | "jsx" => (
-            tree_sitter_javascript::LANGUAGE.into(),
-            r#"
-            (class_declaration name: (_) @name)
-            (function_declaration name: (_) @name)
-            (variable_declarator name: (_) @name value: (arrow_function))
-             "#,
-        ),
-        "ts" | "tsx" => (
-            tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
-            r#"
-            (class_declaration name: (_) @name)
-            (interface_declaration name: (_) @name)
-            (function_declaration name: (_) @name)
-            (variable_declarator name: (_) @name value: (arrow_function))
-             "#,
-        ),
-        "go" => (
-            tree_sitter_go::LANGUAGE.into(),
-            r#"
-            (type_spec name: (_) @name)
-            (function_declaration name: (_) @name)
-            (method_declaration name: (_) @name)
-             "#,
-        ),
-        _ => return concepts,
+    let Some(support) = crate::utils::language::for_extension(extension) else {
+        return Vec::new();
     };
-
-    if parser.set_language(&language).is_err() {
-        return concepts;
+    let content = &crate::utils::notebook::source_text(content, extension);
+    if support.blocks.is_some() {
+        return crate::utils::source::SourceAnalysis::analyze(content, extension).concepts;
     }
+    match support.parse(content) {
+        Some(tree) => concepts_from_tree(&support, &tree, content),
+        None => Vec::new(),
+    }
+}
 
-    let tree = match parser.parse(content, None) {
-        Some(t) => t,
-        None => return concepts,
-    };
-
-    let query = match Query::new(&language, query_str) {
-        Ok(q) => q,
-        Err(_) => return concepts,
+/// Runs the language's concept query over an already parsed tree.
+pub(crate) fn concepts_from_tree(
+    support: &LanguageSupport,
+    tree: &tree_sitter::Tree,
+    content: &str,
+) -> Vec<String> {
+    let mut concepts = Vec::new();
+    let Some(query) = support
+        .concepts
+        .as_deref()
+        .and_then(|q| support.cached_query(q))
+    else {
+        return concepts;
     };
 
     let mut cursor = QueryCursor::new();
@@ -85,16 +50,9 @@ pub fn extract_concepts(content: &str, extension: &str) -> Vec<String> {
                         .parent()
                         .map(|p: tree_sitter::Node| p.kind())
                         .unwrap_or("");
-                    let label = match kind {
-                        "struct_item" => format!("struct {}", text),
-                        "enum_item" => format!("enum {}", text),
-                        "trait_item" => format!("trait {}", text),
-                        "impl_item" => format!("impl {}", text),
-                        "function_item" => format!("fn {}", text),
-                        "class_definition" | "class_declaration" => format!("class {}", text),
-                        "function_definition" | "function_declaration" => format!("fn {}", text),
-                        "interface_declaration" => format!("interface {}", text),
-                        _ => text.to_string(),
+                    let label = match definition_kind(kind) {
+                        Some(prefix) => format!("{} {}", prefix, text),
+                        None => text.to_string(),
                     };
 
                     concepts.push(label);
@@ -110,6 +68,23 @@ pub fn extract_concepts(content: &str, extension: &str) -> Vec<String> {
     concepts
 }
 
+/// Label prefix for a definition node kind (`struct_item` -> `struct`).
+pub(crate) fn definition_kind(node_kind: &str) -> Option<&'static str> {
+    Some(match node_kind {
+        "struct_item" => "struct",
+        "enum_item" => "enum",
+        "trait_item" => "trait",
+        "impl_item" => "impl",
+        "function_item" => "fn",
+        "class_definition" | "class_declaration" => "class",
+        "function_definition" | "function_declaration" => "fn",
+        "interface_declaration" => "interface",
+        "enum_declaration" => "enum",
+        "record_declaration" => "record",
+        _ => return None,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -144,4 +119,20 @@ def global_func():
         assert!(concepts.contains(&"fn global_func".to_string()));
         // fn method might be there too.
     }
+
+    #[test]
+    fn test_extract_java_concepts() {
+        let code = r#"
+public class OrderService implements Service {
+    enum Status { OPEN, CLOSED }
+    public Order place(Cart cart) { return null; }
+}
+interface Service {}
+        "#;
+        let concepts = extract_concepts(code, "java");
+        assert!(concepts.contains(&"class OrderService".to_string()));
+        assert!(concepts.contains(&"interface Service".to_string()));
+        assert!(concepts.contains(&"enum Status".to_string()));
+        assert!(concepts.contains(&"place".to_string()));
+    }
 }
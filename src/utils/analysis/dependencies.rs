@@ -1,65 +1,42 @@
+use crate::utils::language::LanguageSupport;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use streaming_iterator::StreamingIterator;
 use topological_sort::TopologicalSort;
-use tree_sitter::{Parser, Query, QueryCursor};
+use tree_sitter::QueryCursor;
 
 /// Extracts import statements from file content.
 /// Returns a list of imported module names/paths.
 pub fn extract_imports(content: &str, extension: &str) -> Vec<String> {
-    let mut imports = Vec::new();
-    let mut parser = Parser::new();
-
-    let (language, query_str) = match extension {
-        "rs" => (
-            tree_sitter_rust::LANGUAGE.into(),
-            r#"
-            (use_declaration argument: (_) @import)
-            (mod_item name: (_) @import)
-            "#,
-        ),
-        "py" => (
-            tree_sitter_python::LANGUAGE.into(),
-            r#"
-            (import_statement name: (_) @import)
-            (import_from_statement module_name: (_) @import)
-            "#,
-        ),
-        "js" | "jsx" => (
-            tree_sitter_javascript::LANGUAGE.into(),
-            r#"
-            (import_statement source: (string) @import)
-            (call_expression function: (identifier) @func arguments: (arguments (string) @import) (#eq? @func "require"))
-             "#,
-        ),
-        "ts" | "tsx" => (
-            tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
-            r#"
-            (import_statement source: (string) @import)
-            (call_expression function: (identifier) @func arguments: (arguments (string) @import) (#eq? @func "require"))
-             "#,
-        ),
-        "go" => (
-            tree_sitter_go::LANGUAGE.into(),
-            r#"
-            (import_spec path: (string_literal) @import)
-             "#,
-        ),
-        _ => return imports,
+    let Some(support) = crate::utils::language::for_extension(extension) else {
+        return Vec::new();
     };
-
-    if parser.set_language(&language).is_err() {
-        return imports;
+    let content = &crate::utils::notebook::source_text(content, extension);
+    if support.blocks.is_some() {
+        return crate::utils::source::SourceAnalysis::analyze(content, extension).imports;
     }
+    if let Some(scan) = support.line_imports {
+        return scan(content);
+    }
+    match support.parse(content) {
+        Some(tree) => imports_from_tree(&support, &tree, content),
+        None => Vec::new(),
+    }
+}
 
-    let tree = match parser.parse(content, None) {
-        Some(t) => t,
-        None => return imports,
-    };
-
-    let query = match Query::new(&language, query_str) {
-        Ok(q) => q,
-        Err(_) => return imports,
+/// Runs the language's import query over an already parsed tree.
+pub(crate) fn imports_from_tree(
+    support: &LanguageSupport,
+    tree: &tree_sitter::Tree,
+    content: &str,
+) -> Vec<String> {
+    let mut imports = Vec::new();
+    let Some(query) = support
+        .imports
+        .as_deref()
+        .and_then(|q| support.cached_query(q))
+    else {
+        return imports;
     };
 
     let mut cursor = QueryCursor::new();
@@ -75,14 +52,7 @@ pub fn extract_imports(content: &str, extension: &str) -> Vec<String> {
             }
 
             if let Ok(text) = capture.node.utf8_text(content.as_bytes()) {
-                let mut clean_text = text.trim_matches(|c| c == '"' || c == '\'').to_string();
-                #[allow(clippy::collapsible_if)]
-                if extension == "py" {
-                    if let Some(idx) = clean_text.find(" as ") {
-                        clean_text = clean_text[..idx].to_string();
-                    }
-                }
-                imports.push(clean_text);
+                imports.extend((support.import_capture)(capture.node, text, content));
             }
         }
     }
@@ -93,90 +63,897 @@ pub fn extract_imports(content: &str, extension: &str) -> Vec<String> {
     imports
 }
 
-/// Heuristic resolution of import strings to repository paths.
-pub fn resolve_import(import: &str, current_file: &Path, repo_root: &Path) -> Option<PathBuf> {
-    let extension = current_file
-        .extension()
-        .and_then(|s| s.to_str())
-        .unwrap_or("");
+/// Default capture handling: the import is the captured string without its quotes.
+pub(crate) fn quoted_import(_node: tree_sitter::Node, text: &str, _content: &str) -> Vec<String> {
+    vec![text.trim_matches(['"', '\'', '`']).to_string()]
+}
 
-    match extension {
-        "rs" => {
-            // crate::utils::foo -> src/utils/foo.rs
-            // super::foo -> parent/foo.rs
-            // mod foo -> current_dir/foo.rs or current_dir/foo/mod.rs
+/// Java imports keep a trailing `.*` for `import a.b.*;` so the whole package is linked.
+pub(crate) fn java_import_paths(
+    node: tree_sitter::Node,
+    text: &str,
+    _content: &str,
+) -> Vec<String> {
+    let mut cursor = node.walk();
+    let wildcard = node
+        .parent()
+        .is_some_and(|p| p.children(&mut cursor).any(|c| c.kind() == "asterisk"));
+    if wildcard {
+        vec![format!("{}.*", text)]
+    } else {
+        vec![text.to_string()]
+    }
+}
 
-            let current_dir = current_file.parent().unwrap_or(repo_root);
+/// Turns a captured Rust `use` argument or `mod` name into file-relative module paths.
+///
+/// Use-trees are expanded, and paths written inside inline modules (`mod tests { use super::*; }`)
+/// are rebased onto the enclosing file's module; paths that stay inside the file are dropped.
+/// File references are returned with a prefix: `path:` for `#[path]` modules, `include:` for
+/// `include!`/`include_str!`/`include_bytes!`, `out_dir:` for includes from the build script's
+/// `OUT_DIR`, and `build_input:` for `compile_protos` inputs.
+pub(crate) fn rust_import_paths(node: tree_sitter::Node, text: &str, content: &str) -> Vec<String> {
+    match node.kind() {
+        "macro_invocation" => return rust_macro_paths(node, content),
+        "call_expression" => {
+            return string_literals(node, content)
+                .into_iter()
+                .map(|s| format!("build_input:{}", s))
+                .collect();
+        }
+        _ => {}
+    }
 
-            if import.starts_with("crate::") {
-                let suffix = import.trim_start_matches("crate::").replace("::", "/");
-                let candidate = repo_root.join("src").join(format!("{}.rs", suffix));
-                if candidate.exists() {
-                    return Some(candidate);
-                }
-                let candidate_mod = repo_root.join("src").join(&suffix).join("mod.rs");
-                if candidate_mod.exists() {
-                    return Some(candidate_mod);
-                }
-            } else if !import.contains("::") {
-                // simple mod declaration or separate use
-                let candidate = current_dir.join(format!("{}.rs", import));
-                if candidate.exists() {
-                    return Some(candidate);
-                }
-                let candidate_mod = current_dir.join(import).join("mod.rs");
-                if candidate_mod.exists() {
-                    return Some(candidate_mod);
-                }
+    let is_mod_decl = node.parent().is_some_and(|p| p.kind() == "mod_item");
+
+    // Count enclosing inline `mod foo { ... }` blocks
+    let mut depth = 0;
+    let mut ancestor = if is_mod_decl {
+        node.parent().and_then(|p| p.parent())
+    } else {
+        node.parent()
+    };
+    while let Some(n) = ancestor {
+        if n.kind() == "mod_item" {
+            depth += 1;
+        }
+        ancestor = n.parent();
+    }
+
+    if is_mod_decl {
+        // `mod foo;` nested in an inline module lives in a subdirectory we don't track.
+        if depth > 0 {
+            return Vec::new();
+        }
+        let path_attribute = node.parent().and_then(|m| path_attribute(m, content));
+        return match path_attribute {
+            Some(path) => vec![format!("path:{}", path)],
+            None => vec![text.to_string()],
+        };
+    }
+
+    expand_use_tree(text)
+        .into_iter()
+        .filter_map(|path| rebase_inline_path(&path, depth))
+        .collect()
+}
+
+/// File referenced by an include macro.
+fn rust_macro_paths(node: tree_sitter::Node, content: &str) -> Vec<String> {
+    let literals = string_literals(node, content);
+
+    // include!(concat!(env!("OUT_DIR"), "/generated.rs"))
+    if literals.iter().any(|s| s == "OUT_DIR") {
+        return literals
+            .last()
+            .filter(|s| *s != "OUT_DIR")
+            .map(|s| format!("out_dir:{}", s.trim_start_matches('/')))
+            .into_iter()
+            .collect();
+    }
+    literals
+        .first()
+        .map(|s| format!("include:{}", s))
+        .into_iter()
+        .collect()
+}
+
+/// The value of a `#[path = "..."]` attribute on a `mod` item.
+fn path_attribute(module: tree_sitter::Node, content: &str) -> Option<String> {
+    let mut sibling = module.prev_named_sibling();
+    while let Some(attribute) = sibling.filter(|s| s.kind() == "attribute_item") {
+        let text = attribute.utf8_text(content.as_bytes()).ok()?;
+        if text
+            .trim_start_matches("#[")
+            .trim_start()
+            .starts_with("path")
+        {
+            return string_literals(attribute, content).into_iter().next();
+        }
+        sibling = attribute.prev_named_sibling();
+    }
+    None
+}
+
+/// Inputs a Cargo build script announces with `cargo:rerun-if-changed`, as `build_input:` imports.
+///
+/// Only `build.rs` files are scanned; anywhere else the string is just output.
+pub fn build_script_inputs(path: &Path, content: &str) -> Vec<String> {
+    if path.file_name().is_none_or(|name| name != "build.rs") {
+        return Vec::new();
+    }
+    let Some(tree) = crate::utils::language::for_extension("rs").and_then(|s| s.parse(content))
+    else {
+        return Vec::new();
+    };
+    string_literals(tree.root_node(), content)
+        .iter()
+        .filter_map(|s| s.split_once("rerun-if-changed="))
+        .map(|(_, input)| format!("build_input:{}", input))
+        .collect()
+}
+
+/// Imports of the file at `path`: [`extract_imports`] plus its build script inputs.
+pub fn file_imports(path: &Path, content: &str) -> Vec<String> {
+    let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("");
+    let mut imports = extract_imports(content, extension);
+    imports.extend(build_script_inputs(path, content));
+    imports
+}
+
+/// Contents of the string literals under `node`, in source order.
+fn string_literals(node: tree_sitter::Node, content: &str) -> Vec<String> {
+    let mut literals = Vec::new();
+    let mut stack = vec![node];
+    while let Some(n) = stack.pop() {
+        if n.kind() == "string_literal" || n.kind() == "raw_string_literal" {
+            if let Ok(text) = n.utf8_text(content.as_bytes()) {
+                let text = text.trim_start_matches('r').trim_matches('#');
+                literals.push((n.start_byte(), text.trim_matches('"').to_string()));
             }
-            // Relative `super::` resolution is not implemented.
-        }
-        "js" | "ts" | "jsx" | "tsx" => {
-            // Relatives: ./foo, ../bar
-            if import.starts_with('.') {
-                let current_dir = current_file.parent().unwrap_or(repo_root);
-                let base = current_dir.join(import);
-
-                // Try extensions
-                for ext in &["ts", "tsx", "js", "jsx", "d.ts"] {
-                    let candidate = base.with_extension(ext);
-                    if candidate.exists() {
-                        return Some(candidate);
-                    }
-                }
-                // Index files
-                for ext in &["ts", "tsx", "js", "jsx"] {
-                    let candidate = base.join(format!("index.{}", ext));
-                    if candidate.exists() {
-                        return Some(candidate);
-                    }
+            continue;
+        }
+        let mut cursor = n.walk();
+        stack.extend(n.children(&mut cursor));
+    }
+    literals.sort();
+    literals.into_iter().map(|(_, s)| s).collect()
+}
+
+/// Whether an import from `rust_import_paths` inlines the target into the including file
+/// (`include!`, `include_str!`, `include_bytes!`), so the target belongs right next to it.
+pub fn is_inline_include(import: &str) -> bool {
+    import.starts_with("include:")
+}
+
+/// Collects `import a.b.C`, `import a.b.*` and `import a.b.C as D` targets from Kotlin sources.
+pub(crate) fn extract_kotlin_imports(content: &str) -> Vec<String> {
+    lazy_static::lazy_static! {
+        static ref KOTLIN_IMPORT: regex::Regex =
+            regex::Regex::new(r"^\s*import\s+([\w`]+(?:\.[\w`]+)*(?:\.\*)?)").unwrap();
+    }
+
+    let mut imports: Vec<String> = content
+        .lines()
+        .filter_map(|line| KOTLIN_IMPORT.captures(line))
+        .filter_map(|caps| caps.get(1))
+        .map(|m| m.as_str().replace('`', ""))
+        .collect();
+    imports.sort();
+    imports.dedup();
+    imports
+}
+
+/// Expands a captured Python module name into importable dotted paths.
+///
+/// `from pkg import a, b as c` yields `pkg`, `pkg.a` and `pkg.b` so that submodules
+/// imported by name get their own edge; `from . import x` yields `.` and `.x`.
+pub(crate) fn python_import_paths(
+    node: tree_sitter::Node,
+    text: &str,
+    content: &str,
+) -> Vec<String> {
+    let module = text.split(" as ").next().unwrap_or(text).trim().to_string();
+
+    let Some(statement) = node
+        .parent()
+        .filter(|p| p.kind() == "import_from_statement")
+    else {
+        return vec![module];
+    };
+
+    let mut paths = vec![module.clone()];
+    let mut cursor = statement.walk();
+    for name in statement.children_by_field_name("name", &mut cursor) {
+        let name = if name.kind() == "aliased_import" {
+            name.child_by_field_name("name").unwrap_or(name)
+        } else {
+            name
+        };
+        if let Ok(name) = name.utf8_text(content.as_bytes()) {
+            if module.ends_with('.') {
+                paths.push(format!("{}{}", module, name));
+            } else {
+                paths.push(format!("{}.{}", module, name));
+            }
+        }
+    }
+    paths
+}
+
+/// Expands a Rust use-tree such as `crate::{a, b::{self, C}}` into flat paths.
+pub fn expand_use_tree(tree: &str) -> Vec<String> {
+    let normalized = tree
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace(" ::", "::")
+        .replace(":: ", "::");
+    let mut paths = Vec::new();
+    expand_use_tree_into("", normalized.trim().trim_start_matches("::"), &mut paths);
+    paths
+}
+
+fn expand_use_tree_into(prefix: &str, tree: &str, out: &mut Vec<String>) {
+    let join = |tail: &str| match (prefix.is_empty(), tail.is_empty()) {
+        (true, _) => tail.to_string(),
+        (false, true) => prefix.to_string(),
+        (false, false) => format!("{}::{}", prefix, tail),
+    };
+
+    if let Some(open) = tree.find('{') {
+        let head = tree[..open].trim().trim_end_matches("::");
+        let close = tree.rfind('}').filter(|&c| c > open).unwrap_or(tree.len());
+        let base = join(head);
+
+        let inner = &tree[open + 1..close];
+        let mut depth = 0;
+        let mut start = 0;
+        for (i, c) in inner.char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                ',' if depth == 0 => {
+                    expand_use_tree_into(&base, inner[start..i].trim(), out);
+                    start = i + 1;
                 }
+                _ => {}
             }
         }
-        "py" => {
-            // from . import foo
-            // import foo.bar
-            let parts: Vec<&str> = import.split('.').collect();
-            if parts.is_empty() {
-                return None;
+        expand_use_tree_into(&base, inner[start..].trim(), out);
+        return;
+    }
+
+    let leaf = tree.split(" as ").next().unwrap_or("").trim();
+    match leaf {
+        "" => {}
+        "self" => {
+            if !prefix.is_empty() {
+                out.push(prefix.to_string());
             }
+        }
+        _ => out.push(join(leaf)),
+    }
+}
+
+/// Rebases a path written `depth` inline modules deep onto the file's own module.
+fn rebase_inline_path(path: &str, depth: usize) -> Option<String> {
+    if depth == 0 {
+        return Some(path.to_string());
+    }
+    if path == "self" || path.starts_with("self::") {
+        return None;
+    }
+
+    let segments: Vec<&str> = path.split("::").collect();
+    let supers = segments.iter().take_while(|s| **s == "super").count();
+    if supers == 0 {
+        return Some(path.to_string());
+    }
+    if supers <= depth {
+        // Refers to the file itself or to another inline module in it
+        return None;
+    }
+
+    let mut rebased = vec!["super"; supers - depth];
+    rebased.extend(&segments[supers..]);
+    Some(rebased.join("::"))
+}
+
+/// Module position of a Rust source file within its crate.
+struct RustModule {
+    /// Directory module paths are resolved against (the crate's `src/`)
+    src_root: PathBuf,
+    /// Module path of the file, e.g. `["utils", "graph"]` for `src/utils/graph.rs`
+    path: Vec<String>,
+    /// Directory holding files for `mod child;` declarations
+    child_dir: PathBuf,
+}
+
+impl RustModule {
+    fn locate(
+        current_file: &Path,
+        repo_root: &Path,
+        layout: &crate::utils::analysis::project::ProjectLayout,
+    ) -> Self {
+        let parent = current_file.parent().unwrap_or(repo_root).to_path_buf();
+
+        let src_root = layout
+            .rust_crate_for(current_file)
+            .map(|c| c.src_root())
+            .or_else(|| {
+                // Crates outside the workspace: nearest manifest up to the repo root
+                current_file
+                    .ancestors()
+                    .skip(1)
+                    .take_while(|dir| dir.starts_with(repo_root))
+                    .find(|dir| dir.join("Cargo.toml").is_file())
+                    .map(|dir| dir.join("src"))
+            })
+            .unwrap_or_else(|| repo_root.join("src"));
+
+        // Files outside `src/` (tests, examples, build scripts) are crate roots of their own.
+        let Ok(relative) = current_file.strip_prefix(&src_root) else {
+            return Self {
+                src_root: parent.clone(),
+                path: Vec::new(),
+                child_dir: parent,
+            };
+        };
+
+        let mut path: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        let file_name = path.pop().unwrap_or_default();
+        let is_crate_root = path.is_empty() && (file_name == "lib.rs" || file_name == "main.rs");
+
+        let child_dir = if file_name == "mod.rs" || is_crate_root {
+            parent
+        } else {
+            let stem = file_name.trim_end_matches(".rs").to_string();
+            let dir = parent.join(&stem);
+            path.push(stem);
+            dir
+        };
+
+        Self {
+            src_root,
+            path,
+            child_dir,
+        }
+    }
 
-            // Heuristic: maps dots to slashes relative to root.
-            let rel_path = import.replace('.', "/");
-            let candidate = repo_root.join(format!("{}.py", rel_path));
-            if candidate.exists() {
+    /// Resolves `anchor::tail` to the longest module prefix that exists as a file.
+    /// Never walks back to the anchor itself: the anchor is an ancestor that already
+    /// declares this module, so an edge to it would only form a cycle.
+    fn resolve(src_root: &Path, anchor: &[String], tail: &[&str]) -> Option<PathBuf> {
+        let full: Vec<&str> = anchor
+            .iter()
+            .map(String::as_str)
+            .chain(tail.iter().copied())
+            .filter(|s| *s != "*")
+            .map(|s| s.trim_start_matches("r#"))
+            .collect();
+
+        for len in (anchor.len() + 1..=full.len()).rev() {
+            let module_dir: PathBuf = full[..len].iter().collect();
+            let candidate = src_root.join(&module_dir).with_extension("rs");
+            if candidate.is_file() {
                 return Some(candidate);
             }
+            let candidate_mod = src_root.join(&module_dir).join("mod.rs");
+            if candidate_mod.is_file() {
+                return Some(candidate_mod);
+            }
+        }
+        None
+    }
+
+    fn child_file(&self, name: &str) -> Option<PathBuf> {
+        let candidate = self.child_dir.join(format!("{}.rs", name));
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        let candidate_mod = self.child_dir.join(name).join("mod.rs");
+        candidate_mod.is_file().then_some(candidate_mod)
+    }
+}
+
+/// Resolves a flattened Rust path (`crate::a::B`, `super::x`, `mod foo`, `other_crate::y`).
+pub(crate) fn resolve_rust_import(
+    import: &str,
+    current_file: &Path,
+    repo_root: &Path,
+) -> Option<PathBuf> {
+    let layout = crate::utils::analysis::project::ProjectLayout::for_root(repo_root);
+    let current_dir = current_file.parent().unwrap_or(repo_root);
+
+    if let Some((kind, path)) = import.split_once(':')
+        && !path.starts_with(':')
+    {
+        // Package directory: build inputs are relative to it
+        let package_dir = || {
+            current_file
+                .ancestors()
+                .skip(1)
+                .take_while(|dir| dir.starts_with(repo_root))
+                .find(|dir| dir.join("Cargo.toml").is_file())
+                .unwrap_or(current_dir)
+        };
+        let target = match kind {
+            "path" | "include" => current_dir.join(path),
+            "build_input" => package_dir().join(path),
+            // Generated into OUT_DIR by the package's build script
+            "out_dir" => layout
+                .rust_crate_for(current_file)
+                .and_then(|krate| krate.build.clone())
+                .unwrap_or_else(|| package_dir().join("build.rs")),
+            _ => return None,
+        };
+        return target.is_file().then_some(target);
+    }
+
+    let module = RustModule::locate(current_file, repo_root, &layout);
+
+    let segments: Vec<&str> = import.split("::").filter(|s| !s.is_empty()).collect();
+    let (first, rest) = segments.split_first()?;
+
+    if rest.is_empty() {
+        // `mod foo;` declaration: child module first, then sibling for flat layouts
+        if let Some(path) = module.child_file(first) {
+            return Some(path);
+        }
+        let candidate = current_dir.join(format!("{}.rs", first));
+        if candidate.is_file() && candidate != current_file {
+            return Some(candidate);
+        }
+        let candidate_mod = current_dir.join(first).join("mod.rs");
+        if candidate_mod.is_file() {
+            return Some(candidate_mod);
+        }
+    }
 
-            let candidate_init = repo_root.join(rel_path).join("__init__.py");
-            if candidate_init.exists() {
-                return Some(candidate_init);
+    match *first {
+        "crate" => RustModule::resolve(&module.src_root, &[], rest),
+        "self" => RustModule::resolve(&module.src_root, &module.path, rest),
+        "super" => {
+            let supers = segments.iter().take_while(|s| **s == "super").count();
+            let anchor = &module.path[..module.path.len().checked_sub(supers)?];
+            RustModule::resolve(&module.src_root, anchor, &segments[supers..])
+        }
+        name => {
+            if let Some(krate) = layout.rust_crate(name) {
+                let resolved = RustModule::resolve(&krate.src_root(), &[], rest);
+                return resolved
+                    .or_else(|| krate.lib.clone())
+                    .filter(|p| p != current_file);
             }
+            // 2018 uniform paths: `use child::Item` relative to the current module
+            RustModule::resolve(&module.src_root, &module.path, &segments)
         }
-        _ => {}
     }
+}
 
-    None
+/// Resolves a Python module path. Relative imports are anchored at the importing file's
+/// package; absolute ones are tried against the file's own top-level package root and
+/// the configured source roots (`src/`, `pyproject.toml`, `setup.cfg`).
+pub(crate) fn resolve_python_import(
+    import: &str,
+    current_file: &Path,
+    repo_root: &Path,
+) -> Option<PathBuf> {
+    let current_dir = current_file.parent().unwrap_or(repo_root);
+    let dots = import.chars().take_while(|c| *c == '.').count();
+    let segments: Vec<&str> = import[dots..]
+        .split('.')
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    let resolved = if dots > 0 {
+        let base = current_dir.ancestors().nth(dots - 1)?;
+        if segments.is_empty() {
+            python_module_file(base, &[])
+        } else {
+            python_longest_module(base, &segments)
+        }
+    } else {
+        // Directory above the outermost package containing this file (or the script's dir)
+        let package_root = current_dir
+            .ancestors()
+            .find(|d| !d.join("__init__.py").is_file())
+            .unwrap_or(current_dir);
+
+        let layout = crate::utils::analysis::project::ProjectLayout::for_root(repo_root);
+        std::iter::once(package_root)
+            .chain(layout.python_roots.iter().map(PathBuf::as_path))
+            .chain(std::iter::once(repo_root))
+            .find_map(|root| python_longest_module(root, &segments))
+    }?;
+
+    // A package's own `__init__.py` is an ancestor of this module; it imports us, not vice versa.
+    let is_ancestor_init = resolved.file_name().is_some_and(|n| n == "__init__.py")
+        && resolved
+            .parent()
+            .is_some_and(|pkg| current_file.starts_with(pkg));
+    (!is_ancestor_init && resolved != current_file).then_some(resolved)
+}
+
+/// Resolves `a.b.c` to the deepest of `a/b/c`, `a/b`, `a` that exists as a module under `root`.
+fn python_longest_module(root: &Path, segments: &[&str]) -> Option<PathBuf> {
+    (1..=segments.len())
+        .rev()
+        .find_map(|len| python_module_file(root, &segments[..len]))
+}
+
+fn python_module_file(root: &Path, segments: &[&str]) -> Option<PathBuf> {
+    let module: PathBuf = root.join(segments.iter().collect::<PathBuf>());
+    if !segments.is_empty() {
+        let candidate = module.with_extension("py");
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+    }
+    let candidate_init = module.join("__init__.py");
+    candidate_init.is_file().then_some(candidate_init)
+}
+
+/// Resolves an `#include` target. Quoted includes search the including file's directory
+/// first; `<...>` includes only the include path (configured dirs and `compile_commands.json`).
+pub(crate) fn resolve_c_include(
+    import: &str,
+    current_file: &Path,
+    repo_root: &Path,
+) -> Option<PathBuf> {
+    let layout = crate::utils::analysis::project::ProjectLayout::for_root(repo_root);
+    let (header, is_system) = match import.strip_prefix('<') {
+        Some(rest) => (rest.trim_end_matches('>'), true),
+        None => (import, false),
+    };
+
+    let local = current_file
+        .parent()
+        .filter(|_| !is_system)
+        .map(Path::to_path_buf);
+    let root = (!is_system).then(|| repo_root.to_path_buf());
+
+    local
+        .into_iter()
+        .chain(layout.include_dirs.iter().cloned())
+        .chain(root)
+        .map(|dir| dir.join(header))
+        .find(|candidate| candidate.is_file() && candidate != current_file)
+}
+
+/// Source roots a JVM file can import from: its own root (the `java`/`kotlin` directory
+/// above its package path), then every module root from the Maven/Gradle layout.
+fn jvm_source_roots(current_file: &Path, repo_root: &Path) -> Vec<PathBuf> {
+    let own = current_file
+        .ancestors()
+        .skip(1)
+        .take_while(|d| d.starts_with(repo_root))
+        .find(|d| d.file_name().is_some_and(|n| n == "java" || n == "kotlin"))
+        .map(Path::to_path_buf);
+
+    let layout = crate::utils::analysis::project::ProjectLayout::for_root(repo_root);
+    let mut roots: Vec<PathBuf> = own.into_iter().collect();
+    for root in &layout.jvm_source_roots {
+        if !roots.contains(root) {
+            roots.push(root.clone());
+        }
+    }
+    roots
+}
+
+/// Resolves `a.b.C` (or `a.b.C.member` for static and nested imports) to `a/b/C.java` or `.kt`.
+fn resolve_jvm_import(import: &str, current_file: &Path, repo_root: &Path) -> Option<PathBuf> {
+    let segments: Vec<&str> = import.split('.').collect();
+    jvm_source_roots(current_file, repo_root)
+        .iter()
+        .find_map(|root| {
+            (1..=segments.len()).rev().find_map(|len| {
+                let base = root.join(segments[..len].iter().collect::<PathBuf>());
+                ["java", "kt"]
+                    .iter()
+                    .map(|ext| base.with_extension(ext))
+                    .find(|p| p.is_file())
+            })
+        })
+        .filter(|p| p != current_file)
+}
+
+/// Resolves a Java/Kotlin import: a single class file, or every file of a `.*` package.
+pub(crate) fn resolve_jvm_targets(
+    import: &str,
+    current_file: &Path,
+    repo_root: &Path,
+) -> Vec<PathBuf> {
+    if import.ends_with(".*") {
+        resolve_jvm_wildcard(import, current_file, repo_root)
+    } else {
+        resolve_jvm_import(import, current_file, repo_root)
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Resolves `a.b.*` to every source file of package `a.b`.
+fn resolve_jvm_wildcard(import: &str, current_file: &Path, repo_root: &Path) -> Vec<PathBuf> {
+    let package: PathBuf = import.trim_end_matches(".*").split('.').collect();
+    jvm_source_roots(current_file, repo_root)
+        .iter()
+        .filter_map(|root| std::fs::read_dir(root.join(&package)).ok())
+        .flat_map(|entries| {
+            let mut files: Vec<PathBuf> = entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.is_file()
+                        && p.extension().is_some_and(|e| e == "java" || e == "kt")
+                        && p != current_file
+                })
+                .collect();
+            files.sort();
+            files
+        })
+        .collect()
+}
+
+/// Maps a Go import path to the sources of its package via `go.mod`/`go.work` module paths.
+pub(crate) fn resolve_go_import(
+    import: &str,
+    current_file: &Path,
+    repo_root: &Path,
+) -> Vec<PathBuf> {
+    let layout = crate::utils::analysis::project::ProjectLayout::for_root(repo_root);
+    let Some(module) = layout.go_module_for(import, current_file) else {
+        return Vec::new();
+    };
+    let subpath = import[module.path.len()..].trim_start_matches('/');
+    let package_dir = module.dir.join(subpath);
+
+    let Ok(entries) = std::fs::read_dir(&package_dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.is_file()
+                && p.extension().is_some_and(|e| e == "go")
+                && !p.to_string_lossy().ends_with("_test.go")
+                && p != current_file
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Resolves a JS/TS specifier: relative paths, tsconfig `paths`/`baseUrl` aliases,
+/// then workspace packages via their `exports`/`main` fields.
+pub(crate) fn resolve_js_import(
+    import: &str,
+    current_file: &Path,
+    repo_root: &Path,
+) -> Option<PathBuf> {
+    let current_dir = current_file.parent().unwrap_or(repo_root);
+    if import.starts_with('.') {
+        return resolve_js_file(&current_dir.join(import));
+    }
+
+    let layout = crate::utils::analysis::project::ProjectLayout::for_root(repo_root);
+
+    if let Some(config) = layout.ts_config_for(current_file) {
+        // Most specific pattern wins (longest prefix before `*`)
+        let mut matches: Vec<(usize, &str, &[String])> = config
+            .paths
+            .iter()
+            .filter_map(|(pattern, targets)| {
+                let captured = match pattern.split_once('*') {
+                    Some((prefix, suffix)) => import
+                        .strip_prefix(prefix)?
+                        .strip_suffix(suffix)
+                        .filter(|_| import.len() >= prefix.len() + suffix.len())?,
+                    None if pattern == import => "",
+                    None => return None,
+                };
+                Some((pattern.len(), captured, targets.as_slice()))
+            })
+            .collect();
+        matches.sort_by_key(|m| std::cmp::Reverse(m.0));
+
+        for (_, captured, targets) in matches {
+            for target in targets {
+                let substituted = target.replacen('*', captured, 1);
+                if let Some(found) = resolve_js_file(&config.paths_base().join(substituted)) {
+                    return Some(found);
+                }
+            }
+        }
+
+        if let Some(found) = config
+            .base_url
+            .as_ref()
+            .and_then(|base| resolve_js_file(&base.join(import)))
+        {
+            return Some(found);
+        }
+    }
+
+    // Bare specifier: `@scope/pkg/sub/path` or `pkg/sub/path`
+    let mut parts = import.splitn(if import.starts_with('@') { 3 } else { 2 }, '/');
+    let name = if import.starts_with('@') {
+        format!("{}/{}", parts.next()?, parts.next()?)
+    } else {
+        parts.next()?.to_string()
+    };
+    let subpath = parts.next();
+    let package = layout.js_package(&name)?;
+    resolve_package_entry(package, subpath)
+}
+
+/// Finds the source file for a JS/TS module path, trying extensions and index files.
+fn resolve_js_file(base: &Path) -> Option<PathBuf> {
+    const EXTENSIONS: [&str; 7] = ["ts", "tsx", "d.ts", "js", "jsx", "mjs", "cjs"];
+
+    if base.is_file() {
+        return Some(base.to_path_buf());
+    }
+    let with_ext = |path: &Path, ext: &str| PathBuf::from(format!("{}.{}", path.display(), ext));
+
+    for ext in EXTENSIONS {
+        let candidate = with_ext(base, ext);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+    }
+    // ESM-style TypeScript imports name the emitted `.js` file
+    if matches!(
+        base.extension().and_then(|e| e.to_str()),
+        Some("js" | "jsx" | "mjs" | "cjs")
+    ) {
+        let stem = base.with_extension("");
+        for ext in ["ts", "tsx", "mts", "cts"] {
+            let candidate = with_ext(&stem, ext);
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+    }
+    // Index files
+    EXTENSIONS
+        .iter()
+        .map(|ext| base.join(format!("index.{}", ext)))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Resolves `package` or `package/subpath` through `exports`, then `source`/`module`/`main`.
+fn resolve_package_entry(
+    package: &crate::utils::analysis::project::JsPackage,
+    subpath: Option<&str>,
+) -> Option<PathBuf> {
+    let key = subpath.map_or(".".to_string(), |s| format!("./{}", s));
+
+    if let Some(exports) = package.manifest.get("exports") {
+        let targets = match exports {
+            serde_json::Value::Object(map) if map.keys().any(|k| k.starts_with('.')) => {
+                match map.get(&key) {
+                    Some(target) => export_targets(target, ""),
+                    // Subpath patterns: "./utils/*": "./src/utils/*.ts"
+                    None => map
+                        .iter()
+                        .filter_map(|(pattern, target)| {
+                            let (prefix, suffix) = pattern.split_once('*')?;
+                            let captured = key.strip_prefix(prefix)?.strip_suffix(suffix)?;
+                            Some(export_targets(target, captured))
+                        })
+                        .flatten()
+                        .collect(),
+                }
+            }
+            _ if key == "." => export_targets(exports, ""),
+            _ => Vec::new(),
+        };
+
+        if let Some(found) = targets
+            .iter()
+            .find_map(|t| resolve_package_target(&package.dir, t))
+        {
+            return Some(found);
+        }
+    }
+
+    match subpath {
+        None => ["source", "module", "main", "types"]
+            .iter()
+            .filter_map(|field| package.manifest.get(*field)?.as_str())
+            .find_map(|t| resolve_package_target(&package.dir, t))
+            .or_else(|| resolve_js_file(&package.dir.join("src").join("index")))
+            .or_else(|| resolve_js_file(&package.dir.join("index"))),
+        Some(sub) => resolve_js_file(&package.dir.join(sub))
+            .or_else(|| resolve_js_file(&package.dir.join("src").join(sub))),
+    }
+}
+
+/// Flattens an `exports` target (string, array or condition map) into candidate paths.
+fn export_targets(target: &serde_json::Value, captured: &str) -> Vec<String> {
+    // Prefer conditions that point at sources over built artifacts
+    const CONDITIONS: [&str; 9] = [
+        "source",
+        "development",
+        "import",
+        "module",
+        "default",
+        "require",
+        "node",
+        "browser",
+        "types",
+    ];
+
+    match target {
+        serde_json::Value::String(s) => vec![s.replace('*', captured)],
+        serde_json::Value::Array(items) => items
+            .iter()
+            .flat_map(|t| export_targets(t, captured))
+            .collect(),
+        serde_json::Value::Object(conditions) => {
+            let known = CONDITIONS.iter().filter_map(|c| conditions.get(*c));
+            let others = conditions
+                .iter()
+                .filter(|(k, _)| !CONDITIONS.contains(&k.as_str()))
+                .map(|(_, v)| v);
+            known
+                .chain(others)
+                .flat_map(|t| export_targets(t, captured))
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Maps a package target to a file, falling back from build output (`dist/`, `lib/`) to `src/`.
+fn resolve_package_target(dir: &Path, target: &str) -> Option<PathBuf> {
+    let relative = target.trim_start_matches("./");
+    if let Some(found) = resolve_js_file(&dir.join(relative)) {
+        return Some(found);
+    }
+
+    let (first, rest) = relative.split_once('/')?;
+    if !matches!(first, "dist" | "lib" | "build" | "out" | "esm" | "cjs") {
+        return None;
+    }
+    let stem = rest
+        .trim_end_matches(".d.ts")
+        .trim_end_matches(".js")
+        .trim_end_matches(".mjs")
+        .trim_end_matches(".cjs");
+    resolve_js_file(&dir.join("src").join(stem))
+}
+
+/// Resolves an import to every file it makes available.
+///
+/// Most languages import a single file, but a Go import names a package directory, so
+/// the importing file depends on each of the package's non-test sources.
+pub fn resolve_import_targets(import: &str, current_file: &Path, repo_root: &Path) -> Vec<PathBuf> {
+    let extension = current_file
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("");
+    let Some(resolver) = crate::utils::language::for_extension(extension).and_then(|l| l.resolver)
+    else {
+        return Vec::new();
+    };
+    resolver(import, current_file, repo_root)
+        .iter()
+        .map(|p| crate::utils::analysis::project::normalize_path(p))
+        .collect()
+}
+
+/// Heuristic resolution of import strings to repository paths.
+pub fn resolve_import(import: &str, current_file: &Path, repo_root: &Path) -> Option<PathBuf> {
+    resolve_import_targets(import, current_file, repo_root)
+        .into_iter()
+        .next()
 }
 
 /// Sorts paths topologically based on dependencies.
@@ -206,11 +983,10 @@ where
 
     for path in paths {
         if let Ok(content) = std::fs::read_to_string(path) {
-            let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("");
-            let imports = extract_imports(&content, extension);
+            let imports = file_imports(path, &content);
 
             for import in imports {
-                if let Some(dep_path) = resolve_import(&import, path, repo_root) {
+                for dep_path in resolve_import_targets(&import, path, repo_root) {
                     // Resolve if the dependency is within the scanned set.
                     // Performs a strict matching check on canonical paths.
                     if let Ok(canon_dep) = dep_path.canonicalize() {
@@ -263,14 +1039,17 @@ pub fn build_dependency_graph(
     for path in paths {
         graph.add_node(path.clone());
         if let Ok(content) = std::fs::read_to_string(path) {
-            let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("");
-            let imports = extract_imports(&content, extension);
+            let imports = file_imports(path, &content);
 
             for import in imports {
-                if let Some(dep_path) = resolve_import(&import, path, repo_root) {
+                for dep_path in resolve_import_targets(&import, path, repo_root) {
                     // Add edges for internal dependencies to provide architectural context.
                     // resolve_import verifies existence of target on disk.
-                    graph.add_edge(path.clone(), dep_path);
+                    if is_inline_include(&import) {
+                        graph.attach(path.clone(), dep_path);
+                    } else {
+                        graph.add_edge(path.clone(), dep_path);
+                    }
                 }
             }
         }
@@ -295,6 +1074,275 @@ mod tests {
         assert!(imports.contains(&"bar".to_string()));
     }
 
+    #[test]
+    fn test_extract_rust_use_trees() {
+        let code = r#"
+            use crate::{config::AbyssConfig, utils::{self, graph::DependencyGraph as Graph}};
+            mod inline {
+                use super::helpers;
+                use super::super::outer;
+                use self::private::Thing;
+            }
+            mod tests {
+                use super::*;
+            }
+        "#;
+        let imports = extract_imports(code, "rs");
+        assert!(imports.contains(&"crate::config::AbyssConfig".to_string()));
+        assert!(imports.contains(&"crate::utils".to_string()));
+        assert!(imports.contains(&"crate::utils::graph::DependencyGraph".to_string()));
+        assert!(imports.contains(&"super::outer".to_string()));
+        // Inline-module paths that stay inside the file are dropped
+        assert!(!imports.iter().any(|i| i.contains("helpers")));
+        assert!(!imports.iter().any(|i| i.contains("Thing")));
+        // Inline modules are not module declarations
+        assert!(!imports.contains(&"tests".to_string()));
+    }
+
+    #[test]
+    fn test_resolve_rust_module_paths() -> anyhow::Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("src/utils/analysis"))?;
+        std::fs::write(root.join("Cargo.toml"), "[package]\nname = \"app\"\n")?;
+        for file in [
+            "src/lib.rs",
+            "src/config.rs",
+            "src/utils/mod.rs",
+            "src/utils/tokens.rs",
+            "src/utils/analysis/mod.rs",
+            "src/utils/analysis/graph.rs",
+            "src/utils/analysis/rank.rs",
+        ] {
+            std::fs::write(root.join(file), "")?;
+        }
+        let rank = root.join("src/utils/analysis/rank.rs");
+
+        // Walks back from the item to the module file
+        assert_eq!(
+            resolve_import("crate::config::AbyssConfig", &rank, root),
+            Some(root.join("src/config.rs"))
+        );
+        assert_eq!(
+            resolve_import("super::graph::DependencyGraph", &rank, root),
+            Some(root.join("src/utils/analysis/graph.rs"))
+        );
+        assert_eq!(
+            resolve_import("super::super::tokens::count", &rank, root),
+            Some(root.join("src/utils/tokens.rs"))
+        );
+        // Items of an ancestor module don't create back-edges
+        assert_eq!(resolve_import("super::Helper", &rank, root), None);
+        assert_eq!(resolve_import("crate::AbyssConfig", &rank, root), None);
+        // `mod` declarations in mod.rs and in non-mod.rs files
+        assert_eq!(
+            resolve_import("graph", &root.join("src/utils/analysis/mod.rs"), root),
+            Some(root.join("src/utils/analysis/graph.rs"))
+        );
+        assert_eq!(
+            resolve_import("analysis", &root.join("src/utils/mod.rs"), root),
+            Some(root.join("src/utils/analysis/mod.rs"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_rust_file_includes() -> anyhow::Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let root = temp.path();
+        let write = |rel: &str, content: &str| -> anyhow::Result<()> {
+            let path = root.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(path, content)?;
+            Ok(())
+        };
+        write("Cargo.toml", "[package]\nname = \"app\"\n")?;
+        write(
+            "build.rs",
+            "fn main() {\n    println!(\"cargo:rerun-if-changed=proto/common.proto\");\n    tonic_build::compile_protos(\"proto/api.proto\").unwrap();\n}\n",
+        )?;
+        write("proto/api.proto", "syntax = \"proto3\";\n")?;
+        write("proto/common.proto", "syntax = \"proto3\";\n")?;
+        write("queries/users.sql", "SELECT * FROM users;\n")?;
+        write("src/sys/linux.rs", "pub fn id() {}\n")?;
+        let lib = r#"
+#[cfg(target_os = "linux")]
+#[path = "sys/linux.rs"]
+mod platform;
+
+const USERS: &str = include_str!("../queries/users.sql");
+
+pub mod api {
+    include!(concat!(env!("OUT_DIR"), "/api.rs"));
+}
+
+pub fn main() {
+    println!("not a build input");
+}
+"#;
+        write("src/lib.rs", lib)?;
+
+        let imports = extract_imports(lib, "rs");
+        assert_eq!(
+            imports,
+            vec![
+                "include:../queries/users.sql",
+                "out_dir:api.rs",
+                "path:sys/linux.rs",
+            ]
+        );
+        let lib_path = root.join("src/lib.rs");
+        let build = std::fs::read_to_string(root.join("build.rs"))?;
+        assert_eq!(
+            extract_imports(&build, "rs"),
+            vec!["build_input:proto/api.proto"]
+        );
+        assert_eq!(
+            build_script_inputs(&root.join("build.rs"), &build),
+            vec!["build_input:proto/common.proto"]
+        );
+        // Outside a build script the directive is just a printed string
+        assert!(build_script_inputs(&lib_path, &build).is_empty());
+
+        let files = vec![
+            root.join("build.rs"),
+            root.join("proto/api.proto"),
+            root.join("proto/common.proto"),
+            root.join("queries/users.sql"),
+            root.join("src/sys/linux.rs"),
+            lib_path.clone(),
+        ];
+        let graph = build_dependency_graph(&files, root);
+        let edges = graph.get_edges();
+        assert!(edges[&lib_path].contains(&root.join("src/sys/linux.rs")));
+        assert!(edges[&lib_path].contains(&root.join("queries/users.sql")));
+        assert!(edges[&lib_path].contains(&root.join("build.rs")));
+        assert!(edges[&root.join("build.rs")].contains(&root.join("proto/api.proto")));
+        assert!(edges[&root.join("build.rs")].contains(&root.join("proto/common.proto")));
+
+        // The SQL file is placed right before the file that includes it
+        let order = graph.sort_topologically(|a, b| a.cmp(b));
+        let position = |p: &Path| order.iter().position(|o| o == p).unwrap();
+        assert_eq!(
+            position(&root.join("queries/users.sql")) + 1,
+            position(&lib_path)
+        );
+        assert!(position(&root.join("proto/api.proto")) < position(&root.join("build.rs")));
+        assert!(position(&root.join("build.rs")) < position(&lib_path));
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_workspace_crates() -> anyhow::Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let root = temp.path();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"server\", \"shared-types\"]\n",
+        )?;
+        std::fs::create_dir_all(root.join("server/src"))?;
+        std::fs::create_dir_all(root.join("shared-types/src/model"))?;
+        std::fs::write(
+            root.join("server/Cargo.toml"),
+            "[package]\nname = \"server\"\n",
+        )?;
+        std::fs::write(
+            root.join("shared-types/Cargo.toml"),
+            "[package]\nname = \"shared-types\"\n",
+        )?;
+        std::fs::write(root.join("server/src/main.rs"), "")?;
+        std::fs::write(root.join("server/src/handler.rs"), "")?;
+        std::fs::write(root.join("shared-types/src/lib.rs"), "")?;
+        std::fs::write(root.join("shared-types/src/model/user.rs"), "")?;
+
+        let handler = root.join("server/src/handler.rs");
+        assert_eq!(
+            resolve_import("shared_types::model::user::User", &handler, root),
+            Some(root.join("shared-types/src/model/user.rs"))
+        );
+        assert_eq!(
+            resolve_import("shared_types::Id", &handler, root),
+            Some(root.join("shared-types/src/lib.rs"))
+        );
+        // `crate::` resolves within the member crate, not the repo root
+        std::fs::write(root.join("server/src/routes.rs"), "")?;
+        assert_eq!(
+            resolve_import("crate::routes::index", &handler, root),
+            Some(root.join("server/src/routes.rs"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_ts_reexports() {
+        let code = r#"
+            import { a } from "./a";
+            export { b } from "@acme/ui";
+            export * from './c';
+        "#;
+        let imports = extract_imports(code, "ts");
+        assert_eq!(imports, vec!["./a", "./c", "@acme/ui"]);
+    }
+
+    #[test]
+    fn test_resolve_ts_aliases_and_packages() -> anyhow::Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let root = temp.path();
+        let write = |rel: &str, content: &str| -> anyhow::Result<()> {
+            let path = root.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(path, content)?;
+            Ok(())
+        };
+        write("pnpm-workspace.yaml", "packages:\n  - 'packages/*'\n")?;
+        write(
+            "tsconfig.base.json",
+            r#"{ "compilerOptions": { "paths": { "@app/*": ["apps/web/src/*"] } } }"#,
+        )?;
+        write(
+            "apps/web/tsconfig.json",
+            r#"{ "extends": "../../tsconfig.base.json" }"#,
+        )?;
+        write("apps/web/src/main.ts", "")?;
+        write("apps/web/src/store/user.service.ts", "")?;
+        write(
+            "packages/ui/package.json",
+            r#"{ "name": "@acme/ui", "exports": { ".": { "types": "./dist/index.d.ts", "import": "./dist/index.js" }, "./icons/*": "./src/icons/*.tsx" } }"#,
+        )?;
+        write("packages/ui/src/index.ts", "")?;
+        write("packages/ui/src/icons/Close.tsx", "")?;
+        write(
+            "packages/utils/package.json",
+            r#"{ "name": "utils", "main": "lib/index.js" }"#,
+        )?;
+        write("packages/utils/lib/index.js", "")?;
+
+        let main = root.join("apps/web/src/main.ts");
+        assert_eq!(
+            resolve_import("@app/store/user.service", &main, root),
+            Some(root.join("apps/web/src/store/user.service.ts"))
+        );
+        assert_eq!(
+            resolve_import("./store/user.service.js", &main, root),
+            Some(root.join("apps/web/src/store/user.service.ts"))
+        );
+        // `exports` pointing at unbuilt `dist/` falls back to `src/`
+        assert_eq!(
+            resolve_import("@acme/ui", &main, root),
+            Some(root.join("packages/ui/src/index.ts"))
+        );
+        assert_eq!(
+            resolve_import("@acme/ui/icons/Close", &main, root),
+            Some(root.join("packages/ui/src/icons/Close.tsx"))
+        );
+        assert_eq!(
+            resolve_import("utils", &main, root),
+            Some(root.join("packages/utils/lib/index.js"))
+        );
+        assert_eq!(resolve_import("react", &main, root), None);
+        Ok(())
+    }
+
     #[test]
     fn test_extract_python_imports() {
         let code = r#"
@@ -304,14 +1352,254 @@ mod tests {
         "#;
         let imports = extract_imports(code, "py");
         assert!(imports.contains(&"os".to_string()));
-        // "utils" is captured from `from utils ...`
-        // We don't capture "helper" because without FS it's ambiguous if it's a file or symbol.
-        // For file dependency graph, depending on "utils" (utils.py or utils/__init__.py) is sufficient
-        // to establish the edge.
+        // "utils" is captured from `from utils ...`, and "utils.helper" as well since
+        // `helper` may be a submodule; resolution decides whether that file exists.
         assert!(imports.contains(&"utils".to_string()));
+        assert!(imports.contains(&"utils.helper".to_string()));
         assert!(imports.contains(&"numpy".to_string()));
     }
 
+    #[test]
+    fn test_extract_jvm_imports() {
+        let java = "package com.acme.api;\n\nimport java.util.List;\nimport static com.acme.core.Ids.next;\nimport com.acme.model.*;\n\nclass Api {}\n";
+        assert_eq!(
+            extract_imports(java, "java"),
+            vec![
+                "com.acme.core.Ids.next",
+                "com.acme.model.*",
+                "java.util.List"
+            ]
+        );
+        let kotlin = "package com.acme\n\nimport com.acme.model.User\nimport com.acme.util.*\nimport kotlinx.coroutines.flow.Flow as F\n";
+        assert_eq!(
+            extract_imports(kotlin, "kt"),
+            vec![
+                "com.acme.model.User",
+                "com.acme.util.*",
+                "kotlinx.coroutines.flow.Flow"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_jvm_modules() -> anyhow::Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let root = temp.path();
+        std::fs::write(
+            root.join("settings.gradle.kts"),
+            "rootProject.name = \"shop\"\ninclude(\":api\", \":core:model\")\n",
+        )?;
+        for rel in [
+            "api/src/main/java/com/acme/api/Api.java",
+            "core/model/src/main/java/com/acme/model/User.java",
+            "core/model/src/main/kotlin/com/acme/model/Order.kt",
+            "core/model/src/main/java/com/acme/model/Ids.java",
+        ] {
+            let path = root.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(path, "")?;
+        }
+
+        let api = root.join("api/src/main/java/com/acme/api/Api.java");
+        assert_eq!(
+            resolve_import("com.acme.model.User", &api, root),
+            Some(root.join("core/model/src/main/java/com/acme/model/User.java"))
+        );
+        // Static member and nested class imports walk back to the declaring file
+        assert_eq!(
+            resolve_import("com.acme.model.Ids.next", &api, root),
+            Some(root.join("core/model/src/main/java/com/acme/model/Ids.java"))
+        );
+        assert_eq!(
+            resolve_import("com.acme.model.Order", &api, root),
+            Some(root.join("core/model/src/main/kotlin/com/acme/model/Order.kt"))
+        );
+        assert_eq!(resolve_import("java.util.List", &api, root), None);
+        assert_eq!(
+            resolve_import_targets("com.acme.model.*", &api, root),
+            vec![
+                root.join("core/model/src/main/java/com/acme/model/Ids.java"),
+                root.join("core/model/src/main/java/com/acme/model/User.java"),
+                root.join("core/model/src/main/kotlin/com/acme/model/Order.kt"),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_c_includes() {
+        let code = "#include <stdio.h>\n#include \"util/list.h\"\nint main() { return 0; }\n";
+        assert_eq!(extract_imports(code, "c"), vec!["<stdio.h>", "util/list.h"]);
+        let code = "#include <vector>\n#include \"engine.hpp\"\n";
+        assert_eq!(extract_imports(code, "cpp"), vec!["<vector>", "engine.hpp"]);
+    }
+
+    #[test]
+    fn test_resolve_c_includes() -> anyhow::Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let root = temp.path();
+        for rel in ["src/engine.cpp", "src/engine.hpp", "include/core/types.h"] {
+            let path = root.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(path, "")?;
+        }
+        std::fs::write(
+            root.join("compile_commands.json"),
+            r#"[{"directory": ".", "command": "c++ -Iinclude -c src/engine.cpp", "file": "src/engine.cpp"}]"#,
+        )?;
+
+        let engine = root.join("src/engine.cpp");
+        assert_eq!(
+            resolve_import("engine.hpp", &engine, root),
+            Some(root.join("src/engine.hpp"))
+        );
+        assert_eq!(
+            resolve_import("core/types.h", &engine, root),
+            Some(root.join("include/core/types.h"))
+        );
+        assert_eq!(
+            resolve_import("<core/types.h>", &engine, root),
+            Some(root.join("include/core/types.h"))
+        );
+        // System headers outside the tree stay unresolved
+        assert_eq!(resolve_import("<vector>", &engine, root), None);
+        // Relative to the repo root as a last resort
+        assert_eq!(
+            resolve_import("src/engine.hpp", &engine, root),
+            Some(root.join("src/engine.hpp"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_go_imports() {
+        let code =
+            "package main\n\nimport (\n\t\"fmt\"\n\tstore \"example.com/svc/internal/store\"\n)\n";
+        assert_eq!(
+            extract_imports(code, "go"),
+            vec!["example.com/svc/internal/store", "fmt"]
+        );
+    }
+
+    #[test]
+    fn test_resolve_go_packages() -> anyhow::Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let root = temp.path();
+        let write = |rel: &str| -> anyhow::Result<()> {
+            let path = root.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(path, "package x\n")?;
+            Ok(())
+        };
+        std::fs::write(root.join("go.mod"), "module example.com/svc\n\ngo 1.22\n")?;
+        write("cmd/api/main.go")?;
+        write("internal/store/store.go")?;
+        write("internal/store/query.go")?;
+        write("internal/store/store_test.go")?;
+
+        let main = root.join("cmd/api/main.go");
+        assert_eq!(
+            resolve_import_targets("example.com/svc/internal/store", &main, root),
+            vec![
+                root.join("internal/store/query.go"),
+                root.join("internal/store/store.go"),
+            ]
+        );
+        assert!(resolve_import_targets("fmt", &main, root).is_empty());
+        assert!(resolve_import_targets("example.com/other/store", &main, root).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_component_imports() -> anyhow::Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let root = temp.path();
+        let write = |rel: &str, content: &str| -> anyhow::Result<()> {
+            let path = root.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(path, content)?;
+            Ok(())
+        };
+        write(
+            "tsconfig.json",
+            r#"{ "compilerOptions": { "paths": { "@/*": ["./src/*"] } } }"#,
+        )?;
+        write(
+            "src/components/UserCard.vue",
+            "<template><div /></template>\n",
+        )?;
+        write("src/store.ts", "export const store = {};\n")?;
+        let page = "<script setup lang=\"ts\">\nimport UserCard from '@/components/UserCard.vue'\nimport { store } from '../store'\n</script>\n";
+        write("src/pages/Home.vue", page)?;
+
+        let home = root.join("src/pages/Home.vue");
+        let targets: Vec<PathBuf> = extract_imports(page, "vue")
+            .iter()
+            .flat_map(|import| resolve_import_targets(import, &home, root))
+            .collect();
+        assert_eq!(
+            targets,
+            vec![
+                root.join("src/store.ts"),
+                root.join("src/components/UserCard.vue"),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_python_relative_imports() {
+        let code = "from . import models\nfrom ..core.db import Session as S, engine\n";
+        let imports = extract_imports(code, "py");
+        assert!(imports.contains(&".".to_string()));
+        assert!(imports.contains(&".models".to_string()));
+        assert!(imports.contains(&"..core.db".to_string()));
+        assert!(imports.contains(&"..core.db.Session".to_string()));
+        assert!(imports.contains(&"..core.db.engine".to_string()));
+    }
+
+    #[test]
+    fn test_resolve_python_src_layout() -> anyhow::Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let root = temp.path();
+        let write = |rel: &str, content: &str| -> anyhow::Result<()> {
+            let path = root.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(path, content)?;
+            Ok(())
+        };
+        write(
+            "pyproject.toml",
+            "[tool.setuptools.packages.find]\nwhere = [\"lib\"]\n",
+        )?;
+        write("lib/shop/__init__.py", "")?;
+        write("lib/shop/models.py", "")?;
+        write("lib/shop/api/__init__.py", "")?;
+        write("lib/shop/api/views.py", "")?;
+        write("tests/test_views.py", "")?;
+
+        let views = root.join("lib/shop/api/views.py");
+        // Relative imports are anchored at the importing package
+        assert_eq!(
+            resolve_import("..models.Order", &views, root),
+            Some(root.join("lib/shop/models.py"))
+        );
+        // The package's own __init__ is not a dependency of its modules
+        assert_eq!(resolve_import(".", &views, root), None);
+        assert_eq!(resolve_import("shop", &views, root), None);
+        // `from shop import models` reaches the submodule file
+        let test = root.join("tests/test_views.py");
+        assert_eq!(
+            resolve_import("shop.models", &test, root),
+            Some(root.join("lib/shop/models.py"))
+        );
+        assert_eq!(
+            resolve_import("shop.api.views.index", &test, root),
+            Some(root.join("lib/shop/api/views.py"))
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_topo_sort_with_files() -> anyhow::Result<()> {
         let temp = std::env::temp_dir().join("abyss_dep_test");
@@ -5,6 +5,8 @@ use std::path::PathBuf;
 pub struct DependencyGraph {
     nodes: HashSet<PathBuf>,
     edges: HashMap<PathBuf, HashSet<PathBuf>>,
+    /// Inlined file -> the file that includes it (`include_str!` and friends)
+    attached: HashMap<PathBuf, PathBuf>,
 }
 
 impl DependencyGraph {
@@ -22,6 +24,15 @@ impl DependencyGraph {
         self.edges.entry(from).or_default().insert(to);
     }
 
+    /// Adds a dependency whose target is inlined into `from`; sorting places `to`
+    /// immediately before its first includer.
+    pub fn attach(&mut self, from: PathBuf, to: PathBuf) {
+        self.add_edge(from.clone(), to.clone());
+        if from != to {
+            self.attached.entry(to).or_insert(from);
+        }
+    }
+
     pub fn get_nodes(&self) -> &HashSet<PathBuf> {
         &self.nodes
     }
@@ -34,6 +45,42 @@ impl DependencyGraph {
         self.nodes.len()
     }
 
+    /// Returns the graph restricted to `paths` and the edges between them.
+    pub fn subgraph(&self, paths: &[PathBuf]) -> DependencyGraph {
+        let keep: HashSet<&PathBuf> = paths.iter().filter(|p| self.nodes.contains(*p)).collect();
+        let mut graph = DependencyGraph::new();
+        for path in &keep {
+            graph.add_node((*path).clone());
+            for to in self.edges.get(*path).into_iter().flatten() {
+                if keep.contains(to) {
+                    graph.add_edge((*path).clone(), to.clone());
+                }
+            }
+            if let Some(host) = self.attached.get(*path)
+                && keep.contains(host)
+            {
+                graph.attached.insert((*path).clone(), host.clone());
+            }
+        }
+        graph
+    }
+
+    /// Everything `path` depends on, directly or transitively (excluding `path`), sorted.
+    pub fn transitive_dependencies(&self, path: &PathBuf) -> Vec<PathBuf> {
+        let mut seen: HashSet<&PathBuf> = HashSet::new();
+        let mut stack = vec![path];
+        while let Some(current) = stack.pop() {
+            for dep in self.edges.get(current).into_iter().flatten() {
+                if dep != path && seen.insert(dep) {
+                    stack.push(dep);
+                }
+            }
+        }
+        let mut deps: Vec<PathBuf> = seen.into_iter().cloned().collect();
+        deps.sort();
+        deps
+    }
+
     pub fn has_node(&self, path: &PathBuf) -> bool {
         self.nodes.contains(path)
     }
@@ -110,57 +157,321 @@ impl DependencyGraph {
         scores
     }
 
+    /// PageRank whose random jumps land only on `seeds` (by weight), so scores measure
+    /// proximity to them rather than global centrality. The walk follows imports in
+    /// both directions: a seed's dependencies explain it and its dependents use it.
+    /// Falls back to `calculate_pagerank` when no seed is in the graph.
+    pub fn personalized_pagerank(&self, seeds: &HashMap<PathBuf, f64>) -> HashMap<PathBuf, f64> {
+        // A short walk: with the usual 0.85, rank bounces off a hub's leaves and
+        // collects on the hub no matter how far it is from the seeds
+        let damping_factor = 0.5;
+        let iterations = 20;
+
+        let total: f64 = seeds
+            .iter()
+            .filter(|(path, weight)| self.nodes.contains(*path) && **weight > 0.0)
+            .map(|(_, weight)| weight)
+            .sum();
+        if total <= 0.0 {
+            return self.calculate_pagerank();
+        }
+        let teleport: HashMap<&PathBuf, f64> = seeds
+            .iter()
+            .filter(|(path, weight)| self.nodes.contains(*path) && **weight > 0.0)
+            .map(|(path, weight)| (path, weight / total))
+            .collect();
+
+        let mut neighbours: HashMap<&PathBuf, HashSet<&PathBuf>> = HashMap::new();
+        for (from, targets) in &self.edges {
+            for to in targets.iter().filter(|to| *to != from) {
+                neighbours.entry(from).or_default().insert(to);
+                neighbours.entry(to).or_default().insert(from);
+            }
+        }
+
+        let mut scores: HashMap<&PathBuf, f64> = teleport.clone();
+        for _ in 0..iterations {
+            // Rank stranded on isolated nodes returns to the seeds
+            let sink_rank: f64 = scores
+                .iter()
+                .filter(|(node, _)| !neighbours.contains_key(*node))
+                .map(|(_, score)| score)
+                .sum();
+
+            let mut new_scores: HashMap<&PathBuf, f64> = HashMap::new();
+            for (node, weight) in &teleport {
+                *new_scores.entry(node).or_default() +=
+                    (1.0 - damping_factor + damping_factor * sink_rank) * weight;
+            }
+            for (node, score) in &scores {
+                let Some(next) = neighbours.get(node) else {
+                    continue;
+                };
+                let share = damping_factor * score / next.len() as f64;
+                for neighbour in next {
+                    *new_scores.entry(neighbour).or_default() += share;
+                }
+            }
+            scores = new_scores;
+        }
+
+        self.nodes
+            .iter()
+            .map(|node| (node.clone(), scores.get(node).copied().unwrap_or(0.0)))
+            .collect()
+    }
+
+    /// Strongly connected components (Tarjan), in reverse topological order: every
+    /// component comes after the components that depend on it. Members are sorted.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<PathBuf>> {
+        let mut nodes: Vec<&PathBuf> = self.nodes.iter().collect();
+        nodes.sort();
+        let index_of: HashMap<&PathBuf, usize> =
+            nodes.iter().enumerate().map(|(i, n)| (*n, i)).collect();
+        let successors: Vec<Vec<usize>> = nodes
+            .iter()
+            .map(|n| {
+                let mut next: Vec<usize> = self
+                    .edges
+                    .get(*n)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|to| index_of.get(to).copied())
+                    .collect();
+                next.sort_unstable();
+                next
+            })
+            .collect();
+
+        // Iterative, so deep import chains cannot overflow the stack
+        const UNVISITED: usize = usize::MAX;
+        let mut index = vec![UNVISITED; nodes.len()];
+        let mut lowlink = vec![0; nodes.len()];
+        let mut on_stack = vec![false; nodes.len()];
+        let mut stack = Vec::new();
+        let mut next_index = 0;
+        let mut components = Vec::new();
+
+        for start in 0..nodes.len() {
+            if index[start] != UNVISITED {
+                continue;
+            }
+            let mut work = vec![(start, 0)];
+            while let Some(&(v, child)) = work.last() {
+                if index[v] == UNVISITED {
+                    index[v] = next_index;
+                    lowlink[v] = next_index;
+                    next_index += 1;
+                    stack.push(v);
+                    on_stack[v] = true;
+                }
+                if let Some(&w) = successors[v].get(child) {
+                    if let Some(top) = work.last_mut() {
+                        top.1 += 1;
+                    }
+                    if index[w] == UNVISITED {
+                        work.push((w, 0));
+                    } else if on_stack[w] {
+                        lowlink[v] = lowlink[v].min(index[w]);
+                    }
+                    continue;
+                }
+
+                work.pop();
+                if let Some(&(parent, _)) = work.last() {
+                    lowlink[parent] = lowlink[parent].min(lowlink[v]);
+                }
+                if lowlink[v] == index[v] {
+                    let mut component = Vec::new();
+                    while let Some(w) = stack.pop() {
+                        on_stack[w] = false;
+                        component.push(nodes[w].clone());
+                        if w == v {
+                            break;
+                        }
+                    }
+                    component.sort();
+                    components.push(component);
+                }
+            }
+        }
+        components
+    }
+
+    /// Groups of files that import each other, largest first.
+    pub fn cycles(&self) -> Vec<Vec<PathBuf>> {
+        let mut cycles: Vec<Vec<PathBuf>> = self
+            .strongly_connected_components()
+            .into_iter()
+            .filter(|c| c.len() > 1)
+            .collect();
+        cycles.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        cycles
+    }
+
     /// Sorts files topologically.
     /// `comparator`: A function to compare two independent items (tie-breaker).
     /// If A depends on B, B comes before A.
+    ///
+    /// Each import cycle is condensed into one unit placed where the cycle as a whole
+    /// belongs; its members are ordered by `comparator`.
     pub fn sort_topologically<F>(&self, mut comparator: F) -> Vec<PathBuf>
     where
         F: FnMut(&PathBuf, &PathBuf) -> std::cmp::Ordering,
     {
-        use topological_sort::TopologicalSort;
-        let mut ts = TopologicalSort::<PathBuf>::new();
-
-        // Seed with all nodes
-        for node in &self.nodes {
-            ts.insert(node.clone());
+        let mut components = self.strongly_connected_components();
+        for component in &mut components {
+            component.sort_by(|a, b| comparator(a, b));
         }
+        let component_of: HashMap<&PathBuf, usize> = components
+            .iter()
+            .enumerate()
+            .flat_map(|(i, c)| c.iter().map(move |p| (p, i)))
+            .collect();
 
-        // Dependency order: A depends on B implies B precedes A.
+        // Dependency order between components: A depends on B implies B precedes A.
+        let mut dependents: Vec<HashSet<usize>> = vec![HashSet::new(); components.len()];
+        let mut pending = vec![0usize; components.len()];
         for (from, targets) in &self.edges {
+            let dependent = component_of[from];
             for to in targets {
-                ts.add_dependency(to.clone(), from.clone());
+                let dependency = component_of[to];
+                if dependency != dependent && dependents[dependency].insert(dependent) {
+                    pending[dependent] += 1;
+                }
             }
         }
 
-        let mut result = Vec::new();
-        while !ts.is_empty() {
-            // Pop all items with no dependencies
-            let mut batch = ts.pop_all();
-            if batch.is_empty() {
-                // Handle cycles or isolated nodes.
-                break;
+        let mut batch: Vec<usize> = (0..components.len()).filter(|&c| pending[c] == 0).collect();
+        let mut result = Vec::with_capacity(self.nodes.len());
+        while !batch.is_empty() {
+            // Components are ranked by their best member
+            batch.sort_by(|&a, &b| comparator(&components[a][0], &components[b][0]));
+
+            let mut next = Vec::new();
+            for &c in &batch {
+                result.extend(components[c].iter().cloned());
+                for &dependent in &dependents[c] {
+                    pending[dependent] -= 1;
+                    if pending[dependent] == 0 {
+                        next.push(dependent);
+                    }
+                }
             }
+            batch = next;
+        }
 
-            // Sort this batch using the importance score
-            batch.sort_by(|a, b| comparator(a, b));
+        self.place_attached(result)
+    }
 
-            result.extend(batch);
+    /// Moves attached files to just before the file that includes them.
+    fn place_attached(&self, order: Vec<PathBuf>) -> Vec<PathBuf> {
+        if self.attached.is_empty() {
+            return order;
         }
 
-        // If items remaining (cycles), append them purely by score
-        if result.len() < self.nodes.len() {
-            // Find missing
-            let included: HashSet<&PathBuf> = result.iter().collect();
-            let mut remaining: Vec<PathBuf> = self
-                .nodes
-                .iter()
-                .filter(|n| !included.contains(n))
-                .cloned()
-                .collect();
-            remaining.sort_by(|a, b| comparator(a, b));
-            result.extend(remaining);
+        let mut guests: HashMap<&PathBuf, Vec<&PathBuf>> = HashMap::new();
+        for path in &order {
+            if let Some(host) = self.attached.get(path) {
+                guests.entry(host).or_default().push(path);
+            }
+        }
+
+        fn emit<'a>(
+            path: &'a PathBuf,
+            guests: &HashMap<&PathBuf, Vec<&'a PathBuf>>,
+            placed: &mut HashSet<&'a PathBuf>,
+            result: &mut Vec<PathBuf>,
+        ) {
+            if !placed.insert(path) {
+                return;
+            }
+            for guest in guests.get(path).into_iter().flatten() {
+                emit(guest, guests, placed, result);
+            }
+            result.push(path.clone());
         }
 
+        let mut placed = HashSet::new();
+        let mut result = Vec::with_capacity(order.len());
+        for path in &order {
+            if !self
+                .attached
+                .get(path)
+                .is_some_and(|h| self.nodes.contains(h))
+            {
+                emit(path, &guests, &mut placed, &mut result);
+            }
+        }
+        // Guests whose host chain loops back on itself keep their position at the end
+        for path in &order {
+            emit(path, &guests, &mut placed, &mut result);
+        }
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_keeps_downstream_order() {
+        let p = |name: &str| PathBuf::from(name);
+        let mut graph = DependencyGraph::new();
+        // core <-> util form a cycle; everything else is layered on top of it
+        graph.add_edge(p("core.rs"), p("util.rs"));
+        graph.add_edge(p("util.rs"), p("core.rs"));
+        graph.add_edge(p("util.rs"), p("base.rs"));
+        graph.add_edge(p("service.rs"), p("core.rs"));
+        graph.add_edge(p("api.rs"), p("service.rs"));
+        graph.add_edge(p("main.rs"), p("api.rs"));
+        graph.add_node(p("readme.md"));
+
+        assert_eq!(graph.cycles(), vec![vec![p("core.rs"), p("util.rs")]]);
+
+        // Reverse alphabetical tie-breaking would put main.rs first if the cycle broke sorting
+        let order = graph.sort_topologically(|a, b| b.cmp(a));
+        let names: Vec<&str> = order.iter().map(|p| p.to_str().unwrap()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "readme.md",
+                "base.rs",
+                "util.rs",
+                "core.rs",
+                "service.rs",
+                "api.rs",
+                "main.rs"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_personalized_pagerank_favours_seed_neighbourhood() {
+        let p = |name: &str| PathBuf::from(name);
+        let mut graph = DependencyGraph::new();
+        // Every handler uses the logger; the billing handler uses the invoice model
+        for handler in ["users.rs", "orders.rs", "search.rs", "billing.rs"] {
+            graph.add_edge(p(handler), p("log.rs"));
+        }
+        graph.add_edge(p("billing.rs"), p("invoice.rs"));
+        graph.add_edge(p("invoice.rs"), p("money.rs"));
+        graph.add_node(p("notes.md"));
+
+        let global = graph.calculate_pagerank();
+        assert!(global[&p("log.rs")] > global[&p("invoice.rs")]);
+
+        let seeds = HashMap::from([(p("money.rs"), 1.0)]);
+        let near = graph.personalized_pagerank(&seeds);
+        assert!(near[&p("invoice.rs")] > near[&p("log.rs")]);
+        assert!(near[&p("billing.rs")] > near[&p("users.rs")]);
+        assert_eq!(near[&p("notes.md")], 0.0);
+        let total: f64 = near.values().sum();
+        assert!((total - 1.0).abs() < 1e-9);
+
+        // Unknown seeds fall back to global centrality
+        let unknown = HashMap::from([(p("missing.rs"), 1.0)]);
+        assert_eq!(graph.personalized_pagerank(&unknown), global);
+    }
+}
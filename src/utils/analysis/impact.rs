@@ -7,6 +7,7 @@ use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::PathBuf;
 
 use crate::utils::graph::DependencyGraph;
+use crate::utils::symbols::SymbolGraph;
 
 /// Impact analysis result for changed files
 #[derive(Debug, Clone)]
@@ -23,6 +24,8 @@ pub struct ImpactAnalysis {
     pub risk_factors: Vec<RiskFactor>,
     /// Suggested tests to run
     pub suggested_tests: Vec<PathBuf>,
+    /// Definitions that reference the changed symbols (symbol analysis only)
+    pub affected_symbols: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -59,6 +62,17 @@ impl std::fmt::Display for ImpactAnalysis {
             self.transitively_affected.len()
         )?;
 
+        if !self.affected_symbols.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "Affected symbols: {}", self.affected_symbols.len())?;
+            for symbol in self.affected_symbols.iter().take(10) {
+                writeln!(f, "  <- {}", symbol)?;
+            }
+            if self.affected_symbols.len() > 10 {
+                writeln!(f, "  ... and {} more", self.affected_symbols.len() - 10)?;
+            }
+        }
+
         writeln!(f)?;
         writeln!(
             f,
@@ -103,12 +117,14 @@ fn risk_level(score: f64) -> &'static str {
 
 /// Impact analyzer for code changes
 pub struct ImpactAnalyzer<'a> {
-    #[allow(dead_code)]
-    graph: &'a DependencyGraph,
+    /// Import edges, plus symbol reference edges once `with_symbols` is applied
+    links: DependencyGraph,
     /// Reverse dependency map (who depends on me?)
     reverse_deps: HashMap<PathBuf, HashSet<PathBuf>>,
     /// PageRank scores for centrality
     pagerank: HashMap<PathBuf, f64>,
+    /// Symbol references, if available
+    symbols: Option<&'a SymbolGraph>,
 }
 
 impl<'a> ImpactAnalyzer<'a> {
@@ -129,12 +145,108 @@ impl<'a> ImpactAnalyzer<'a> {
         let pagerank = graph.calculate_pagerank();
 
         Self {
-            graph,
+            links: graph.clone(),
             reverse_deps,
             pagerank,
+            symbols: None,
         }
     }
 
+    /// Also treats files whose definitions reference a file's symbols as its dependents.
+    pub fn with_symbols(mut self, symbols: &'a SymbolGraph) -> Self {
+        for (from, to) in symbols.file_edges() {
+            self.reverse_deps
+                .entry(to.clone())
+                .or_default()
+                .insert(from.clone());
+            self.links.add_edge(from, to);
+        }
+        self.symbols = Some(symbols);
+        self
+    }
+
+    /// Ranks `files` by proximity to `seeds` (personalized PageRank), closest first.
+    pub fn rank_by_proximity(&self, seeds: &[PathBuf], files: &[PathBuf]) -> Vec<(PathBuf, f64)> {
+        let seeds: HashMap<PathBuf, f64> = seeds.iter().map(|s| (s.clone(), 1.0)).collect();
+        let proximity = self.links.personalized_pagerank(&seeds);
+        let mut ranked: Vec<(PathBuf, f64)> = files
+            .iter()
+            .map(|f| (f.clone(), proximity.get(f).copied().unwrap_or(0.0)))
+            .collect();
+        ranked.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.0.cmp(&b.0))
+        });
+        ranked
+    }
+
+    /// Orders `files` closest to the changed files first.
+    fn closest_first(&self, changed: &[PathBuf], files: HashSet<PathBuf>) -> Vec<PathBuf> {
+        let files: Vec<PathBuf> = files.into_iter().collect();
+        self.rank_by_proximity(changed, &files)
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect()
+    }
+
+    /// Analyze impact of changing one symbol (`name`, `Type::name`); requires `with_symbols`.
+    /// Returns `None` if no definition matches.
+    pub fn analyze_symbol(&self, name: &str, all_files: &[PathBuf]) -> Option<ImpactAnalysis> {
+        let symbols = self.symbols?;
+        let ids = symbols.find(name);
+        if ids.is_empty() {
+            return None;
+        }
+
+        let mut changed_files: Vec<PathBuf> = ids
+            .iter()
+            .map(|&id| symbols.symbol(id).file.clone())
+            .collect();
+        changed_files.dedup();
+        let changed_set: HashSet<PathBuf> = changed_files.iter().cloned().collect();
+
+        let direct: HashSet<usize> = ids.iter().flat_map(|&id| symbols.referrers(id)).collect();
+        let transitive = symbols.transitive_referrers(&ids);
+
+        let mut directly_affected = HashSet::new();
+        let mut transitively_affected = HashSet::new();
+        for &id in &transitive {
+            let file = &symbols.symbol(id).file;
+            if changed_set.contains(file) {
+                continue;
+            }
+            if direct.contains(&id) {
+                directly_affected.insert(file.clone());
+            } else {
+                transitively_affected.insert(file.clone());
+            }
+        }
+        transitively_affected.retain(|f| !directly_affected.contains(f));
+
+        let (risk_score, risk_factors) =
+            self.calculate_risk(&changed_set, &directly_affected, &transitively_affected);
+        let suggested_tests = self.find_relevant_tests(
+            &changed_set,
+            &directly_affected,
+            &transitively_affected,
+            all_files,
+        );
+
+        Some(ImpactAnalysis {
+            directly_affected: self.closest_first(&changed_files, directly_affected),
+            transitively_affected: self.closest_first(&changed_files, transitively_affected),
+            changed_files,
+            risk_score,
+            risk_factors,
+            suggested_tests,
+            affected_symbols: transitive
+                .iter()
+                .map(|&id| symbols.symbol(id).to_string())
+                .collect(),
+        })
+    }
+
     /// Analyze impact of changed files
     pub fn analyze(&self, changed_files: &[PathBuf], all_files: &[PathBuf]) -> ImpactAnalysis {
         let changed_set: HashSet<_> = changed_files.iter().cloned().collect();
@@ -183,11 +295,12 @@ impl<'a> ImpactAnalyzer<'a> {
 
         ImpactAnalysis {
             changed_files: changed_files.to_vec(),
-            directly_affected: directly_affected.into_iter().collect(),
-            transitively_affected: transitively_affected.into_iter().collect(),
+            directly_affected: self.closest_first(changed_files, directly_affected),
+            transitively_affected: self.closest_first(changed_files, transitively_affected),
             risk_score,
             risk_factors,
             suggested_tests,
+            affected_symbols: Vec::new(),
         }
     }
 
@@ -265,7 +378,7 @@ impl<'a> ImpactAnalyzer<'a> {
         (score.min(1.0), factors)
     }
 
-    /// Find test files that are relevant to the changes
+    /// Find test files that are relevant to the changes, closest to them first
     fn find_relevant_tests(
         &self,
         changed: &HashSet<PathBuf>,
@@ -290,7 +403,7 @@ impl<'a> ImpactAnalyzer<'a> {
             .collect();
 
         // Match tests to affected files by name similarity
-        let mut relevant_tests = Vec::new();
+        let mut relevant_tests = HashSet::new();
         for test in test_files {
             let test_name = test
                 .file_stem()
@@ -308,13 +421,14 @@ impl<'a> ImpactAnalyzer<'a> {
                     .to_lowercase();
 
                 if affected_name.contains(&test_name) || test_name.contains(&affected_name) {
-                    relevant_tests.push(test.clone());
+                    relevant_tests.insert(test.clone());
                     break;
                 }
             }
         }
 
-        relevant_tests
+        let changed: Vec<PathBuf> = changed.iter().cloned().collect();
+        self.closest_first(&changed, relevant_tests)
     }
 }
 
@@ -360,12 +474,68 @@ mod tests {
         let all_files = vec![core.clone(), utils.clone(), handler.clone()];
 
         // Change core.rs
-        let analysis = analyzer.analyze(&[core.clone()], &all_files);
+        let analysis = analyzer.analyze(std::slice::from_ref(&core), &all_files);
 
         // utils should be directly affected
         assert!(analysis.directly_affected.contains(&utils));
         // handler should be transitively affected
         assert!(analysis.transitively_affected.contains(&handler));
+
+        // A far-away central file ranks below the change's neighbourhood
+        let log = PathBuf::from("log.rs");
+        for caller in ["a.rs", "b.rs", "c.rs"] {
+            graph.add_edge(PathBuf::from(caller), log.clone());
+        }
+        let analyzer = ImpactAnalyzer::new(&graph);
+        let ranked: Vec<PathBuf> = analyzer
+            .rank_by_proximity(
+                std::slice::from_ref(&core),
+                &[log.clone(), handler.clone(), utils.clone()],
+            )
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        assert_eq!(ranked, vec![utils, handler, log]);
+    }
+
+    #[test]
+    fn test_symbol_impact() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache = dir.path().join("cache.rs");
+        let runner = dir.path().join("runner.rs");
+        let main = dir.path().join("main.rs");
+        std::fs::write(
+            &cache,
+            "pub struct Cache;\nimpl Cache {\n    pub fn update(&self) {}\n    pub fn clear(&self) {}\n}\n",
+        )
+        .unwrap();
+        std::fs::write(&runner, "pub fn run(c: &Cache) {\n    c.update();\n}\n").unwrap();
+        std::fs::write(&main, "fn main() {\n    run(&Cache);\n}\n").unwrap();
+
+        let all_files = vec![cache.clone(), runner.clone(), main.clone()];
+        let mut graph = DependencyGraph::new();
+        for file in &all_files {
+            graph.add_node(file.clone());
+        }
+        let symbols = SymbolGraph::build(&all_files, &graph);
+        let analyzer = ImpactAnalyzer::new(&graph).with_symbols(&symbols);
+
+        // Symbol references stand in for the missing import edges
+        let analysis = analyzer.analyze(std::slice::from_ref(&runner), &all_files);
+        assert_eq!(analysis.directly_affected, vec![main.clone()]);
+
+        let analysis = analyzer
+            .analyze_symbol("Cache::update", &all_files)
+            .unwrap();
+        assert_eq!(analysis.changed_files, vec![cache.clone()]);
+        assert_eq!(analysis.directly_affected, vec![runner.clone()]);
+        assert_eq!(analysis.transitively_affected, vec![main.clone()]);
+        assert_eq!(analysis.affected_symbols.len(), 2);
+        assert!(
+            analyzer
+                .analyze_symbol("Cache::missing", &all_files)
+                .is_none()
+        );
     }
 
     #[test]
@@ -1,18 +1,28 @@
 //! Analysis utilities for code intelligence
 //!
-//! Contains dependency graph, ranking, impact analysis, and query processing.
+//! Contains dependency and symbol graphs, ranking, test pairing, near-duplicate detection, impact analysis, and query processing.
 
 pub mod concepts;
 pub mod dependencies;
 pub mod graph;
 pub mod impact;
+pub mod pairing;
 pub mod preflight;
+pub mod project;
 pub mod quality;
 pub mod query;
 pub mod rank;
+pub mod similarity;
+pub mod source;
+pub mod symbols;
 
 // Re-export commonly used items
 pub use graph::DependencyGraph;
 pub use impact::ImpactAnalyzer;
+pub use project::ProjectLayout;
 pub use query::QueryEngine;
-pub use rank::{FileScore, calculate_entropy, heuristic_score, sort_files, sort_paths};
+pub use rank::{
+    FileScore, RankingProfile, calculate_entropy, heuristic_score, sort_files, sort_paths,
+};
+pub use source::SourceAnalysis;
+pub use symbols::SymbolGraph;
@@ -0,0 +1,226 @@
+//! Test-to-subject pairing
+//!
+//! Finds the file each test exercises, so tests can be placed right after their subject
+//! instead of at the bottom of the output. Import edges are tried first, naming
+//! conventions (`test_cache.py`, `cache_test.go`, `cache.spec.ts`, `CacheTest.java`) second.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::utils::graph::DependencyGraph;
+
+/// Directories whose files are tests
+const TEST_DIRS: &[&str] = &["test", "tests", "__tests__", "spec", "specs"];
+
+/// Files named after their parent directory's module
+const MODULE_FILES: &[&str] = &["mod", "index", "__init__"];
+
+/// Whether `path` (relative to its repository root) is a test file.
+pub fn is_test_file(path: &Path) -> bool {
+    let in_test_dir = path
+        .parent()
+        .into_iter()
+        .flat_map(Path::components)
+        .any(|c| TEST_DIRS.contains(&c.as_os_str().to_string_lossy().to_lowercase().as_str()));
+    in_test_dir || subject_name(path).is_some()
+}
+
+/// Module name of the file a test exercises, if the test is named after it.
+fn subject_name(test: &Path) -> Option<String> {
+    let stem = test.file_stem()?.to_str()?;
+    let lower = stem.to_lowercase();
+    let subject = if let Some(rest) = lower.strip_prefix("test_") {
+        rest
+    } else if let Some(rest) = [".test", ".spec", "_test", "_tests", "_spec"]
+        .iter()
+        .find_map(|suffix| lower.strip_suffix(suffix))
+    {
+        rest
+    } else if let Some(rest) = ["Test", "Tests", "Spec"]
+        .iter()
+        .find_map(|suffix| stem.strip_suffix(suffix))
+        .filter(|rest| rest.ends_with(|c: char| c.is_lowercase() || c.is_ascii_digit()))
+    {
+        // `CacheTest.java`, but not `Contest.java`
+        return Some(rest.to_lowercase());
+    } else {
+        return None;
+    };
+    (!subject.is_empty()).then(|| subject.to_string())
+}
+
+/// Name a test would use for this file: the stem, or the directory for `mod.rs` and friends.
+fn module_name(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_str()?.to_lowercase();
+    if MODULE_FILES.contains(&stem.as_str()) {
+        return Some(path.parent()?.file_name()?.to_str()?.to_lowercase());
+    }
+    Some(stem)
+}
+
+/// Maps each test file to the file it exercises.
+///
+/// A test is paired with a non-test file it imports, preferring one named like the test
+/// when it imports several. Tests that import nothing useful fall back to the nearest
+/// non-test file (by directory) with the matching name. Unpaired tests are left out.
+pub fn pair_tests(
+    files: &[(PathBuf, PathBuf)],
+    graph: &DependencyGraph,
+) -> HashMap<PathBuf, PathBuf> {
+    let tests: HashSet<&PathBuf> = files
+        .iter()
+        .filter(|(path, root)| is_test_file(path.strip_prefix(root).unwrap_or(path)))
+        .map(|(path, _)| path)
+        .collect();
+    let subjects: HashSet<&PathBuf> = files
+        .iter()
+        .map(|(path, _)| path)
+        .filter(|path| !tests.contains(path))
+        .collect();
+    let mut by_name: HashMap<String, Vec<&PathBuf>> = HashMap::new();
+    for subject in &subjects {
+        if let Some(name) = module_name(subject) {
+            by_name.entry(name).or_default().push(subject);
+        }
+    }
+
+    let mut pairs = HashMap::new();
+    for test in &tests {
+        let wanted = subject_name(test);
+        let named = |path: &Path| wanted.is_some() && module_name(path) == wanted;
+
+        let mut imported: Vec<&PathBuf> = graph
+            .get_edges()
+            .get(*test)
+            .into_iter()
+            .flatten()
+            .filter(|dep| subjects.contains(dep))
+            .collect();
+        imported.sort();
+
+        let subject = match imported.iter().find(|path| named(path)) {
+            Some(subject) => Some(*subject),
+            None if imported.len() == 1 => Some(imported[0]),
+            None => wanted
+                .as_ref()
+                .and_then(|name| by_name.get(name))
+                .into_iter()
+                .flatten()
+                .copied()
+                .max_by_key(|path| (shared_prefix(test, path), std::cmp::Reverse(*path))),
+        };
+        if let Some(subject) = subject {
+            pairs.insert((*test).clone(), subject.clone());
+        }
+    }
+    pairs
+}
+
+/// Number of leading path components `a` and `b` have in common
+fn shared_prefix(a: &Path, b: &Path) -> usize {
+    a.components()
+        .zip(b.components())
+        .take_while(|(x, y)| x == y)
+        .count()
+}
+
+/// Moves each paired test to just after its subject (tests of one subject sorted by path).
+pub fn place_tests(order: Vec<PathBuf>, pairs: &HashMap<PathBuf, PathBuf>) -> Vec<PathBuf> {
+    let present: HashSet<&PathBuf> = order.iter().collect();
+    let mut tests_of: HashMap<&PathBuf, Vec<&PathBuf>> = HashMap::new();
+    for (test, subject) in pairs {
+        if present.contains(test) && present.contains(subject) {
+            tests_of.entry(subject).or_default().push(test);
+        }
+    }
+    for tests in tests_of.values_mut() {
+        tests.sort();
+    }
+    let moved: HashSet<&PathBuf> = tests_of.values().flatten().copied().collect();
+
+    let mut result = Vec::with_capacity(order.len());
+    for path in &order {
+        if moved.contains(path) {
+            continue;
+        }
+        result.push(path.clone());
+        for test in tests_of.get(path).into_iter().flatten() {
+            result.push((*test).clone());
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subject_names() {
+        let name = |p: &str| subject_name(Path::new(p));
+        assert_eq!(name("tests/test_cache.py").as_deref(), Some("cache"));
+        assert_eq!(name("pkg/cache_test.go").as_deref(), Some("cache"));
+        assert_eq!(name("src/cache.spec.ts").as_deref(), Some("cache"));
+        assert_eq!(name("src/CacheTest.java").as_deref(), Some("cache"));
+        assert_eq!(name("src/Contest.java"), None);
+        assert_eq!(name("src/cache.rs"), None);
+
+        assert!(is_test_file(Path::new("tests/integration.rs")));
+        assert!(is_test_file(Path::new("pkg/cache_test.go")));
+        assert!(!is_test_file(Path::new("src/testing.rs")));
+        assert!(!is_test_file(Path::new("tests")));
+    }
+
+    #[test]
+    fn test_pair_and_place_tests() {
+        let root = PathBuf::from("/repo");
+        let p = |name: &str| root.join(name);
+        let files: Vec<(PathBuf, PathBuf)> = [
+            "src/cache.rs",
+            "src/config.rs",
+            "src/parser/mod.rs",
+            "src/main.rs",
+            "tests/cache_test.rs",
+            "tests/parser_test.rs",
+            "tests/cli.rs",
+            "tests/test_unknown.py",
+        ]
+        .iter()
+        .map(|f| (p(f), root.clone()))
+        .collect();
+
+        let mut graph = DependencyGraph::new();
+        // Imports win; among several, the one named like the test
+        graph.add_edge(p("tests/cache_test.rs"), p("src/config.rs"));
+        graph.add_edge(p("tests/cache_test.rs"), p("src/cache.rs"));
+        // A single import is the subject, whatever the name
+        graph.add_edge(p("tests/cli.rs"), p("src/main.rs"));
+
+        let pairs = pair_tests(&files, &graph);
+        assert_eq!(pairs[&p("tests/cache_test.rs")], p("src/cache.rs"));
+        assert_eq!(pairs[&p("tests/cli.rs")], p("src/main.rs"));
+        // No imports: matched by name, `mod.rs` standing for its directory
+        assert_eq!(pairs[&p("tests/parser_test.rs")], p("src/parser/mod.rs"));
+        assert!(!pairs.contains_key(&p("tests/test_unknown.py")));
+
+        let order: Vec<PathBuf> = files.iter().map(|(f, _)| f.clone()).collect();
+        let placed = place_tests(order, &pairs);
+        let names: Vec<String> = placed
+            .iter()
+            .map(|f| f.strip_prefix(&root).unwrap().display().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "src/cache.rs",
+                "tests/cache_test.rs",
+                "src/config.rs",
+                "src/parser/mod.rs",
+                "tests/parser_test.rs",
+                "src/main.rs",
+                "tests/cli.rs",
+                "tests/test_unknown.py",
+            ]
+        );
+    }
+}
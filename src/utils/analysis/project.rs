@@ -0,0 +1,969 @@
+//! Project layout discovery for import resolution
+//!
+//! Reads build manifests (`Cargo.toml`, `package.json`, `tsconfig.json`, ...) once per
+//! repository root so that `resolve_import` can map package names and aliases to source files.
+
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+lazy_static! {
+    static ref LAYOUTS: Mutex<HashMap<PathBuf, Arc<ProjectLayout>>> = Mutex::new(HashMap::new());
+    /// User-configured C/C++ include directories (relative ones are joined onto each root)
+    static ref INCLUDE_DIRS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+}
+
+/// A Rust crate declared in the repository (root package or workspace member).
+#[derive(Debug, Clone)]
+pub struct RustCrate {
+    /// Crate name as used in paths (`-` normalized to `_`)
+    pub name: String,
+    /// Directory containing the crate's `Cargo.toml`
+    pub dir: PathBuf,
+    /// Library entry point, if the crate has one
+    pub lib: Option<PathBuf>,
+    /// Build script (`build.rs` or `package.build`), if the crate has one
+    pub build: Option<PathBuf>,
+}
+
+impl RustCrate {
+    /// Directory that module paths are resolved against (usually `src/`).
+    pub fn src_root(&self) -> PathBuf {
+        self.lib
+            .as_ref()
+            .and_then(|l| l.parent())
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| self.dir.join("src"))
+    }
+}
+
+/// A JavaScript/TypeScript workspace package (pnpm, npm or yarn workspaces).
+#[derive(Debug, Clone)]
+pub struct JsPackage {
+    /// Package name from `package.json`, e.g. `@acme/ui`
+    pub name: String,
+    /// Directory containing the `package.json`
+    pub dir: PathBuf,
+    /// Parsed `package.json` (for `exports`, `main`, `module`, ...)
+    pub manifest: serde_json::Value,
+}
+
+/// A Go module root from `go.mod`, a `go.work` `use` directive or a local `replace`.
+#[derive(Debug, Clone)]
+pub struct GoModule {
+    /// Module path, e.g. `example.com/svc`
+    pub path: String,
+    /// Directory holding the module's packages
+    pub dir: PathBuf,
+}
+
+/// Effective `compilerOptions` of a `tsconfig.json` after following `extends`.
+#[derive(Debug, Clone, Default)]
+pub struct TsConfig {
+    /// Absolute `baseUrl`, if any config in the chain sets one
+    pub base_url: Option<PathBuf>,
+    /// `paths` patterns in declaration order
+    pub paths: Vec<(String, Vec<String>)>,
+    /// Directory `paths` targets are relative to when there is no `baseUrl`
+    pub paths_dir: PathBuf,
+}
+
+impl TsConfig {
+    /// Directory that `paths` substitutions are joined onto.
+    pub fn paths_base(&self) -> &Path {
+        self.base_url.as_deref().unwrap_or(&self.paths_dir)
+    }
+}
+
+/// Manifest-derived information about a repository.
+#[derive(Debug, Default)]
+pub struct ProjectLayout {
+    pub root: PathBuf,
+    pub rust_crates: Vec<RustCrate>,
+    pub js_packages: Vec<JsPackage>,
+    /// Directories Python packages are imported from (`src/`, setuptools `where`, ...)
+    pub python_roots: Vec<PathBuf>,
+    /// Modules listed in `go.work`, or the root `go.mod`
+    pub go_modules: Vec<GoModule>,
+    /// `src/{main,test}/{java,kotlin}` directories of the root and its Maven/Gradle modules
+    pub jvm_source_roots: Vec<PathBuf>,
+    /// C/C++ include search path: configured dirs, then `-I` flags from `compile_commands.json`
+    pub include_dirs: Vec<PathBuf>,
+    /// Nearest tsconfig per directory, filled lazily
+    ts_configs: Mutex<HashMap<PathBuf, Option<Arc<TsConfig>>>>,
+    /// Modules from the nearest `go.mod` per directory, filled lazily
+    go_mods: Mutex<HashMap<PathBuf, Arc<Vec<GoModule>>>>,
+}
+
+impl ProjectLayout {
+    /// Returns the (cached) layout for a repository root.
+    pub fn for_root(root: &Path) -> Arc<ProjectLayout> {
+        let mut layouts = LAYOUTS.lock().unwrap_or_else(|e| e.into_inner());
+        layouts
+            .entry(root.to_path_buf())
+            .or_insert_with(|| Arc::new(ProjectLayout::load(root)))
+            .clone()
+    }
+
+    /// Drops all cached layouts so manifests are re-read on the next scan.
+    pub fn clear_cache() {
+        LAYOUTS.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    /// Sets the extra C/C++ include directories used by layouts loaded from now on.
+    pub fn set_include_dirs(dirs: &[PathBuf]) {
+        *INCLUDE_DIRS.lock().unwrap_or_else(|e| e.into_inner()) = dirs.to_vec();
+    }
+
+    /// Reads all manifests under `root`.
+    pub fn load(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            rust_crates: load_rust_crates(root),
+            js_packages: load_js_packages(root),
+            python_roots: load_python_roots(root),
+            go_modules: load_go_modules(root),
+            include_dirs: load_include_dirs(root),
+            jvm_source_roots: load_jvm_source_roots(root),
+            ts_configs: Mutex::new(HashMap::new()),
+            go_mods: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Finds a library crate by its path name (e.g. `my_crate` for `my-crate`).
+    pub fn rust_crate(&self, name: &str) -> Option<&RustCrate> {
+        self.rust_crates
+            .iter()
+            .find(|c| c.name == name && c.lib.is_some())
+    }
+
+    /// Finds the crate whose directory most closely contains `path`.
+    pub fn rust_crate_for(&self, path: &Path) -> Option<&RustCrate> {
+        self.rust_crates
+            .iter()
+            .filter(|c| path.starts_with(&c.dir))
+            .max_by_key(|c| c.dir.components().count())
+    }
+
+    /// Finds a workspace package by name.
+    pub fn js_package(&self, name: &str) -> Option<&JsPackage> {
+        self.js_packages.iter().find(|p| p.name == name)
+    }
+
+    /// Returns the `tsconfig.json` (or `jsconfig.json`) governing `file`.
+    pub fn ts_config_for(&self, file: &Path) -> Option<Arc<TsConfig>> {
+        let dir = file.parent()?;
+        let mut cache = self.ts_configs.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(cached) = cache.get(dir) {
+            return cached.clone();
+        }
+
+        let config = dir
+            .ancestors()
+            .take_while(|d| d.starts_with(&self.root))
+            .flat_map(|d| [d.join("tsconfig.json"), d.join("jsconfig.json")])
+            .find(|p| p.is_file())
+            .and_then(|p| load_ts_config(&p, 0))
+            .map(Arc::new);
+
+        cache.insert(dir.to_path_buf(), config.clone());
+        config
+    }
+
+    /// Finds the Go module providing `import`, as seen from `file`.
+    ///
+    /// Workspace modules are consulted along with the nearest `go.mod`, so nested
+    /// modules resolve even without a `go.work`. The longest matching module path wins.
+    pub fn go_module_for(&self, import: &str, file: &Path) -> Option<GoModule> {
+        let nearest = file.parent().map(|dir| self.nearest_go_mod(dir));
+        self.go_modules
+            .iter()
+            .chain(nearest.iter().flat_map(|m| m.iter()))
+            .filter(|m| {
+                import == m.path
+                    || import
+                        .strip_prefix(&m.path)
+                        .is_some_and(|rest| rest.starts_with('/'))
+            })
+            .max_by_key(|m| m.path.len())
+            .cloned()
+    }
+
+    fn nearest_go_mod(&self, dir: &Path) -> Arc<Vec<GoModule>> {
+        let mut cache = self.go_mods.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(cached) = cache.get(dir) {
+            return cached.clone();
+        }
+
+        let modules = dir
+            .ancestors()
+            .take_while(|d| d.starts_with(&self.root))
+            .find(|d| d.join("go.mod").is_file())
+            .map(load_go_mod)
+            .unwrap_or_default();
+
+        let modules = Arc::new(modules);
+        cache.insert(dir.to_path_buf(), modules.clone());
+        modules
+    }
+}
+
+/// Lexically removes `.` and `..` components so resolved paths match discovered ones.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    use std::path::Component;
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+fn read_toml(path: &Path) -> Option<toml::Table> {
+    std::fs::read_to_string(path)
+        .ok()?
+        .parse::<toml::Table>()
+        .ok()
+}
+
+/// Collects the root package and all workspace members.
+fn load_rust_crates(root: &Path) -> Vec<RustCrate> {
+    let mut crates = Vec::new();
+    let Some(manifest) = read_toml(&root.join("Cargo.toml")) else {
+        return crates;
+    };
+
+    if let Some(krate) = rust_crate_from_manifest(root, &manifest) {
+        crates.push(krate);
+    }
+
+    let Some(workspace) = manifest.get("workspace").and_then(|w| w.as_table()) else {
+        return crates;
+    };
+
+    let patterns = |key: &str| -> Vec<String> {
+        workspace
+            .get(key)
+            .and_then(|v| v.as_array())
+            .map(|a| {
+                a.iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let excluded: Vec<PathBuf> = patterns("exclude").iter().map(|p| root.join(p)).collect();
+
+    for pattern in patterns("members") {
+        let full = root.join(&pattern);
+        let Ok(entries) = glob::glob(&full.to_string_lossy()) else {
+            continue;
+        };
+        for dir in entries.flatten() {
+            if excluded.iter().any(|e| dir.starts_with(e)) || dir == root {
+                continue;
+            }
+            if let Some(krate) =
+                read_toml(&dir.join("Cargo.toml")).and_then(|m| rust_crate_from_manifest(&dir, &m))
+            {
+                crates.push(krate);
+            }
+        }
+    }
+
+    crates
+}
+
+fn rust_crate_from_manifest(dir: &Path, manifest: &toml::Table) -> Option<RustCrate> {
+    let package = manifest.get("package")?.as_table()?;
+    let lib_table = manifest.get("lib").and_then(|l| l.as_table());
+
+    let name = lib_table
+        .and_then(|l| l.get("name"))
+        .or_else(|| package.get("name"))?
+        .as_str()?
+        .replace('-', "_");
+
+    let lib = lib_table
+        .and_then(|l| l.get("path"))
+        .and_then(|p| p.as_str())
+        .map(|p| dir.join(p))
+        .unwrap_or_else(|| dir.join("src").join("lib.rs"));
+
+    let build = match package.get("build") {
+        Some(toml::Value::String(path)) => Some(dir.join(path)),
+        Some(toml::Value::Boolean(false)) => None,
+        _ => Some(dir.join("build.rs")),
+    };
+
+    Some(RustCrate {
+        name,
+        dir: dir.to_path_buf(),
+        lib: lib.is_file().then_some(lib),
+        build: build.filter(|b| b.is_file()),
+    })
+}
+
+/// Parses JSON that may contain comments and trailing commas (tsconfig style).
+pub fn parse_jsonc(content: &str) -> Option<serde_json::Value> {
+    let mut out = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        out.push(escaped);
+                    }
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = ' ';
+                for next in chars.by_ref() {
+                    if prev == '*' && next == '/' {
+                        break;
+                    }
+                    prev = next;
+                }
+            }
+            (',', _) => {
+                // Drop trailing commas before a closing bracket
+                let rest = chars.clone().find(|n| !n.is_whitespace());
+                if !matches!(rest, Some('}') | Some(']')) {
+                    out.push(c);
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+
+    serde_json::from_str(&out).ok()
+}
+
+/// Loads a tsconfig and merges the `extends` chain (children override parents).
+fn load_ts_config(path: &Path, depth: usize) -> Option<TsConfig> {
+    if depth > 10 {
+        return None;
+    }
+    let json = parse_jsonc(&std::fs::read_to_string(path).ok()?)?;
+    let dir = path.parent()?.to_path_buf();
+
+    let parents: Vec<&str> = match json.get("extends") {
+        Some(serde_json::Value::String(s)) => vec![s.as_str()],
+        Some(serde_json::Value::Array(a)) => a.iter().filter_map(|v| v.as_str()).collect(),
+        _ => Vec::new(),
+    };
+
+    let mut config = TsConfig {
+        paths_dir: dir.clone(),
+        ..Default::default()
+    };
+    for parent in parents {
+        if let Some(base) =
+            resolve_ts_extends(&dir, parent).and_then(|p| load_ts_config(&p, depth + 1))
+        {
+            config = base;
+        }
+    }
+
+    let Some(options) = json.get("compilerOptions") else {
+        return Some(config);
+    };
+    if let Some(base_url) = options.get("baseUrl").and_then(|v| v.as_str()) {
+        config.base_url = Some(normalize_path(&dir.join(base_url)));
+    }
+    if let Some(paths) = options.get("paths").and_then(|v| v.as_object()) {
+        config.paths = paths
+            .iter()
+            .map(|(pattern, targets)| {
+                let targets = targets
+                    .as_array()
+                    .map(|a| {
+                        a.iter()
+                            .filter_map(|t| t.as_str().map(String::from))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                (pattern.clone(), targets)
+            })
+            .collect();
+        config.paths_dir = dir;
+    }
+
+    Some(config)
+}
+
+/// Locates the file named by an `extends` entry (relative path or package in `node_modules`).
+fn resolve_ts_extends(dir: &Path, extends: &str) -> Option<PathBuf> {
+    let with_json = |p: PathBuf| -> Option<PathBuf> {
+        if p.is_file() {
+            return Some(p);
+        }
+        let json = PathBuf::from(format!("{}.json", p.display()));
+        if json.is_file() {
+            return Some(json);
+        }
+        let nested = p.join("tsconfig.json");
+        nested.is_file().then_some(nested)
+    };
+
+    if extends.starts_with('.') || Path::new(extends).is_absolute() {
+        return with_json(normalize_path(&dir.join(extends)));
+    }
+    dir.ancestors()
+        .find_map(|d| with_json(d.join("node_modules").join(extends)))
+}
+
+/// Collects packages from `pnpm-workspace.yaml` and `package.json` `workspaces`.
+fn load_js_packages(root: &Path) -> Vec<JsPackage> {
+    let mut patterns: Vec<String> = Vec::new();
+
+    if let Some(pnpm) = std::fs::read_to_string(root.join("pnpm-workspace.yaml"))
+        .ok()
+        .and_then(|c| serde_yaml::from_str::<serde_yaml::Value>(&c).ok())
+        && let Some(packages) = pnpm.get("packages").and_then(|p| p.as_sequence())
+    {
+        patterns.extend(packages.iter().filter_map(|p| p.as_str().map(String::from)));
+    }
+
+    let root_manifest = std::fs::read_to_string(root.join("package.json"))
+        .ok()
+        .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok());
+    if let Some(manifest) = &root_manifest {
+        let workspaces = manifest.get("workspaces");
+        let list = workspaces
+            .and_then(|w| w.as_array())
+            .or_else(|| workspaces.and_then(|w| w.get("packages")?.as_array()));
+        if let Some(list) = list {
+            patterns.extend(list.iter().filter_map(|p| p.as_str().map(String::from)));
+        }
+    }
+
+    let excluded: Vec<glob::Pattern> = patterns
+        .iter()
+        .filter_map(|p| p.strip_prefix('!'))
+        .filter_map(|p| glob::Pattern::new(&root.join(p).to_string_lossy()).ok())
+        .collect();
+
+    let mut packages = Vec::new();
+    let mut push = |dir: &Path, manifest: serde_json::Value| {
+        if let Some(name) = manifest.get("name").and_then(|n| n.as_str()) {
+            packages.push(JsPackage {
+                name: name.to_string(),
+                dir: dir.to_path_buf(),
+                manifest: manifest.clone(),
+            });
+        }
+    };
+
+    if let Some(manifest) = root_manifest {
+        push(root, manifest);
+    }
+
+    for pattern in patterns.iter().filter(|p| !p.starts_with('!')) {
+        let full = root.join(pattern);
+        let Ok(entries) = glob::glob(&full.to_string_lossy()) else {
+            continue;
+        };
+        for dir in entries.flatten() {
+            if dir == root
+                || dir.components().any(|c| c.as_os_str() == "node_modules")
+                || excluded.iter().any(|e| e.matches_path(&dir))
+            {
+                continue;
+            }
+            if let Some(manifest) = std::fs::read_to_string(dir.join("package.json"))
+                .ok()
+                .and_then(|c| serde_json::from_str(&c).ok())
+            {
+                push(&dir, manifest);
+            }
+        }
+    }
+
+    packages
+}
+
+/// Reads `go.work` `use` directives, falling back to the root `go.mod`.
+fn load_go_modules(root: &Path) -> Vec<GoModule> {
+    let Ok(work) = std::fs::read_to_string(root.join("go.work")) else {
+        return load_go_mod(root);
+    };
+
+    let mut modules: Vec<GoModule> = go_directives(&work, "use")
+        .iter()
+        .flat_map(|dir| load_go_mod(&normalize_path(&root.join(dir))))
+        .collect();
+    modules.extend(go_local_replaces(root, &work));
+    modules
+}
+
+/// Parses `dir/go.mod` into its module plus any local `replace` targets.
+fn load_go_mod(dir: &Path) -> Vec<GoModule> {
+    let Ok(content) = std::fs::read_to_string(dir.join("go.mod")) else {
+        return Vec::new();
+    };
+
+    let mut modules: Vec<GoModule> = go_directives(&content, "module")
+        .into_iter()
+        .take(1)
+        .map(|path| GoModule {
+            path,
+            dir: dir.to_path_buf(),
+        })
+        .collect();
+    modules.extend(go_local_replaces(dir, &content));
+    modules
+}
+
+/// `replace example.com/x => ../x` entries that point into the file system.
+fn go_local_replaces(dir: &Path, content: &str) -> Vec<GoModule> {
+    go_directives(content, "replace")
+        .iter()
+        .filter_map(|entry| {
+            let (from, to) = entry.split_once("=>")?;
+            let from = from.split_whitespace().next()?;
+            let to = to.split_whitespace().next()?;
+            let is_local = to.starts_with("./") || to.starts_with("../") || to.starts_with('/');
+            is_local.then(|| GoModule {
+                path: from.to_string(),
+                dir: normalize_path(&dir.join(to)),
+            })
+        })
+        .collect()
+}
+
+/// Collects the arguments of a `go.mod`/`go.work` directive, in single-line or block form.
+fn go_directives(content: &str, keyword: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut in_block = false;
+
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        if in_block {
+            if line == ")" {
+                in_block = false;
+            } else if !line.is_empty() {
+                values.push(line.trim_matches('"').to_string());
+            }
+            continue;
+        }
+
+        let Some(rest) = line.strip_prefix(keyword) else {
+            continue;
+        };
+        let rest = rest.trim();
+        if !line[keyword.len()..].starts_with([' ', '\t', '(']) {
+            continue;
+        }
+        if let Some(inline) = rest.strip_prefix('(') {
+            in_block = !inline.trim_end().ends_with(')');
+            let inline = inline.trim().trim_end_matches(')').trim();
+            if !inline.is_empty() {
+                values.push(inline.trim_matches('"').to_string());
+            }
+        } else if !rest.is_empty() {
+            values.push(rest.trim_matches('"').to_string());
+        }
+    }
+    values
+}
+
+/// Collects JVM source roots for the root project and the modules it declares
+/// (`<module>` in `pom.xml`, `include` in `settings.gradle[.kts]`).
+fn load_jvm_source_roots(root: &Path) -> Vec<PathBuf> {
+    lazy_static! {
+        static ref MAVEN_MODULE: regex::Regex =
+            regex::Regex::new(r"<module>\s*([^<\s]+)\s*</module>").unwrap();
+        static ref GRADLE_INCLUDE: regex::Regex =
+            regex::Regex::new(r#"^\s*include\b(.*)"#).unwrap();
+        static ref QUOTED: regex::Regex = regex::Regex::new(r#"["']([^"']+)["']"#).unwrap();
+    }
+
+    let mut modules = vec![root.to_path_buf()];
+    let mut index = 0;
+    // Maven modules nest; Gradle lists every project in the root settings file
+    while index < modules.len() && index < 256 {
+        let dir = modules[index].clone();
+        index += 1;
+        if let Ok(pom) = std::fs::read_to_string(dir.join("pom.xml")) {
+            for caps in MAVEN_MODULE.captures_iter(&pom) {
+                let module = normalize_path(&dir.join(&caps[1]));
+                if !modules.contains(&module) {
+                    modules.push(module);
+                }
+            }
+        }
+    }
+    for settings in ["settings.gradle", "settings.gradle.kts"] {
+        let Ok(content) = std::fs::read_to_string(root.join(settings)) else {
+            continue;
+        };
+        for line in content.lines() {
+            let Some(args) = GRADLE_INCLUDE.captures(line) else {
+                continue;
+            };
+            for caps in QUOTED.captures_iter(&args[1]) {
+                let project: PathBuf = caps[1].split(':').filter(|s| !s.is_empty()).collect();
+                let module = root.join(project);
+                if !modules.contains(&module) {
+                    modules.push(module);
+                }
+            }
+        }
+    }
+
+    let mut roots = Vec::new();
+    for module in &modules {
+        for set in ["main", "test"] {
+            for language in ["java", "kotlin"] {
+                let dir = module.join("src").join(set).join(language);
+                if dir.is_dir() {
+                    roots.push(dir);
+                }
+            }
+        }
+    }
+    roots
+}
+
+/// Builds the C/C++ include search path for a root.
+fn load_include_dirs(root: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = INCLUDE_DIRS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .map(|d| root.join(d))
+        .collect();
+
+    // CMake and Meson usually write the database into the build directory
+    if let Some(content) = ["compile_commands.json", "build/compile_commands.json"]
+        .iter()
+        .find_map(|p| std::fs::read_to_string(root.join(p)).ok())
+        && let Ok(serde_json::Value::Array(entries)) = serde_json::from_str(&content)
+    {
+        for entry in &entries {
+            let directory = entry
+                .get("directory")
+                .and_then(|d| d.as_str())
+                .map(|d| root.join(d))
+                .unwrap_or_else(|| root.to_path_buf());
+            let args: Vec<String> = match (entry.get("arguments"), entry.get("command")) {
+                (Some(serde_json::Value::Array(a)), _) => a
+                    .iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect(),
+                (_, Some(serde_json::Value::String(c))) => c
+                    .split_whitespace()
+                    .map(|a| a.trim_matches(|c| c == '"' || c == '\'').to_string())
+                    .collect(),
+                _ => continue,
+            };
+            dirs.extend(include_flags(&args).into_iter().map(|d| directory.join(d)));
+        }
+    }
+
+    let mut unique = Vec::new();
+    for dir in dirs.into_iter().map(|d| normalize_path(&d)) {
+        if dir.is_dir() && !unique.contains(&dir) {
+            unique.push(dir);
+        }
+    }
+    unique
+}
+
+/// Extracts include directories from compiler arguments (`-Idir`, `-I dir`, `-isystem dir`, ...).
+fn include_flags(args: &[String]) -> Vec<String> {
+    const FLAGS: [&str; 3] = ["-iquote", "-isystem", "-I"];
+    let mut dirs = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let Some(flag) = FLAGS.iter().find(|f| arg.starts_with(**f)) else {
+            continue;
+        };
+        let value = &arg[flag.len()..];
+        if !value.is_empty() {
+            dirs.push(value.to_string());
+        } else if let Some(next) = iter.next() {
+            dirs.push(next.clone());
+        }
+    }
+    dirs
+}
+
+/// Collects Python source roots from `pyproject.toml`, `setup.cfg` and the `src/` convention.
+fn load_python_roots(root: &Path) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = Vec::new();
+
+    if let Some(pyproject) = read_toml(&root.join("pyproject.toml")) {
+        let tool = pyproject.get("tool");
+        let lookup = |path: &[&str]| -> Option<&toml::Value> {
+            path.iter().try_fold(tool?, |value, key| value.get(*key))
+        };
+        let strings = |value: Option<&toml::Value>| -> Vec<String> {
+            match value {
+                Some(toml::Value::String(s)) => vec![s.clone()],
+                Some(toml::Value::Array(a)) => a
+                    .iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect(),
+                _ => Vec::new(),
+            }
+        };
+
+        // setuptools
+        roots.extend(
+            strings(lookup(&["setuptools", "packages", "find", "where"]))
+                .into_iter()
+                .map(|w| root.join(w)),
+        );
+        if let Some(dir) = lookup(&["setuptools", "package-dir", ""]).and_then(|v| v.as_str()) {
+            roots.push(root.join(dir));
+        }
+        // poetry: packages = [{ include = "pkg", from = "src" }]
+        if let Some(packages) = lookup(&["poetry", "packages"]).and_then(|v| v.as_array()) {
+            roots.extend(
+                packages
+                    .iter()
+                    .filter_map(|p| p.get("from")?.as_str())
+                    .map(|from| root.join(from)),
+            );
+        }
+        // hatch: packages = ["src/pkg"]
+        roots.extend(
+            strings(lookup(&["hatch", "build", "targets", "wheel", "packages"]))
+                .into_iter()
+                .filter_map(|p| root.join(p).parent().map(Path::to_path_buf)),
+        );
+    }
+
+    if let Ok(setup_cfg) = std::fs::read_to_string(root.join("setup.cfg")) {
+        roots.extend(
+            setup_cfg_roots(&setup_cfg)
+                .into_iter()
+                .map(|d| root.join(d)),
+        );
+    }
+
+    let src = root.join("src");
+    if src.is_dir() {
+        roots.push(src);
+    }
+
+    let mut unique = Vec::new();
+    for dir in roots.into_iter().map(|r| normalize_path(&r)) {
+        if dir.is_dir() && !unique.contains(&dir) {
+            unique.push(dir);
+        }
+    }
+    unique
+}
+
+/// Reads `package_dir = =src` and `[options.packages.find] where = src` from setup.cfg.
+fn setup_cfg_roots(content: &str) -> Vec<String> {
+    let mut roots = Vec::new();
+    let mut section = String::new();
+    let mut key = String::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') {
+            continue;
+        }
+        if trimmed.starts_with('[') {
+            section = trimmed.trim_matches(|c| c == '[' || c == ']').to_string();
+            key.clear();
+            continue;
+        }
+
+        // Indented lines continue the previous key's value
+        let value = if line.starts_with(char::is_whitespace) {
+            trimmed
+        } else if let Some((k, v)) = trimmed.split_once('=') {
+            key = k.trim().to_string();
+            v.trim()
+        } else {
+            continue;
+        };
+
+        match (section.as_str(), key.as_str()) {
+            ("options", "package_dir") => {
+                // Entries look like `=src` or ` = src` (empty package name means root)
+                if let Some((pkg, dir)) = value.split_once('=')
+                    && pkg.trim().is_empty()
+                    && !dir.trim().is_empty()
+                {
+                    roots.push(dir.trim().to_string());
+                }
+            }
+            ("options.packages.find", "where") if !value.is_empty() => {
+                roots.push(value.to_string());
+            }
+            _ => {}
+        }
+    }
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_workspace_members() -> anyhow::Result<()> {
+        let temp = TempDir::new()?;
+        let root = temp.path();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\nexclude = [\"crates/skip\"]\n",
+        )?;
+        for name in ["core-types", "skip"] {
+            fs::create_dir_all(root.join("crates").join(name).join("src"))?;
+            fs::write(
+                root.join("crates").join(name).join("Cargo.toml"),
+                format!("[package]\nname = \"{}\"\n", name),
+            )?;
+            fs::write(root.join("crates").join(name).join("src/lib.rs"), "")?;
+        }
+
+        let layout = ProjectLayout::load(root);
+        let krate = layout.rust_crate("core_types").expect("member crate");
+        assert_eq!(krate.src_root(), root.join("crates/core-types/src"));
+        assert!(layout.rust_crate("skip").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ts_config_extends() -> anyhow::Result<()> {
+        let temp = TempDir::new()?;
+        let root = temp.path();
+        fs::write(
+            root.join("tsconfig.base.json"),
+            r#"{
+                // shared options
+                "compilerOptions": {
+                    "baseUrl": ".",
+                    "paths": { "@app/*": ["src/app/*"], },
+                },
+            }"#,
+        )?;
+        fs::create_dir_all(root.join("web"))?;
+        fs::write(
+            root.join("web/tsconfig.json"),
+            r#"{ "extends": "../tsconfig.base", /* no overrides */ "compilerOptions": {} }"#,
+        )?;
+
+        let layout = ProjectLayout::load(root);
+        let config = layout.ts_config_for(&root.join("web/main.ts")).unwrap();
+        assert_eq!(config.paths_base(), root);
+        assert_eq!(config.paths[0].0, "@app/*");
+        assert_eq!(config.paths[0].1, vec!["src/app/*".to_string()]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_go_work_modules() -> anyhow::Result<()> {
+        let temp = TempDir::new()?;
+        let root = temp.path();
+        fs::write(
+            root.join("go.work"),
+            "go 1.22\n\nuse (\n\t./svc // service\n\t./lib\n)\n",
+        )?;
+        fs::create_dir_all(root.join("svc"))?;
+        fs::write(
+            root.join("svc/go.mod"),
+            "module example.com/svc\n\nreplace example.com/proto v1.0.0 => ../proto\n",
+        )?;
+        fs::create_dir_all(root.join("lib"))?;
+        fs::write(root.join("lib/go.mod"), "module \"example.com/lib\"\n")?;
+
+        let layout = ProjectLayout::load(root);
+        let modules: Vec<(&str, PathBuf)> = layout
+            .go_modules
+            .iter()
+            .map(|m| (m.path.as_str(), m.dir.clone()))
+            .collect();
+        assert_eq!(
+            modules,
+            vec![
+                ("example.com/svc", root.join("svc")),
+                ("example.com/proto", root.join("proto")),
+                ("example.com/lib", root.join("lib")),
+            ]
+        );
+
+        let file = root.join("svc/main.go");
+        let found = layout.go_module_for("example.com/lib/store", &file);
+        assert_eq!(found.map(|m| m.dir), Some(root.join("lib")));
+        assert!(layout.go_module_for("example.com/library", &file).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_compile_commands_include_dirs() -> anyhow::Result<()> {
+        let temp = TempDir::new()?;
+        let root = temp.path();
+        fs::create_dir_all(root.join("include"))?;
+        fs::create_dir_all(root.join("third_party/fmt/include"))?;
+        fs::create_dir_all(root.join("build"))?;
+        fs::write(
+            root.join("build/compile_commands.json"),
+            r#"[
+                {"directory": "build", "command": "c++ -I../include -isystem ../third_party/fmt/include -c ../src/a.cpp", "file": "../src/a.cpp"},
+                {"directory": "build", "arguments": ["c++", "-I", "../include", "-I../missing", "-c", "../src/b.cpp"], "file": "../src/b.cpp"}
+            ]"#,
+        )?;
+
+        let layout = ProjectLayout::load(root);
+        assert_eq!(
+            layout.include_dirs,
+            vec![root.join("include"), root.join("third_party/fmt/include")]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_setup_cfg_roots() {
+        let cfg = "[metadata]\nname = x\n\n[options]\npackage_dir =\n    =src\n\n[options.packages.find]\nwhere = lib\n";
+        assert_eq!(setup_cfg_roots(cfg), vec!["src", "lib"]);
+    }
+
+    #[test]
+    fn test_parse_jsonc_keeps_strings() {
+        let value = parse_jsonc(r#"{ "url": "http://x//y", "glob": "a/*", }"#).unwrap();
+        assert_eq!(value["url"], "http://x//y");
+        assert_eq!(value["glob"], "a/*");
+    }
+}
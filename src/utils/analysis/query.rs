@@ -9,6 +9,7 @@ use std::fs;
 use std::path::PathBuf;
 
 use crate::utils::graph::DependencyGraph;
+use crate::utils::symbols::SymbolGraph;
 
 /// Stopwords to filter from queries (common English words)
 const STOPWORDS: &[&str] = &[
@@ -116,19 +117,23 @@ pub struct FileRelevance {
     pub score: f64,
     pub keyword_matches: usize,
     pub filename_match: bool,
+    /// Personalized PageRank seeded by the direct matches: proximity to the hits
     pub dependency_boost: f64,
+    /// Definitions named after a keyword, plus references to them
+    pub symbol_matches: usize,
 }
 
 impl std::fmt::Display for FileRelevance {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{}: score={:.2} (matches={}, filename={}, dep_boost={:.2})",
+            "{}: score={:.2} (matches={}, filename={}, dep_boost={:.2}, symbols={})",
             self.path.display(),
             self.score,
             self.keyword_matches,
             self.filename_match,
-            self.dependency_boost
+            self.dependency_boost,
+            self.symbol_matches
         )
     }
 }
@@ -136,33 +141,71 @@ impl std::fmt::Display for FileRelevance {
 /// Query engine for finding relevant files
 pub struct QueryEngine<'a> {
     analysis: QueryAnalysis,
-    #[allow(dead_code)]
     graph: &'a DependencyGraph,
-    pagerank: HashMap<PathBuf, f64>,
+    /// Per file: (definitions matching a keyword, references to matching definitions)
+    symbol_hits: HashMap<PathBuf, (usize, usize)>,
 }
 
 impl<'a> QueryEngine<'a> {
     /// Create a new query engine
     pub fn new(query: &str, graph: &'a DependencyGraph) -> Self {
         let analysis = QueryAnalysis::from_query(query);
-        let pagerank = graph.calculate_pagerank();
 
         Self {
             analysis,
             graph,
-            pagerank,
+            symbol_hits: HashMap::new(),
         }
     }
 
-    /// Score all files by relevance to query
-    pub fn score_files(&self, files: &[PathBuf]) -> Vec<FileRelevance> {
+    /// Boosts files defining symbols named after a keyword, and files that use them.
+    pub fn with_symbols(mut self, symbols: &SymbolGraph) -> Self {
+        for (id, symbol) in symbols.symbols().iter().enumerate() {
+            let matches = name_words(&symbol.name)
+                .iter()
+                .any(|word| self.analysis.expanded_keywords.contains(word));
+            if !matches {
+                continue;
+            }
+            self.symbol_hits.entry(symbol.file.clone()).or_default().0 += 1;
+            for referrer in symbols.referrers(id) {
+                let file = &symbols.symbol(referrer).file;
+                if file != &symbol.file {
+                    self.symbol_hits.entry(file.clone()).or_default().1 += 1;
+                }
+            }
+        }
+        self
+    }
+
+    /// Files matching the query on their own, with their scores, as PageRank seeds
+    pub fn seeds(&self, files: &[PathBuf]) -> HashMap<PathBuf, f64> {
         files
             .par_iter()
             .filter_map(|path| self.score_file(path))
+            .map(|r| (r.path, r.score))
             .collect()
     }
 
-    /// Score a single file
+    /// Score all files by relevance to query
+    pub fn score_files(&self, files: &[PathBuf]) -> Vec<FileRelevance> {
+        let mut scored: Vec<FileRelevance> = files
+            .par_iter()
+            .filter_map(|path| self.score_file(path))
+            .collect();
+
+        // Rank by proximity to the direct hits, not by global centrality
+        let seeds: HashMap<PathBuf, f64> =
+            scored.iter().map(|r| (r.path.clone(), r.score)).collect();
+        let proximity = self.graph.personalized_pagerank(&seeds);
+        for relevance in &mut scored {
+            relevance.dependency_boost = proximity.get(&relevance.path).copied().unwrap_or(0.0);
+            relevance.score += relevance.dependency_boost * 100.0;
+        }
+        scored
+    }
+
+    /// Score a single file on its own matches
     fn score_file(&self, path: &PathBuf) -> Option<FileRelevance> {
         // Read file content
         let content = fs::read_to_string(path).ok()?;
@@ -188,14 +231,13 @@ impl<'a> QueryEngine<'a> {
             .iter()
             .any(|kw| filename.contains(kw.as_str()));
 
+        let (definitions, references) = self.symbol_hits.get(path).copied().unwrap_or_default();
+
         // Skip files with no matches
-        if keyword_matches == 0 && !filename_match {
+        if keyword_matches == 0 && !filename_match && definitions + references == 0 {
             return None;
         }
 
-        // Get PageRank boost
-        let pagerank_boost = self.pagerank.get(path).copied().unwrap_or(0.0);
-
         // Calculate final score
         let mut score = 0.0;
 
@@ -207,11 +249,13 @@ impl<'a> QueryEngine<'a> {
             score += 50.0;
         }
 
-        // PageRank boost (centrality matters)
-        score += pagerank_boost * 100.0;
+        // Defining a matching symbol is a strong signal, using one a weaker one
+        score += definitions.min(5) as f64 * 20.0;
+        score += references.min(5) as f64 * 5.0;
 
-        // Normalize by file size (prefer smaller, focused files)
-        let size_factor = 1.0 / (1.0 + (content.len() as f64 / 10000.0).ln());
+        // Normalize by file size (prefer smaller, focused files). `ln_1p` keeps the factor in
+        // (0, 1]: a plain `ln` goes negative under 10 KB and divides by zero around 3.7 KB
+        let size_factor = 1.0 / (1.0 + (content.len() as f64 / 10000.0).ln_1p());
         score *= size_factor;
 
         Some(FileRelevance {
@@ -219,7 +263,8 @@ impl<'a> QueryEngine<'a> {
             score,
             keyword_matches,
             filename_match,
-            dependency_boost: pagerank_boost,
+            dependency_boost: 0.0,
+            symbol_matches: definitions + references,
         })
     }
 
@@ -274,6 +319,24 @@ impl<'a> QueryEngine<'a> {
     }
 }
 
+/// Lowercased words of an identifier (`updateCache`, `update_cache` -> `update`, `cache`).
+fn name_words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    for c in name.chars() {
+        if (!c.is_alphanumeric() || c.is_uppercase()) && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        if c.is_alphanumeric() {
+            current.extend(c.to_lowercase());
+        }
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -305,6 +368,55 @@ mod tests {
         assert!(analysis.expanded_keywords.contains("stripe"));
     }
 
+    #[test]
+    fn test_name_words() {
+        assert_eq!(name_words("updateCache"), vec!["update", "cache"]);
+        assert_eq!(name_words("SessionStore"), vec!["session", "store"]);
+        assert_eq!(name_words("refresh_token"), vec!["refresh", "token"]);
+    }
+
+    #[test]
+    fn test_proximity_to_hits_beats_centrality() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = |name: &str| dir.path().join(name);
+        std::fs::write(path("login.rs"), "pub fn login() {}\n").unwrap();
+        std::fs::write(path("token.rs"), "// used by login\n").unwrap();
+        std::fs::write(path("log.rs"), "// login\n").unwrap();
+
+        let mut graph = DependencyGraph::new();
+        graph.add_edge(path("login.rs"), path("token.rs"));
+        for caller in ["a.rs", "b.rs", "c.rs", "d.rs"] {
+            graph.add_edge(path(caller), path("log.rs"));
+        }
+
+        let engine = QueryEngine::new("login flow", &graph);
+        let files = vec![path("login.rs"), path("token.rs"), path("log.rs")];
+        assert_eq!(
+            engine.get_top_files(&files, 3),
+            vec![path("login.rs"), path("token.rs"), path("log.rs")]
+        );
+    }
+
+    #[test]
+    fn test_size_factor_on_small_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = |name: &str| dir.path().join(name);
+        std::fs::write(path("tiny.rs"), "// login\n").unwrap();
+        let near_pole = format!("// login\n{}", "x".repeat(3_670));
+        std::fs::write(path("medium.rs"), near_pole).unwrap();
+        let large = format!("// login\n{}", "x".repeat(50_000));
+        std::fs::write(path("large.rs"), large).unwrap();
+
+        let graph = DependencyGraph::new();
+        let engine = QueryEngine::new("login", &graph);
+        let score = |name: &str| engine.score_file(&path(name)).unwrap().score;
+        for name in ["tiny.rs", "medium.rs", "large.rs"] {
+            assert!(score(name).is_finite() && score(name) > 0.0, "{}", name);
+        }
+        assert!(score("tiny.rs") > score("medium.rs"));
+        assert!(score("medium.rs") > score("large.rs"));
+    }
+
     #[test]
     fn test_short_words_filtered() {
         let analysis = QueryAnalysis::from_query("a is to be or");
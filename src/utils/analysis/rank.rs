@@ -1,3 +1,5 @@
+use crate::config::{RankWeights, RankingConfig};
+use lazy_static::lazy_static;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
@@ -38,39 +40,166 @@ pub struct FileScore {
     pub entropy: f64,
     pub churn: i32,
     pub heuristic: i32,
+    /// 1.0 for a file changed just now, halving every `ranking.recency_half_life` days
+    pub recency: f64,
     pub tokens: usize,
 }
 
 impl FileScore {
+    /// Combined score with the default weights.
     pub fn final_score(&self) -> f64 {
-        // Normalize and weight
+        self.final_score_with(&RankWeights::default())
+    }
+
+    /// Combined score with the `[ranking.weights]` from the config.
+    pub fn final_score_with(&self, weights: &RankWeights) -> f64 {
         // Heuristic: 0-1000
         // Churn: 0-200
-        // PageRank: 0.0-1.0 (approx, depends on N)
+        // PageRank: 0.0-1.0 (approx, depends on N); 0.01 PR -> 10 points by default
         // Entropy: 4.0-6.0 range usually for code
-
-        // Combine all factors into final score
-
-        let rank_bonus = self.pagerank * 1000.0; // Assume 0.01 PR -> 10 points
-        let entropy_bonus = self.entropy * 10.0;
-
-        self.heuristic as f64 + (self.churn as f64) + rank_bonus + entropy_bonus
+        // Recency: 0.0-1.0; a file changed today -> 100 points by default
+        self.heuristic as f64 * weights.heuristic
+            + self.churn as f64 * weights.churn
+            + self.pagerank * weights.pagerank
+            + self.entropy * weights.entropy
+            + self.recency * weights.recency
     }
 }
 
-/// Semantic Ranking Logic for File Ordering
+/// Built-in ranking profile, checked in order (first match wins).
 ///
-/// Heuristics:
-/// 1. Documentation (1000): README.md
+/// 1. Documentation (1000, 900): README.md, ARCHITECTURE.md
 /// 2. Configs (800): Cargo.toml, package.json
 /// 3. Entry Points (700): main.rs, lib.rs
 /// 4. Core Logic (600): core/, app/, model/
 /// 5. Utilities (400): util/, common/
 /// 6. Tests (100): tests/, *.spec.ts
-/// 7. Default (500)
 ///
-/// Higher score = Earlier in output.
+/// Anything else gets the default score (500).
+pub const DEFAULT_RULES: &[(&str, i32)] = &[
+    ("readme.md", 1000),
+    ("readme.txt", 1000),
+    ("architecture.md", 900),
+    ("contributing.md", 900),
+    ("cargo.toml", 800),
+    ("package.json", 800),
+    ("go.mod", 800),
+    ("makefile", 800),
+    ("dockerfile", 800),
+    ("main.rs", 700),
+    ("lib.rs", 700),
+    ("index.js", 700),
+    ("main.go", 700),
+    ("core", 600),
+    ("app", 600),
+    ("model", 600),
+    ("models", 600),
+    ("schema", 600),
+    ("schemas", 600),
+    ("util", 400),
+    ("utils", 400),
+    ("common", 400),
+    ("helper", 400),
+    ("helpers", 400),
+    ("test", 100),
+    ("tests", 100),
+    ("__tests__", 100),
+    ("spec", 100),
+    ("specs", 100),
+    ("bench", 100),
+    ("benches", 100),
+    ("benchmarks", 100),
+    ("test_*", 100),
+    ("*_test.*", 100),
+    ("*.test.*", 100),
+    ("*_spec.*", 100),
+    ("*.spec.*", 100),
+];
+
+lazy_static! {
+    static ref DEFAULT_PROFILE: RankingProfile = RankingProfile::new(&RankingConfig::default());
+}
+
+/// Compiled ranking rules.
+///
+/// A pattern without `/` matches any single path component (directory or file name) or
+/// the file name without its extension, so `util` matches `src/util/mod.rs` and `util.rs`
+/// but not `mapper_utility.rs`. A pattern with `/` matches the whole relative path.
+/// Matching is case-insensitive.
+#[derive(Debug)]
+pub struct RankingProfile {
+    rules: Vec<(glob::Pattern, bool, i32)>,
+    default_score: i32,
+    depth_penalty: i32,
+}
+
+impl RankingProfile {
+    pub fn new(config: &RankingConfig) -> Self {
+        let builtin = DEFAULT_RULES
+            .iter()
+            .filter(|_| config.default_profile)
+            .map(|&(pattern, score)| (pattern, score));
+        let custom = config.rules.iter().map(|r| (r.pattern.as_str(), r.score));
+
+        let rules = custom
+            .chain(builtin)
+            .filter_map(|(pattern, score)| {
+                let pattern = pattern.trim_start_matches("./").to_lowercase();
+                match glob::Pattern::new(&pattern) {
+                    Ok(compiled) => Some((compiled, pattern.contains('/'), score)),
+                    Err(e) => {
+                        eprintln!("Warning: invalid ranking pattern {:?}: {}", pattern, e);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        Self {
+            rules,
+            default_score: config.default_score,
+            depth_penalty: config.depth_penalty,
+        }
+    }
+
+    /// Base score of a path relative to the repository root.
+    pub fn score(&self, path: &Path) -> i32 {
+        let components: Vec<String> = path
+            .components()
+            .filter_map(|c| match c {
+                std::path::Component::Normal(part) => Some(part.to_string_lossy().to_lowercase()),
+                _ => None,
+            })
+            .collect();
+        let full = components.join("/");
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        let options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+        let score = self
+            .rules
+            .iter()
+            .find(|(pattern, whole_path, _)| {
+                if *whole_path {
+                    pattern.matches_with(&full, options)
+                } else {
+                    pattern.matches(&stem) || components.iter().any(|c| pattern.matches(c))
+                }
+            })
+            .map_or(self.default_score, |&(_, _, score)| score);
+
+        // Prefer high-level files
+        score - components.len() as i32 * self.depth_penalty
+    }
+}
+
 /// Sorting using unified FileScore
+/// Higher score = Earlier in output.
 pub fn sort_paths(paths: &mut [PathBuf], scores: &HashMap<PathBuf, FileScore>) {
     paths.sort_by(|a, b| {
         let score_a = scores.get(a).map(|s| s.final_score()).unwrap_or(0.0);
@@ -84,88 +213,29 @@ pub fn sort_paths(paths: &mut [PathBuf], scores: &HashMap<PathBuf, FileScore>) {
     });
 }
 
-/// Calculates the base heuristic score based on filename rules.
+/// Calculates the base heuristic score of a relative path with the built-in profile.
 pub fn heuristic_score(path: &Path) -> i32 {
-    let filename = path
-        .file_name()
-        .and_then(|f: &std::ffi::OsStr| f.to_str())
-        .unwrap_or_default()
-        .to_lowercase();
-
-    let path_str = path.to_string_lossy().to_lowercase();
-    let components: Vec<_> = path
-        .components()
-        .map(|c: std::path::Component| c.as_os_str().to_string_lossy().to_lowercase())
-        .collect();
-
-    let mut score = 500; // Default baseline
-
-    // 1. Documentation (Highest Priority) +1000
-    if filename == "readme.md" || filename == "readme.txt" {
-        score = 1000;
-    } else if filename == "architecture.md" || filename == "contributing.md" {
-        score = 900;
-    }
-    // 2. Project Configuration +800
-    else if filename == "cargo.toml"
-        || filename == "package.json"
-        || filename == "go.mod"
-        || filename == "makefile"
-        || filename == "dockerfile"
-    {
-        score = 800;
-    }
-    // 3. Entry Points +700
-    else if filename == "main.rs"
-        || filename == "lib.rs"
-        || filename == "index.js"
-        || filename == "main.go"
-    {
-        score = 700;
-    }
-    // 4. Core Logic +600
-    else if path_str.contains("core")
-        || path_str.contains("app")
-        || path_str.contains("model")
-        || path_str.contains("schema")
-    {
-        score = 600;
-    }
-    // 6. Utilities +400
-    else if path_str.contains("util")
-        || path_str.contains("common")
-        || path_str.contains("helper")
-    {
-        score = 400;
-    }
-    // 7. Tests/Benchmarks (Lowest Priority) +100
-    else if path_str.contains("test")
-        || path_str.contains("spec")
-        || path_str.contains("bench")
-        || filename.ends_with("_test.go")
-        || filename.ends_with(".test.ts")
-    {
-        score = 100;
-    }
-
-    // 8. Depth Penalty
-    // Subtract 10 points per depth level to prefer high-level files
-    let depth = components.len() as i32;
-    score -= depth * 10;
-
-    score
+    DEFAULT_PROFILE.score(path)
 }
 
-/// Sorts files using topological order from the dependency graph,
+/// Sorts `paths` using topological order from the dependency graph,
 /// with score-based tie-breaking for files at the same dependency level.
 pub fn sort_files(
-    _paths: &[PathBuf],
+    paths: &[PathBuf],
     scores: &HashMap<PathBuf, FileScore>,
     graph: &crate::utils::graph::DependencyGraph,
+    weights: &RankWeights,
 ) -> Vec<PathBuf> {
-    graph.sort_topologically(|a, b| {
-        let score_a = scores.get(a).map(|s| s.final_score()).unwrap_or(0.0);
-        let score_b = scores.get(b).map(|s| s.final_score()).unwrap_or(0.0);
+    // Import targets outside `paths` are graph nodes too, but not files to output
+    graph.subgraph(paths).sort_topologically(|a, b| {
+        let score_a = scores
+            .get(a)
+            .map(|s| s.final_score_with(weights))
+            .unwrap_or(0.0);
+        let score_b = scores
+            .get(b)
+            .map(|s| s.final_score_with(weights))
+            .unwrap_or(0.0);
         // Descending score (higher score first)
         score_b
             .partial_cmp(&score_a)
@@ -254,4 +324,56 @@ mod tests {
         // churned_util (650) > regular_core (600)
         assert_eq!(paths[0].to_str().unwrap(), "churned_util.rs");
     }
+
+    #[test]
+    fn test_rules_match_whole_components() {
+        assert_eq!(heuristic_score(Path::new("src/mapper.rs")), 480);
+        assert_eq!(heuristic_score(Path::new("src/contest.rs")), 480);
+        assert_eq!(heuristic_score(Path::new("src/app/mod.rs")), 570);
+        assert_eq!(heuristic_score(Path::new("src/models.py")), 580);
+        assert_eq!(heuristic_score(Path::new("pkg/api/server_test.go")), 70);
+        assert_eq!(heuristic_score(Path::new("tests/main.rs")), 680);
+    }
+
+    #[test]
+    fn test_custom_ranking() {
+        let config = RankingConfig {
+            rules: vec![
+                crate::config::RankRule {
+                    pattern: "src/generated/**".into(),
+                    score: 0,
+                },
+                crate::config::RankRule {
+                    pattern: "domain".into(),
+                    score: 950,
+                },
+            ],
+            depth_penalty: 0,
+            ..Default::default()
+        };
+        let profile = RankingProfile::new(&config);
+        assert_eq!(profile.score(Path::new("src/generated/api/types.rs")), 0);
+        assert_eq!(profile.score(Path::new("generated/types.rs")), 500);
+        assert_eq!(profile.score(Path::new("src/domain/order.rs")), 950);
+        assert_eq!(profile.score(Path::new("README.md")), 1000);
+
+        let no_defaults = RankingProfile::new(&RankingConfig {
+            default_profile: false,
+            ..config
+        });
+        assert_eq!(no_defaults.score(Path::new("README.md")), 500);
+
+        let score = FileScore {
+            heuristic: 400,
+            churn: 100,
+            ..Default::default()
+        };
+        let weights = RankWeights {
+            churn: 3.0,
+            heuristic: 0.5,
+            ..Default::default()
+        };
+        assert_eq!(score.final_score_with(&weights), 500.0);
+        assert_eq!(score.final_score(), 500.0);
+    }
 }
@@ -0,0 +1,282 @@
+//! Near-duplicate detection
+//!
+//! MinHash signatures over word shingles estimate how much of their content two files
+//! share. Vendored copies, generated stubs and copy-pasted migrations are clustered around
+//! their best-ranked member, and the others can be listed against it as a unified diff.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Words per shingle
+const SHINGLE: usize = 5;
+/// Hash functions per signature
+const PERMUTATIONS: usize = 64;
+/// Signature rows per LSH band; files sharing any band are compared
+const ROWS: usize = 4;
+/// Files with fewer shingles than this are too small to call duplicates
+const MIN_SHINGLES: usize = 16;
+/// Lines of context around each diff hunk
+const CONTEXT: usize = 3;
+/// Largest changed region (old lines × new lines) a diff is computed for
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// MinHash signature of a file's word shingles
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinHash([u64; PERMUTATIONS]);
+
+impl MinHash {
+    /// Signature of `text`, or `None` if it is too short to compare meaningfully.
+    pub fn of(text: &str) -> Option<Self> {
+        let words: Vec<&str> = text
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .filter(|w| !w.is_empty())
+            .collect();
+        if words.len() < SHINGLE + MIN_SHINGLES - 1 {
+            return None;
+        }
+
+        let mut mins = [u64::MAX; PERMUTATIONS];
+        for shingle in words.windows(SHINGLE) {
+            let base = fnv1a(shingle);
+            for (i, min) in mins.iter_mut().enumerate() {
+                let hash = mix(base ^ (i as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+                *min = (*min).min(hash);
+            }
+        }
+        Some(Self(mins))
+    }
+
+    /// Estimated Jaccard similarity of the two shingle sets, from 0.0 to 1.0.
+    pub fn similarity(&self, other: &MinHash) -> f64 {
+        let equal = self.0.iter().zip(&other.0).filter(|(a, b)| a == b).count();
+        equal as f64 / PERMUTATIONS as f64
+    }
+
+    /// LSH bucket keys: one per band of `ROWS` signature values
+    fn bands(&self) -> impl Iterator<Item = (usize, u64)> + '_ {
+        self.0
+            .chunks(ROWS)
+            .enumerate()
+            .map(|(band, rows)| (band, rows.iter().fold(0, |acc, &r| mix(acc ^ r))))
+    }
+}
+
+/// Clusters near-duplicates around their best-ranked member.
+///
+/// `ranked` is ordered best first. Each file joins the most similar earlier representative
+/// at `threshold` or above, or becomes a representative itself. Returns each duplicate
+/// with its representative and their similarity.
+pub fn cluster(ranked: &[(PathBuf, MinHash)], threshold: f64) -> HashMap<PathBuf, (PathBuf, f64)> {
+    let mut buckets: HashMap<(usize, u64), Vec<usize>> = HashMap::new();
+    let mut duplicates = HashMap::new();
+
+    for (index, (path, signature)) in ranked.iter().enumerate() {
+        let mut candidates: Vec<usize> = signature
+            .bands()
+            .filter_map(|key| buckets.get(&key))
+            .flatten()
+            .copied()
+            .collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        let best = candidates
+            .into_iter()
+            .map(|rep| (rep, signature.similarity(&ranked[rep].1)))
+            .filter(|(_, similarity)| *similarity >= threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1).then_with(|| b.0.cmp(&a.0)));
+        match best {
+            Some((rep, similarity)) => {
+                duplicates.insert(path.clone(), (ranked[rep].0.clone(), similarity));
+            }
+            None => {
+                for key in signature.bands() {
+                    buckets.entry(key).or_default().push(index);
+                }
+            }
+        }
+    }
+    duplicates
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Unified diff from `old` to `new` with 3 lines of context; empty if they are identical.
+///
+/// Returns `None` when the changed region is too large to diff line by line.
+pub fn unified_diff(old: &str, new: &str, old_name: &str, new_name: &str) -> Option<String> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+
+    // Only the region between the common prefix and suffix needs the LCS table
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (mid_a, mid_b) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+    if mid_a.is_empty() && mid_b.is_empty() {
+        return Some(String::new());
+    }
+    if (mid_a.len() + 1) * (mid_b.len() + 1) > MAX_DIFF_CELLS {
+        return None;
+    }
+
+    // lcs[i * width + j]: longest common subsequence of mid_a[i..] and mid_b[j..]
+    let width = mid_b.len() + 1;
+    let mut lcs = vec![0u32; (mid_a.len() + 1) * width];
+    for i in (0..mid_a.len()).rev() {
+        for j in (0..mid_b.len()).rev() {
+            lcs[i * width + j] = if mid_a[i] == mid_b[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut ops = vec![Op::Equal; prefix];
+    let (mut i, mut j) = (0, 0);
+    while i < mid_a.len() || j < mid_b.len() {
+        if i < mid_a.len() && j < mid_b.len() && mid_a[i] == mid_b[j] {
+            ops.push(Op::Equal);
+            i += 1;
+            j += 1;
+        } else if j == mid_b.len()
+            || (i < mid_a.len() && lcs[(i + 1) * width + j] >= lcs[i * width + j + 1])
+        {
+            ops.push(Op::Delete);
+            i += 1;
+        } else {
+            ops.push(Op::Insert);
+            j += 1;
+        }
+    }
+    ops.extend(std::iter::repeat_n(Op::Equal, suffix));
+
+    // Position in each file before every op
+    let mut positions = Vec::with_capacity(ops.len());
+    let (mut old_line, mut new_line) = (0, 0);
+    for op in &ops {
+        positions.push((old_line, new_line));
+        if *op != Op::Insert {
+            old_line += 1;
+        }
+        if *op != Op::Delete {
+            new_line += 1;
+        }
+    }
+
+    // Changes closer than twice the context share a hunk
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (index, _) in ops.iter().enumerate().filter(|(_, op)| **op != Op::Equal) {
+        let start = index.saturating_sub(CONTEXT);
+        let end = (index + CONTEXT + 1).min(ops.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut diff = format!("--- a/{}\n+++ b/{}\n", old_name, new_name);
+    for (start, end) in hunks {
+        let range = &ops[start..end];
+        let old_len = range.iter().filter(|op| **op != Op::Insert).count();
+        let new_len = range.iter().filter(|op| **op != Op::Delete).count();
+        let (old_start, new_start) = positions[start];
+        let line_number = |start: usize, len: usize| if len == 0 { start } else { start + 1 };
+        diff.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            line_number(old_start, old_len),
+            old_len,
+            line_number(new_start, new_len),
+            new_len
+        ));
+        for (op, (old_line, new_line)) in range.iter().zip(&positions[start..end]) {
+            let (marker, line) = match op {
+                Op::Equal => (' ', a[*old_line]),
+                Op::Delete => ('-', a[*old_line]),
+                Op::Insert => ('+', b[*new_line]),
+            };
+            diff.push(marker);
+            diff.push_str(line);
+            diff.push('\n');
+        }
+    }
+    Some(diff)
+}
+
+/// FNV-1a over the words of a shingle
+fn fnv1a(words: &[&str]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for word in words {
+        for byte in word.bytes().chain(std::iter::once(b' ')) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+/// SplitMix64 finalizer
+fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migration(table: &str, extra: &str) -> String {
+        let columns: String = (0..30)
+            .map(|i| format!("    field_{i} TEXT NOT NULL DEFAULT 'value {i}',\n"))
+            .collect();
+        format!(
+            "CREATE TABLE {table} (\n    id INTEGER PRIMARY KEY,\n{columns}    notes TEXT{extra}\n);\nCREATE INDEX idx_{table}_notes ON {table} (notes);\n"
+        )
+    }
+
+    #[test]
+    fn test_cluster_near_duplicates() {
+        let original = migration("accounts", "");
+        let copy = migration("accounts", ",\n    archived BOOLEAN");
+        let other = "fn main() {\n    let args: Vec<String> = std::env::args().collect();\n    let config = parse(&args).expect(\"bad arguments\");\n    for file in discover(&config) {\n        println!(\"{}\", render(&file));\n    }\n}\n";
+
+        let signature = |text: &str| MinHash::of(text).unwrap();
+        assert_eq!(signature(&original).similarity(&signature(&original)), 1.0);
+        assert!(MinHash::of("too short").is_none());
+
+        let ranked = vec![
+            (PathBuf::from("001_accounts.sql"), signature(&original)),
+            (PathBuf::from("main.rs"), signature(other)),
+            (PathBuf::from("vendor/001_accounts.sql"), signature(&copy)),
+        ];
+        let duplicates = cluster(&ranked, 0.8);
+        assert_eq!(duplicates.len(), 1);
+        let (of, similarity) = &duplicates[&PathBuf::from("vendor/001_accounts.sql")];
+        assert_eq!(of, &PathBuf::from("001_accounts.sql"));
+        assert!(*similarity >= 0.8 && *similarity < 1.0);
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let new = "a\nb\nc\nd\nE\nf\ng\nh\ni\nj\nk\n";
+        let diff = unified_diff(old, new, "x.txt", "y.txt").unwrap();
+        assert_eq!(
+            diff,
+            "--- a/x.txt\n+++ b/y.txt\n\
+             @@ -2,9 +2,10 @@\n b\n c\n d\n-e\n+E\n f\n g\n h\n i\n j\n+k\n"
+        );
+        assert_eq!(unified_diff(old, old, "x", "x").unwrap(), "");
+    }
+}
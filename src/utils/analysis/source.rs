@@ -0,0 +1,237 @@
+//! Single-pass source analysis
+//!
+//! Parses a file once and runs every per-language query (imports, concepts, summary symbols,
+//! function bodies) against the same tree, instead of each analysis re-parsing the file.
+
+use crate::utils::language;
+use crate::utils::sfc::{Block, BlockKind};
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// Everything the pipeline derives from a file's syntax tree.
+#[derive(Debug, Clone, Default)]
+pub struct SourceAnalysis {
+    /// Raw import strings, as returned by `extract_imports`
+    pub imports: Vec<String>,
+    /// Concept labels, as returned by `extract_concepts`
+    pub concepts: Vec<String>,
+    /// Symbol summary, as returned by `summarize_content`
+    pub summary: Option<String>,
+    /// Outermost function-body byte ranges elided by AST compression, ascending
+    pub body_ranges: Vec<(usize, usize)>,
+    /// Text that replaces an elided body
+    pub placeholder: String,
+    /// Component template/style block contents and their placeholder, collapsed by aggressive compression
+    pub markup_ranges: Vec<(usize, usize, &'static str)>,
+    /// Hash of the analysed content
+    content_hash: u64,
+}
+
+impl SourceAnalysis {
+    /// Parses `content` once and runs all analyses on the tree.
+    pub fn analyze(content: &str, extension: &str) -> Self {
+        let mut analysis = Self {
+            content_hash: content_hash(content),
+            ..Default::default()
+        };
+
+        let Some(support) = language::for_extension(extension) else {
+            analysis.summary = crate::utils::summary::summarize_content_regex(content);
+            return analysis;
+        };
+        analysis.placeholder = support.placeholder.clone();
+
+        if let Some(split) = support.blocks {
+            analysis.add_blocks(content, &split(content));
+            return analysis;
+        }
+
+        if let Some(scan) = support.line_imports {
+            analysis.imports = scan(content);
+        }
+        if support.symbols.is_none() {
+            analysis.summary = crate::utils::summary::summarize_content_regex(content);
+        }
+
+        if let Some(tree) = support.parse(content) {
+            if support.line_imports.is_none() {
+                analysis.imports =
+                    crate::utils::dependencies::imports_from_tree(&support, &tree, content);
+            }
+            analysis.concepts =
+                crate::utils::concepts::concepts_from_tree(&support, &tree, content);
+            if support.symbols.is_some() {
+                analysis.summary = crate::utils::summary::summarize_tree(&support, &tree, content);
+            }
+            analysis.body_ranges = crate::utils::ast::body_ranges(&support, &tree, content);
+        }
+
+        analysis
+    }
+
+    /// Merges the analyses of a component's script blocks, shifting offsets into the file.
+    fn add_blocks(&mut self, content: &str, blocks: &[Block]) {
+        let mut summaries = Vec::new();
+        for block in blocks {
+            let (start, end) = (block.range.start, block.range.end);
+            match block.kind {
+                BlockKind::Script(extension) => {
+                    let script = Self::analyze(&content[start..end], extension);
+                    self.imports.extend(script.imports);
+                    self.concepts.extend(script.concepts);
+                    summaries.extend(script.summary);
+                    self.body_ranges.extend(
+                        script
+                            .body_ranges
+                            .iter()
+                            .map(|(s, e)| (start + s, start + e)),
+                    );
+                    self.placeholder = script.placeholder;
+                }
+                BlockKind::Template if !content[start..end].trim().is_empty() => {
+                    self.markup_ranges.push((start, end, "<!-- ... -->"));
+                }
+                BlockKind::Style if !content[start..end].trim().is_empty() => {
+                    self.markup_ranges.push((start, end, "/* ... */"));
+                }
+                _ => {}
+            }
+        }
+
+        self.imports.sort();
+        self.imports.dedup();
+        self.concepts.sort();
+        self.concepts.dedup();
+        self.body_ranges.sort();
+        self.summary = (!summaries.is_empty()).then(|| summaries.join("; "));
+    }
+
+    /// Whether this analysis was computed from exactly `content`.
+    pub fn matches(&self, content: &str) -> bool {
+        self.content_hash == content_hash(content)
+    }
+
+    /// Applies AST compression to the analysed content (same result as `compress_ast`).
+    pub fn compress(&self, content: &str) -> String {
+        crate::utils::ast::elide_ranges(content, &self.body_ranges, &self.placeholder)
+    }
+
+    /// Elides function bodies and also collapses component template and style blocks.
+    pub fn compress_aggressive(&self, content: &str) -> String {
+        let mut ranges: Vec<(usize, usize, &str)> = self
+            .body_ranges
+            .iter()
+            .map(|&(s, e)| (s, e, self.placeholder.as_str()))
+            .chain(self.markup_ranges.iter().copied())
+            .collect();
+        ranges.sort_by_key(|r| r.0);
+
+        let mut result = content.to_string();
+        for (start, end, placeholder) in ranges.into_iter().rev() {
+            if start < end && end <= result.len() {
+                result.replace_range(start..end, placeholder);
+            }
+        }
+        result
+    }
+}
+
+fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{ast, concepts, dependencies, summary};
+
+    #[test]
+    fn test_single_pass_matches_individual_analyses() {
+        let samples = [
+            (
+                "rs",
+                "use crate::config::AbyssConfig;\nmod graph;\n\npub struct Scanner { root: String }\n\nimpl Scanner {\n    pub fn run(&self) -> usize {\n        self.root.len()\n    }\n}\n",
+            ),
+            (
+                "py",
+                "from .models import User\n\nclass Repo:\n    def load(self, id):\n        return User(id)\n",
+            ),
+            (
+                "ts",
+                "import { api } from './api';\n\nexport interface Props { id: number }\n\nexport function render(p: Props) {\n  return api(p.id);\n}\n",
+            ),
+            (
+                "kt",
+                "import com.acme.model.User\n\nclass Api {\n    fun find() = 1\n}\n",
+            ),
+            (
+                "vue",
+                "<template>\n  <Button @click=\"go\" />\n</template>\n\n<script setup lang=\"ts\">\nimport Button from './Button.vue'\nfunction go() {\n  console.log('go')\n}\n</script>\n",
+            ),
+            ("txt", "just some text\n"),
+        ];
+
+        for (extension, content) in samples {
+            let analysis = SourceAnalysis::analyze(content, extension);
+            assert_eq!(
+                analysis.imports,
+                dependencies::extract_imports(content, extension)
+            );
+            assert_eq!(
+                analysis.concepts,
+                concepts::extract_concepts(content, extension)
+            );
+            assert_eq!(
+                analysis.summary,
+                summary::summarize_content(content, extension)
+            );
+            assert_eq!(
+                analysis.compress(content),
+                ast::compress_ast(content, extension)
+            );
+            assert!(analysis.matches(content));
+        }
+    }
+
+    #[test]
+    fn test_component_analysis() {
+        let content = r#"<template>
+  <UserCard :user="user" />
+</template>
+
+<script setup lang="ts">
+import UserCard from '@/components/UserCard.vue'
+import { useStore } from '../store'
+
+interface Props { id: number }
+
+function load(id: number) {
+  return useStore().fetch(id)
+}
+</script>
+
+<style scoped>
+.card { margin: 0; }
+</style>
+"#;
+        let analysis = SourceAnalysis::analyze(content, "vue");
+        assert_eq!(
+            analysis.imports,
+            vec!["../store", "@/components/UserCard.vue"]
+        );
+        assert!(analysis.concepts.contains(&"interface Props".to_string()));
+        assert!(analysis.concepts.contains(&"fn load".to_string()));
+
+        let smart = analysis.compress(content);
+        assert!(smart.contains("function load(id: number) { /* ... */ }"));
+        assert!(smart.contains(r#"<UserCard :user="user" />"#));
+        assert!(smart.contains(".card { margin: 0; }"));
+
+        let aggressive = analysis.compress_aggressive(content);
+        assert!(aggressive.contains("<template><!-- ... --></template>"));
+        assert!(aggressive.contains("<style scoped>/* ... */</style>"));
+        assert!(aggressive.contains("import UserCard from '@/components/UserCard.vue'"));
+        assert!(!aggressive.contains("fetch(id)"));
+    }
+}
@@ -0,0 +1,669 @@
+//! Symbol-level reference graph
+//!
+//! Definitions come from the per-language concept queries (the same ones behind
+//! `extract_concepts`); references are the identifiers inside each definition. Every
+//! reference is resolved to a definition by name, preferring the same file, then the files
+//! it imports, and an edge is added from the referencing definition to the referenced one.
+//! Member accesses (`x.update()`) only reach other files through imports or a type the
+//! referencing definition names (`x: &Cache`), so common method names stay unlinked.
+
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{Node, QueryCursor};
+
+use crate::utils::graph::DependencyGraph;
+use crate::utils::language::{self, LanguageSupport};
+use crate::utils::sfc::BlockKind;
+
+/// Unimported definitions sharing a name beyond this count are too ambiguous to link
+const MAX_AMBIGUOUS: usize = 3;
+
+/// A definition found in one file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Definition {
+    pub name: String,
+    /// Enclosing type (impl target, class, Go receiver), if any
+    pub owner: Option<String>,
+    pub kind: &'static str,
+    /// 1-based line of the name
+    pub line: usize,
+}
+
+/// An identifier used inside a definition.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Reference {
+    pub name: String,
+    /// Path qualifier (`Cache` in `Cache::update`), if written
+    pub qualifier: Option<String>,
+    /// Accessed on a value (`x.update`, `self.update`)
+    pub member: bool,
+    /// Index of the enclosing definition in `FileSymbols::definitions`
+    pub from: usize,
+}
+
+/// Definitions and references of one file.
+#[derive(Debug, Clone, Default)]
+pub struct FileSymbols {
+    pub definitions: Vec<Definition>,
+    pub references: Vec<Reference>,
+}
+
+/// A definition placed in the repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub owner: Option<String>,
+    pub kind: &'static str,
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+impl Symbol {
+    /// `Owner::name`, or just the name for free definitions.
+    pub fn qualified_name(&self) -> String {
+        match &self.owner {
+            Some(owner) if owner != &self.name => format!("{}::{}", owner, self.name),
+            _ => self.name.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} ({}:{})",
+            self.kind,
+            self.qualified_name(),
+            self.file.display(),
+            self.line
+        )
+    }
+}
+
+/// Where a reference is resolved from.
+struct Context<'a> {
+    file: &'a Path,
+    imported: Option<&'a HashSet<PathBuf>>,
+    mentioned: Option<&'a HashSet<&'a str>>,
+}
+
+/// Reference edges between definitions across the repository.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolGraph {
+    symbols: Vec<Symbol>,
+    by_name: HashMap<String, Vec<usize>>,
+    /// Referencing definition -> referenced definitions
+    edges: HashMap<usize, HashSet<usize>>,
+    /// Referenced definition -> referencing definitions
+    reverse: HashMap<usize, HashSet<usize>>,
+}
+
+impl SymbolGraph {
+    /// Parses `files` in parallel and links their references, using `graph`'s import
+    /// edges to pick between same-named definitions.
+    pub fn build(files: &[PathBuf], graph: &DependencyGraph) -> Self {
+        let extracted: Vec<(PathBuf, FileSymbols)> = files
+            .par_iter()
+            .filter_map(|path| {
+                let content = std::fs::read_to_string(path).ok()?;
+                let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("");
+                Some((path.clone(), file_symbols(&content, extension)))
+            })
+            .collect();
+        Self::from_files(extracted.iter().map(|(p, s)| (p.as_path(), s)), graph)
+    }
+
+    /// Links already extracted file symbols.
+    pub fn from_files<'a>(
+        files: impl IntoIterator<Item = (&'a Path, &'a FileSymbols)>,
+        graph: &DependencyGraph,
+    ) -> Self {
+        let mut symbol_graph = SymbolGraph::default();
+        let mut pending = Vec::new();
+
+        for (path, file) in files {
+            let base = symbol_graph.symbols.len();
+            for definition in &file.definitions {
+                let id = symbol_graph.symbols.len();
+                symbol_graph
+                    .by_name
+                    .entry(definition.name.clone())
+                    .or_default()
+                    .push(id);
+                symbol_graph.symbols.push(Symbol {
+                    name: definition.name.clone(),
+                    owner: definition.owner.clone(),
+                    kind: definition.kind,
+                    file: path.to_path_buf(),
+                    line: definition.line,
+                });
+            }
+            pending.push((path, base, &file.references));
+        }
+
+        for (path, base, references) in pending {
+            let imported = graph.get_edges().get(path);
+            // Names each definition mentions, used to type member accesses
+            let mut mentioned: HashMap<usize, HashSet<&str>> = HashMap::new();
+            for reference in references.iter().filter(|r| !r.member) {
+                mentioned
+                    .entry(reference.from)
+                    .or_default()
+                    .insert(&reference.name);
+            }
+
+            for reference in references {
+                let from = base + reference.from;
+                let context = Context {
+                    file: path,
+                    imported,
+                    mentioned: mentioned.get(&reference.from),
+                };
+                for to in symbol_graph.resolve(reference, from, &context) {
+                    symbol_graph.edges.entry(from).or_default().insert(to);
+                    symbol_graph.reverse.entry(to).or_default().insert(from);
+                }
+            }
+        }
+
+        symbol_graph
+    }
+
+    /// Definitions a reference may point to.
+    fn resolve(&self, reference: &Reference, from: usize, context: &Context) -> Vec<usize> {
+        let Some(named) = self.by_name.get(&reference.name) else {
+            return Vec::new();
+        };
+        let mut candidates: Vec<usize> = named.iter().copied().filter(|&id| id != from).collect();
+
+        let mut qualified = false;
+        if let Some(qualifier) = &reference.qualifier {
+            let owned: Vec<usize> = candidates
+                .iter()
+                .copied()
+                .filter(|&id| self.symbols[id].owner.as_ref() == Some(qualifier))
+                .collect();
+            if !owned.is_empty() {
+                candidates = owned;
+                qualified = true;
+            }
+        }
+
+        let local: Vec<usize> = candidates
+            .iter()
+            .copied()
+            .filter(|&id| self.symbols[id].file == context.file)
+            .collect();
+        if !local.is_empty() {
+            return local;
+        }
+
+        let from_imports: Vec<usize> = candidates
+            .iter()
+            .copied()
+            .filter(|&id| {
+                context
+                    .imported
+                    .is_some_and(|files| files.contains(&self.symbols[id].file))
+            })
+            .collect();
+        if !from_imports.is_empty() {
+            return from_imports;
+        }
+
+        // Elsewhere: members need a type the definition mentions, bare names a free definition
+        candidates.retain(|&id| match &self.symbols[id].owner {
+            Some(owner) if reference.member => context
+                .mentioned
+                .is_some_and(|names| names.contains(owner.as_str())),
+            Some(_) => qualified,
+            None => !reference.member,
+        });
+        if candidates.len() <= MAX_AMBIGUOUS {
+            candidates
+        } else {
+            Vec::new()
+        }
+    }
+
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    pub fn symbol(&self, id: usize) -> &Symbol {
+        &self.symbols[id]
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.values().map(HashSet::len).sum()
+    }
+
+    /// Finds definitions by `name`, `Owner::name` or `Owner.name`.
+    pub fn find(&self, query: &str) -> Vec<usize> {
+        let (owner, name) = match query.rsplit_once("::").or_else(|| query.rsplit_once('.')) {
+            Some((owner, name)) => (owner.rsplit("::").next(), name),
+            None => (None, query),
+        };
+        self.by_name
+            .get(name)
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|&id| owner.is_none() || self.symbols[id].owner.as_deref() == owner)
+            .collect()
+    }
+
+    /// Definitions used by `id`, sorted by location.
+    pub fn references(&self, id: usize) -> Vec<usize> {
+        self.sorted(self.edges.get(&id).into_iter().flatten().copied())
+    }
+
+    /// Definitions that use `id` (its callers), sorted by location.
+    pub fn referrers(&self, id: usize) -> Vec<usize> {
+        self.sorted(self.reverse.get(&id).into_iter().flatten().copied())
+    }
+
+    /// Everything that reaches `ids` through references, excluding `ids` themselves.
+    pub fn transitive_referrers(&self, ids: &[usize]) -> Vec<usize> {
+        self.sorted(self.reachable(ids, &self.reverse).into_iter())
+    }
+
+    /// The definition chain of `ids`: everything they reference, directly or not.
+    pub fn transitive_references(&self, ids: &[usize]) -> Vec<usize> {
+        self.sorted(self.reachable(ids, &self.edges).into_iter())
+    }
+
+    fn reachable(&self, ids: &[usize], edges: &HashMap<usize, HashSet<usize>>) -> HashSet<usize> {
+        let mut visited: HashSet<usize> = ids.iter().copied().collect();
+        let mut queue: VecDeque<usize> = ids.iter().copied().collect();
+        let mut found = HashSet::new();
+        while let Some(id) = queue.pop_front() {
+            for &next in edges.get(&id).into_iter().flatten() {
+                if visited.insert(next) {
+                    found.insert(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+        found
+    }
+
+    fn sorted(&self, ids: impl Iterator<Item = usize>) -> Vec<usize> {
+        let mut ids: Vec<usize> = ids.collect();
+        ids.sort_by(|&a, &b| {
+            let (a, b) = (&self.symbols[a], &self.symbols[b]);
+            (&a.file, a.line).cmp(&(&b.file, b.line))
+        });
+        ids
+    }
+
+    /// File-to-file edges implied by cross-file references.
+    pub fn file_edges(&self) -> HashSet<(PathBuf, PathBuf)> {
+        let mut edges = HashSet::new();
+        for (&from, targets) in &self.edges {
+            for &to in targets {
+                let (from, to) = (&self.symbols[from].file, &self.symbols[to].file);
+                if from != to {
+                    edges.insert((from.clone(), to.clone()));
+                }
+            }
+        }
+        edges
+    }
+}
+
+/// Extracts the definitions and references of one file.
+pub fn file_symbols(content: &str, extension: &str) -> FileSymbols {
+    let Some(support) = language::for_extension(extension) else {
+        return FileSymbols::default();
+    };
+    let content = &crate::utils::notebook::source_text(content, extension);
+
+    if let Some(split) = support.blocks {
+        let mut symbols = FileSymbols::default();
+        for block in split(content) {
+            let BlockKind::Script(script_extension) = block.kind else {
+                continue;
+            };
+            let script = file_symbols(&content[block.range.clone()], script_extension);
+            let line_offset = content[..block.range.start].matches('\n').count();
+            let base = symbols.definitions.len();
+            symbols
+                .definitions
+                .extend(script.definitions.into_iter().map(|mut d| {
+                    d.line += line_offset;
+                    d
+                }));
+            symbols
+                .references
+                .extend(script.references.into_iter().map(|mut r| {
+                    r.from += base;
+                    r
+                }));
+        }
+        return symbols;
+    }
+
+    match support.parse(content) {
+        Some(tree) => symbols_from_tree(&support, &tree, content),
+        None => FileSymbols::default(),
+    }
+}
+
+/// Runs the concept query for definitions, then collects the identifiers inside them.
+pub(crate) fn symbols_from_tree(
+    support: &LanguageSupport,
+    tree: &tree_sitter::Tree,
+    content: &str,
+) -> FileSymbols {
+    let mut symbols = FileSymbols::default();
+    let Some(query) = support
+        .concepts
+        .as_deref()
+        .and_then(|q| support.cached_query(q))
+    else {
+        return symbols;
+    };
+    let source = content.as_bytes();
+
+    // (definition byte range, name start) per entry in `symbols.definitions`
+    let mut spans: Vec<(usize, usize, usize)> = Vec::new();
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(&query, tree.root_node(), source);
+    while let Some(m) = matches.next() {
+        for capture in m.captures {
+            let Some(definition) = capture.node.parent() else {
+                continue;
+            };
+            let Some(kind) = symbol_kind(definition.kind()) else {
+                continue;
+            };
+            let Ok(name) = capture.node.utf8_text(source) else {
+                continue;
+            };
+            if spans.iter().any(|s| s.2 == capture.node.start_byte()) {
+                continue;
+            }
+            symbols.definitions.push(Definition {
+                name: name.to_string(),
+                owner: owner_of(definition, source),
+                kind,
+                line: capture.node.start_position().row + 1,
+            });
+            spans.push((
+                definition.start_byte(),
+                definition.end_byte(),
+                capture.node.start_byte(),
+            ));
+        }
+    }
+    if spans.is_empty() {
+        return symbols;
+    }
+
+    let mut seen = HashSet::new();
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        let mut walker = node.walk();
+        stack.extend(node.children(&mut walker));
+
+        if node.child_count() > 0 || !node.kind().ends_with("identifier") {
+            continue;
+        }
+        let start = node.start_byte();
+        if spans.iter().any(|s| s.2 == start) {
+            continue;
+        }
+        // Innermost definition containing the identifier
+        let Some(from) = spans
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.0 <= start && node.end_byte() <= s.1)
+            .min_by_key(|(_, s)| s.1 - s.0)
+            .map(|(i, _)| i)
+        else {
+            continue;
+        };
+        let Ok(name) = node.utf8_text(source) else {
+            continue;
+        };
+        if name.len() < 2 {
+            continue;
+        }
+        let reference = Reference {
+            name: name.to_string(),
+            qualifier: qualifier_of(node, source),
+            member: is_member(node),
+            from,
+        };
+        if seen.insert(reference.clone()) {
+            symbols.references.push(reference);
+        }
+    }
+
+    symbols
+}
+
+/// Kind label for a definition node; `None` for nodes that are not symbols (impl blocks, modules).
+fn symbol_kind(node_kind: &str) -> Option<&'static str> {
+    match crate::utils::concepts::definition_kind(node_kind) {
+        Some("impl") => None,
+        Some(kind) => Some(kind),
+        None => match node_kind {
+            "method_declaration" | "method_definition" | "variable_declarator" => Some("fn"),
+            "type_spec" => Some("type"),
+            _ => None,
+        },
+    }
+}
+
+/// The type a definition belongs to: Go receiver, Rust impl target, enclosing class or trait.
+fn owner_of(definition: Node, source: &[u8]) -> Option<String> {
+    if let Some(receiver) = definition.child_by_field_name("receiver") {
+        return first_of_kind(receiver, "type_identifier")
+            .and_then(|n| n.utf8_text(source).ok())
+            .map(str::to_string);
+    }
+
+    let mut ancestor = definition.parent();
+    while let Some(node) = ancestor {
+        let owner = match node.kind() {
+            "impl_item" => node.child_by_field_name("type"),
+            "trait_item"
+            | "class_definition"
+            | "class_declaration"
+            | "interface_declaration"
+            | "enum_declaration"
+            | "record_declaration"
+            | "class_specifier" => node.child_by_field_name("name"),
+            _ => None,
+        };
+        if let Some(owner) = owner.and_then(|n| n.utf8_text(source).ok()) {
+            // `Cache<T>` -> `Cache`
+            return Some(owner.split('<').next().unwrap_or(owner).trim().to_string());
+        }
+        ancestor = node.parent();
+    }
+    None
+}
+
+/// The path before `::` when `node` is the last segment of a scoped identifier.
+fn qualifier_of(node: Node, source: &[u8]) -> Option<String> {
+    let parent = node.parent()?;
+    if !parent.kind().starts_with("scoped_") || parent.child_by_field_name("name")? != node {
+        return None;
+    }
+    let path = parent.child_by_field_name("path")?.utf8_text(source).ok()?;
+    path.rsplit("::").next().map(str::to_string)
+}
+
+/// Whether `node` is the accessed name of a field, attribute or method access.
+fn is_member(node: Node) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    let receiver = match parent.kind() {
+        "field_expression" => "value",
+        "attribute" | "member_expression" | "method_invocation" | "field_access" => "object",
+        "selector_expression" => "operand",
+        _ => return false,
+    };
+    parent
+        .child_by_field_name(receiver)
+        .is_some_and(|object| object != node)
+}
+
+fn first_of_kind<'t>(node: Node<'t>, kind: &str) -> Option<Node<'t>> {
+    if node.kind() == kind {
+        return Some(node);
+    }
+    let mut walker = node.walk();
+    let children: Vec<Node<'t>> = node.children(&mut walker).collect();
+    children
+        .into_iter()
+        .find_map(|child| first_of_kind(child, kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph_of(files: &[(&str, &str)], imports: &[(&str, &str)]) -> SymbolGraph {
+        let extracted: Vec<(PathBuf, FileSymbols)> = files
+            .iter()
+            .map(|(path, content)| {
+                let extension = path.rsplit('.').next().unwrap_or("");
+                (PathBuf::from(path), file_symbols(content, extension))
+            })
+            .collect();
+        let mut graph = DependencyGraph::new();
+        for (from, to) in imports {
+            graph.add_edge(PathBuf::from(from), PathBuf::from(to));
+        }
+        SymbolGraph::from_files(extracted.iter().map(|(p, s)| (p.as_path(), s)), &graph)
+    }
+
+    fn names(graph: &SymbolGraph, ids: &[usize]) -> Vec<String> {
+        ids.iter()
+            .map(|&id| graph.symbol(id).qualified_name())
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_callers_and_definition_chain() {
+        let cache = r#"
+pub struct Entry { pub hash: u64 }
+
+pub struct Cache { entries: Vec<Entry> }
+
+impl Cache {
+    pub fn update(&mut self, hash: u64) {
+        self.entries.push(make_entry(hash));
+    }
+}
+
+fn make_entry(hash: u64) -> Entry {
+    Entry { hash }
+}
+"#;
+        let runner = r#"
+use crate::cache::Cache;
+
+pub fn process(cache: &mut Cache) {
+    cache.update(1);
+}
+
+pub fn reset(cache: &mut Cache) {
+    Cache::update(cache, 0);
+}
+
+fn main() {
+    process(&mut Cache::default());
+}
+"#;
+        let graph = graph_of(
+            &[("src/cache.rs", cache), ("src/runner.rs", runner)],
+            &[("src/runner.rs", "src/cache.rs")],
+        );
+
+        let update = graph.find("Cache::update");
+        assert_eq!(update.len(), 1);
+        assert_eq!(graph.symbol(update[0]).line, 7);
+        assert_eq!(graph.find("Other::update"), Vec::<usize>::new());
+
+        assert_eq!(
+            names(&graph, &graph.referrers(update[0])),
+            vec!["process", "reset"]
+        );
+        assert_eq!(
+            names(&graph, &graph.transitive_referrers(&update)),
+            vec!["process", "reset", "main"]
+        );
+        assert_eq!(
+            names(&graph, &graph.transitive_references(&update)),
+            vec!["Entry", "make_entry"]
+        );
+        assert!(graph.file_edges().contains(&(
+            PathBuf::from("src/runner.rs"),
+            PathBuf::from("src/cache.rs")
+        )));
+    }
+
+    #[test]
+    fn test_owners_across_languages() {
+        let go = "package store\n\ntype Store struct{}\n\nfunc (s *Store) Get(id int) int {\n\treturn lookup(id)\n}\n\nfunc lookup(id int) int { return id }\n";
+        let python = "class Repo:\n    def load(self, id):\n        return fetch(id)\n\ndef fetch(id):\n    return id\n";
+        let graph = graph_of(&[("store.go", go), ("repo.py", python)], &[]);
+
+        let get = graph.find("Store.Get");
+        assert_eq!(names(&graph, &get), vec!["Store::Get"]);
+        assert_eq!(
+            names(&graph, &graph.references(get[0])),
+            vec!["Store", "lookup"]
+        );
+
+        let load = graph.find("Repo::load");
+        assert_eq!(names(&graph, &graph.references(load[0])), vec!["fetch"]);
+    }
+
+    #[test]
+    fn test_ambiguous_names_are_not_linked() {
+        let files: Vec<(String, String)> = (0..5)
+            .map(|i| (format!("m{}.rs", i), "pub fn new() {}\n".to_string()))
+            .chain(std::iter::once((
+                "user.rs".to_string(),
+                "fn build() { new(); }\n".to_string(),
+            )))
+            .collect();
+        let refs: Vec<(&str, &str)> = files
+            .iter()
+            .map(|(p, c)| (p.as_str(), c.as_str()))
+            .collect();
+        let graph = graph_of(&refs, &[]);
+        let build = graph.find("build");
+        assert!(graph.references(build[0]).is_empty());
+
+        let graph = graph_of(&refs, &[("user.rs", "m3.rs")]);
+        let build = graph.find("build");
+        assert_eq!(
+            graph
+                .references(build[0])
+                .iter()
+                .map(|&id| graph.symbol(id).file.clone())
+                .collect::<Vec<_>>(),
+            vec![PathBuf::from("m3.rs")]
+        );
+    }
+
+    #[test]
+    fn test_member_access_needs_a_known_type() {
+        let index = "pub struct Index;\nimpl Index {\n    pub fn find(&self) {}\n}\n";
+        let user = "fn search(items: Vec<u8>) {\n    items.iter().find(|x| true);\n}\n\nfn lookup(index: &Index) {\n    index.find();\n}\n\nfn find_free() {\n    find();\n}\n";
+        let graph = graph_of(&[("index.rs", index), ("user.rs", user)], &[]);
+        let find = graph.find("Index::find");
+        assert_eq!(names(&graph, &graph.referrers(find[0])), vec!["lookup"]);
+    }
+}
@@ -1,69 +1,39 @@
+use crate::utils::language::LanguageSupport;
 use streaming_iterator::StreamingIterator;
-use tree_sitter::{Parser, Query, QueryCursor};
+use tree_sitter::QueryCursor;
 
 /// AST-aware compression that replaces function bodies with placeholders.
 /// Preserves function signatures, type definitions, and interfaces for LLM context.
 pub fn compress_ast(content: &str, extension: &str) -> String {
-    let language: tree_sitter::Language = match extension {
-        "rs" => tree_sitter_rust::LANGUAGE.into(),
-        "js" | "jsx" => tree_sitter_javascript::LANGUAGE.into(),
-        "ts" | "tsx" => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
-        "py" => tree_sitter_python::LANGUAGE.into(),
-        "go" => tree_sitter_go::LANGUAGE.into(),
-        "c" | "h" => tree_sitter_c::LANGUAGE.into(),
-        "cpp" | "hpp" | "cc" | "cxx" => tree_sitter_cpp::LANGUAGE.into(),
-        _ => return content.to_string(),
+    let Some(support) = crate::utils::language::for_extension(extension) else {
+        return content.to_string();
     };
-
-    let mut parser = Parser::new();
-    if parser.set_language(&language).is_err() {
+    if support.blocks.is_some() {
+        return crate::utils::source::SourceAnalysis::analyze(content, extension).compress(content);
+    }
+    if support.bodies.is_none() {
         return content.to_string();
     }
-
-    let tree = match parser.parse(content, None) {
-        Some(t) => t,
-        None => return content.to_string(),
+    let Some(tree) = support.parse(content) else {
+        return content.to_string();
     };
 
-    // Language-specific queries for function bodies
-    let query_str = match extension {
-        "rs" => {
-            r#"
-            (function_item body: (block) @body)
-        "#
-        }
-        "js" | "jsx" | "ts" | "tsx" => {
-            r#"
-            (function_declaration body: (statement_block) @body)
-            (method_definition body: (statement_block) @body)
-            (arrow_function body: (statement_block) @body)
-        "#
-        }
-        "py" => {
-            r#"
-            (function_definition body: (block) @body)
-        "#
-        }
-        "go" => {
-            r#"
-            (function_declaration body: (block) @body)
-            (method_declaration body: (block) @body)
-        "#
-        }
-        "c" | "h" | "cpp" | "hpp" | "cc" | "cxx" => {
-            r#"
-            (function_definition body: (compound_statement) @body)
-        "#
-        }
-        _ => return content.to_string(),
-    };
+    let ranges = body_ranges(&support, &tree, content);
+    elide_ranges(content, &ranges, &support.placeholder)
+}
 
-    let query = match Query::new(&language, query_str) {
-        Ok(q) => q,
-        Err(e) => {
-            eprintln!("Query error: {:?}", e);
-            return content.to_string();
-        }
+/// Finds the outermost function bodies in a parsed tree, ordered by start offset.
+pub(crate) fn body_ranges(
+    support: &LanguageSupport,
+    tree: &tree_sitter::Tree,
+    content: &str,
+) -> Vec<(usize, usize)> {
+    let Some(query) = support
+        .bodies
+        .as_deref()
+        .and_then(|q| support.cached_query(q))
+    else {
+        return Vec::new();
     };
 
     let mut cursor = QueryCursor::new();
@@ -74,22 +44,14 @@ pub fn compress_ast(content: &str, extension: &str) -> String {
     while let Some(m) = matches.next() {
         for capture in m.captures {
             let node = capture.node;
-            let start = node.start_byte();
-            let end = node.end_byte();
-
             // Replace entire block node with placeholder
-
-            ranges_to_replace.push((start, end));
+            ranges_to_replace.push((node.start_byte(), node.end_byte()));
         }
     }
 
-    // Sort reverse to replace without offset issues
-    ranges_to_replace.sort_by(|a, b| b.0.cmp(&a.0));
     // Sort by start ASC, end DESC to prefer outermost ranges
     // Filter nested ranges: only keep non-overlapping outermost matches
     // If we pick one, we skip all subsequent that start before its end.
-
-    // Implementation:
     ranges_to_replace.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
 
     let mut final_ranges = Vec::new();
@@ -102,23 +64,22 @@ pub fn compress_ast(content: &str, extension: &str) -> String {
         }
     }
 
-    // Now reverse for replacement
-    final_ranges.reverse();
+    final_ranges
+}
 
+/// Replaces ascending, non-overlapping byte ranges of `content` with `placeholder`.
+pub(crate) fn elide_ranges(content: &str, ranges: &[(usize, usize)], placeholder: &str) -> String {
     let mut result = content.to_string();
-    for (start, end) in final_ranges {
+    // Replace back to front so earlier offsets stay valid
+    for &(start, end) in ranges.iter().rev() {
         // Safety check bounds
         if start >= result.len() || end > result.len() {
             continue;
         }
 
-        // We replace with `{ /* ... */ }`.
-        // But the node includes braces?
-        // Rust `(block)` includes `{}`.
-        // JS `(statement_block)` includes `{}`.
-        // So we replace the whole range.
-
-        result.replace_range(start..end, "{ /* ... */ }");
+        // Body nodes include their braces (Rust `block`, JS `statement_block`),
+        // so the whole range is replaced by the language's placeholder.
+        result.replace_range(start..end, placeholder);
     }
 
     result
@@ -175,4 +136,36 @@ impl Foo {
         assert!(compressed.contains("fn bar(&self) { /* ... */ }"));
         assert!(!compressed.contains("println"));
     }
+
+    #[test]
+    fn test_python_compression_keeps_valid_syntax() {
+        let code = "class Repo:\n    def load(self, id):\n        row = self.db.get(id)\n        return row\n";
+        let compressed = compress_ast(code, "py");
+        assert_eq!(
+            compressed,
+            "class Repo:\n    def load(self, id):\n        ...\n"
+        );
+    }
+
+    #[test]
+    fn test_java_compression() {
+        let code = r#"
+public class Service {
+    private final Repo repo;
+
+    public Service(Repo repo) {
+        this.repo = repo;
+    }
+
+    public User find(long id) {
+        return repo.load(id).orElseThrow(() -> { throw new NotFound(id); });
+    }
+}
+"#;
+        let compressed = compress_ast(code, "java");
+        assert!(compressed.contains("private final Repo repo;"));
+        assert!(compressed.contains("public Service(Repo repo) { /* ... */ }"));
+        assert!(compressed.contains("public User find(long id) { /* ... */ }"));
+        assert!(!compressed.contains("orElseThrow"));
+    }
 }
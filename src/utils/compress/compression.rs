@@ -86,6 +86,11 @@ pub fn compress_standard(content: &str) -> String {
 
 /// Aggressive compression: Replace function bodies with placeholders
 pub fn compress_aggressive(content: &str, extension: &str) -> String {
+    // Components also collapse their template and style blocks
+    if crate::utils::language::for_extension(extension).is_some_and(|l| l.blocks.is_some()) {
+        return crate::utils::source::SourceAnalysis::analyze(content, extension)
+            .compress_aggressive(content);
+    }
     // Use AST-aware compression if available for this language
     super::ast::compress_ast(content, extension)
 }
@@ -41,6 +41,7 @@ impl ContextTier {
     }
 
     /// Parse from string
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "summary" | "s" | "1" => Some(ContextTier::Summary),
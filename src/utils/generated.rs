@@ -0,0 +1,178 @@
+//! Generated-file detection
+//!
+//! Code generators stamp a banner comment at the top of their output. Discovery reads the
+//! first lines of each file and, depending on the banner and `[generated]` in `abyss.toml`,
+//! drops the file or forces it down to a summary.
+
+use std::io::Read;
+use std::path::Path;
+
+/// Lines at the top of a file searched for a banner
+const HEADER_LINES: usize = 10;
+/// Bytes read from each file to find them
+const HEADER_BYTES: u64 = 2048;
+
+/// Comment leaders a banner line starts with
+const COMMENT_LEADERS: &[&str] = &[
+    "//", "#", "/*", "*", "--", "<!--", ";", "%", "{-", "\"\"\"", "'''",
+];
+
+/// Banners of protoc and its plugins, prost, tonic and friends
+const PROTOBUF_BANNERS: &[&str] = &[
+    "generated by the protocol buffer compiler",
+    "protoc-gen-",
+    "generated by prost-build",
+    "generated by tonic-build",
+];
+
+/// The kind of banner that marks a file as generated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Marker {
+    /// Go's `Code generated <tool>; DO NOT EDIT.` line
+    Go,
+    /// protoc / prost banners
+    Protobuf,
+    /// An `@generated` banner (Meta, Rust, JS tooling)
+    Tagged,
+    /// Any other `DO NOT EDIT` notice
+    DoNotEdit,
+}
+
+impl Marker {
+    /// Key of this marker in the `[generated]` table and `--generated`
+    pub fn key(self) -> &'static str {
+        match self {
+            Marker::Go => "go",
+            Marker::Protobuf => "protobuf",
+            Marker::Tagged => "tagged",
+            Marker::DoNotEdit => "do_not_edit",
+        }
+    }
+}
+
+impl std::fmt::Display for Marker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Marker::Go => write!(f, "'Code generated ... DO NOT EDIT.'"),
+            Marker::Protobuf => write!(f, "protoc banner"),
+            Marker::Tagged => write!(f, "'@generated'"),
+            Marker::DoNotEdit => write!(f, "'DO NOT EDIT'"),
+        }
+    }
+}
+
+/// The generated-file banner in the first lines of `content`, if any.
+pub fn detect(content: &str) -> Option<Marker> {
+    let comments: Vec<&str> = content
+        .lines()
+        .take(HEADER_LINES)
+        .map(str::trim)
+        .filter(|line| COMMENT_LEADERS.iter().any(|l| line.starts_with(l)))
+        .collect();
+
+    // Most specific first: Go and protoc banners also say DO NOT EDIT
+    let is_go = |line: &&str| {
+        line.strip_prefix("//")
+            .map(str::trim_start)
+            .is_some_and(|rest| {
+                rest.starts_with("Code generated ") && rest.ends_with("DO NOT EDIT.")
+            })
+    };
+    let is_protobuf = |line: &&str| {
+        let lower = line.to_lowercase();
+        PROTOBUF_BANNERS.iter().any(|b| lower.contains(b))
+    };
+
+    if comments.iter().any(is_go) {
+        Some(Marker::Go)
+    } else if comments.iter().any(is_protobuf) {
+        Some(Marker::Protobuf)
+    } else if comments.iter().any(|line| has_tag(line)) {
+        Some(Marker::Tagged)
+    } else if comments.iter().any(|line| line.contains("DO NOT EDIT")) {
+        Some(Marker::DoNotEdit)
+    } else {
+        None
+    }
+}
+
+/// Reads the start of `path` and looks for a generated-file banner.
+pub fn sniff(path: &Path) -> Option<Marker> {
+    let mut header = Vec::new();
+    std::fs::File::open(path)
+        .ok()?
+        .take(HEADER_BYTES)
+        .read_to_end(&mut header)
+        .ok()?;
+    if crate::utils::binary::is_binary(&header) {
+        return None;
+    }
+    detect(&String::from_utf8_lossy(&header))
+}
+
+/// An `@generated` banner: the tag opening the comment, or next to a `DO NOT EDIT` notice.
+///
+/// A tag mentioned in prose (`files marked @generated are skipped`) is not a banner.
+fn has_tag(line: &str) -> bool {
+    let mut text = line;
+    while let Some(rest) = COMMENT_LEADERS.iter().find_map(|l| text.strip_prefix(l)) {
+        text = rest.trim_start();
+    }
+    let tags: Vec<usize> = text
+        .match_indices("@generated")
+        .filter(|(i, tag)| {
+            let before = text[..*i].chars().next_back();
+            let after = text[i + tag.len()..].chars().next();
+            before.is_none_or(char::is_whitespace)
+                && after.is_none_or(|c| c.is_whitespace() || matches!(c, '.' | ',' | ':'))
+        })
+        .map(|(i, _)| i)
+        .collect();
+    tags.first() == Some(&0) || (!tags.is_empty() && text.to_lowercase().contains("do not edit"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_markers() {
+        let go = "// Code generated by protoc-gen-go. DO NOT EDIT.\n// source: api.proto\n\npackage api\n";
+        assert_eq!(detect(go), Some(Marker::Go));
+        let stringer =
+            "// Code generated by \"stringer -type=Kind\"; DO NOT EDIT.\n\npackage kind\n";
+        assert_eq!(detect(stringer), Some(Marker::Go));
+
+        let protoc = "# -*- coding: utf-8 -*-\n# Generated by the protocol buffer compiler.  DO NOT EDIT!\n# source: api.proto\n";
+        assert_eq!(detect(protoc), Some(Marker::Protobuf));
+        let prost = "// This file is @generated by prost-build.\npub struct Ping {}\n";
+        assert_eq!(detect(prost), Some(Marker::Protobuf));
+
+        assert_eq!(
+            detect("/**\n * @generated\n */\nexport {};\n"),
+            Some(Marker::Tagged)
+        );
+        assert_eq!(
+            detect("// @generated SignedSource<<8f3a>>\nmodule.exports = {};\n"),
+            Some(Marker::Tagged)
+        );
+        assert_eq!(
+            detect("# This file is @generated, do not edit by hand\nkey: 1\n"),
+            Some(Marker::Tagged)
+        );
+        assert_eq!(
+            detect("# Autogenerated from schema.json, DO NOT EDIT\nkey: 1\n"),
+            Some(Marker::DoNotEdit)
+        );
+
+        // Mentions in prose or code are not banners
+        assert_eq!(detect("//! Skips files tagged `@generated`.\n"), None);
+        assert_eq!(
+            detect("// Files marked @generated are left to the codegen step\nfn f() {}\n"),
+            None
+        );
+        assert_eq!(detect("let s = \"DO NOT EDIT\";\n"), None);
+        let late = format!("{}// DO NOT EDIT\n", "fn f() {}\n".repeat(HEADER_LINES));
+        assert_eq!(detect(&late), None);
+    }
+}
@@ -0,0 +1,144 @@
+//! CODEOWNERS parsing
+//!
+//! Reads the `CODEOWNERS` file GitHub and GitLab use to assign reviewers, so output can be
+//! filtered to, annotated with and grouped by the teams that own each file.
+
+use std::path::Path;
+
+/// Locations checked for a CODEOWNERS file, in GitHub's order
+const LOCATIONS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+struct OwnerRule {
+    pattern: glob::Pattern,
+    /// `dir/` only matches files below the directory
+    dir_only: bool,
+    /// `dir/*` matches direct children, not nested files
+    direct_children: bool,
+    owners: Vec<String>,
+}
+
+/// Parsed CODEOWNERS rules; the last matching rule decides a file's owners.
+#[derive(Default)]
+pub struct CodeOwners {
+    rules: Vec<OwnerRule>,
+}
+
+impl CodeOwners {
+    /// Loads the first CODEOWNERS file found in the repository, if any.
+    pub fn load(repo_root: &Path) -> Option<Self> {
+        LOCATIONS.iter().find_map(|location| {
+            let text = std::fs::read_to_string(repo_root.join(location)).ok()?;
+            Some(Self::parse(&text))
+        })
+    }
+
+    /// Parses CODEOWNERS text. Invalid patterns are skipped.
+    pub fn parse(text: &str) -> Self {
+        let mut rules = Vec::new();
+        for line in text.lines() {
+            let line = line.trim();
+            // GitLab section headers (`[Docs]`, `^[Optional]`) carry no paths
+            if line.is_empty()
+                || line.starts_with('#')
+                || line.starts_with('[')
+                || line.starts_with("^[")
+            {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let Some(raw) = fields.next() else {
+                continue;
+            };
+            let owners: Vec<String> = fields
+                .take_while(|f| !f.starts_with('#'))
+                .map(str::to_string)
+                .collect();
+
+            let dir_only = raw.ends_with('/');
+            let trimmed = raw.trim_end_matches('/');
+            // A leading or inner slash anchors the pattern to the repository root
+            let anchored = trimmed.contains('/');
+            let trimmed = trimmed.trim_start_matches('/');
+            let glob = if anchored || trimmed.starts_with("**") {
+                trimmed.to_string()
+            } else {
+                format!("**/{}", trimmed)
+            };
+            let Ok(pattern) = glob::Pattern::new(&glob) else {
+                continue;
+            };
+            rules.push(OwnerRule {
+                pattern,
+                dir_only,
+                direct_children: trimmed.ends_with("/*"),
+                owners,
+            });
+        }
+        Self { rules }
+    }
+
+    /// Owners of a file, given its path relative to the repository root.
+    pub fn owners(&self, relative: &Path) -> &[String] {
+        let options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| {
+                if rule.direct_children {
+                    return rule.pattern.matches_path_with(relative, options);
+                }
+                // A matching directory covers everything below it
+                let mut candidates = relative.ancestors().filter(|a| !a.as_os_str().is_empty());
+                if rule.dir_only {
+                    candidates.next();
+                }
+                candidates.any(|c| rule.pattern.matches_path_with(c, options))
+            })
+            .map(|rule| rule.owners.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Whether `owner` (`@org/team`, `@user` or an email; the `@` is optional) owns the file.
+    pub fn is_owned_by(&self, relative: &Path, owner: &str) -> bool {
+        let wanted = owner.trim_start_matches('@');
+        self.owners(relative)
+            .iter()
+            .any(|o| o.trim_start_matches('@').eq_ignore_ascii_case(wanted))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codeowners_matching() {
+        let owners = CodeOwners::parse(
+            "# Default owners\n\
+             *       @org/core\n\
+             *.js    @org/frontend  # inline comment\n\
+             /build/ @org/infra\n\
+             docs/*  @org/docs\n\
+             apps/   @org/apps @alice\n\
+             /apps/legacy/vendor.js\n",
+        );
+        let of = |p: &str| owners.owners(Path::new(p)).join(" ");
+
+        assert_eq!(of("README.md"), "@org/core");
+        assert_eq!(of("src/ui/app.js"), "@org/frontend");
+        assert_eq!(of("build/ci/deploy.sh"), "@org/infra");
+        assert_eq!(of("src/build/main.rs"), "@org/core");
+        assert_eq!(of("docs/intro.md"), "@org/docs");
+        assert_eq!(of("docs/api/ref.md"), "@org/core");
+        assert_eq!(of("web/apps/main.js"), "@org/apps @alice");
+        // A rule without owners un-assigns the file
+        assert_eq!(of("apps/legacy/vendor.js"), "");
+
+        assert!(owners.is_owned_by(Path::new("apps/x.rs"), "alice"));
+        assert!(owners.is_owned_by(Path::new("apps/x.rs"), "@ORG/apps"));
+        assert!(!owners.is_owned_by(Path::new("apps/x.rs"), "@org/core"));
+    }
+}
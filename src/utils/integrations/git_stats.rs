@@ -1,7 +1,10 @@
 use git2::Repository;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
+/// Commits walked for churn and recency
+const CHURN_COMMIT_LIMIT: usize = 1000;
+
 #[derive(Debug, Clone, Default)]
 pub struct GitStats {
     pub last_modified: u64,
@@ -9,6 +12,64 @@ pub struct GitStats {
     pub churn_score: u32, // Number of commits touching this file
 }
 
+impl GitStats {
+    /// Recency in (0, 1]: 1 for a file changed at `now`, halving every `half_life_days`.
+    /// Zero for files without history or with a non-positive half-life.
+    pub fn recency(&self, now: u64, half_life_days: f64) -> f64 {
+        if self.last_modified == 0 || half_life_days <= 0.0 {
+            return 0.0;
+        }
+        let age_days = now.saturating_sub(self.last_modified) as f64 / 86_400.0;
+        0.5f64.powf(age_days / half_life_days)
+    }
+}
+
+/// Whether a commit author's name or email contains `needle` (lowercase)
+fn by_author(name: &str, email: &str, needle: &str) -> bool {
+    name.to_lowercase().contains(needle) || email.to_lowercase().contains(needle)
+}
+
+/// Current time in seconds since the epoch
+pub fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Parses a `--since` value into epoch seconds: a date (`2024-05-01`), an RFC 3339
+/// timestamp, or an age relative to `now` (`14d`, `2w`, `3 months`, `1 year ago`).
+pub fn parse_since(value: &str, now: u64) -> anyhow::Result<u64> {
+    let value = value.trim();
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let midnight = date.and_time(chrono::NaiveTime::MIN).and_utc();
+        return Ok(midnight.timestamp().max(0) as u64);
+    }
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(time.timestamp().max(0) as u64);
+    }
+
+    let relative = value.trim_end_matches("ago").trim();
+    let split = relative
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(relative.len());
+    let (count, unit) = relative.split_at(split);
+    let days_per_unit = match unit.trim().trim_end_matches('s') {
+        "d" | "day" => 1,
+        "w" | "week" => 7,
+        "month" => 30,
+        "y" | "year" => 365,
+        _ => anyhow::bail!(
+            "Invalid date '{}': use YYYY-MM-DD or an age like 14d, 2w, 3 months",
+            value
+        ),
+    };
+    let count: u64 = count
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid date '{}': missing a number", value))?;
+    Ok(now.saturating_sub(count * days_per_unit * 86_400))
+}
+
 /// Collects git statistics for files in the repository.
 /// Returns an empty map if the directory is not a git repository.
 pub fn get_git_stats(repo_root: &Path) -> HashMap<PathBuf, GitStats> {
@@ -34,11 +95,9 @@ pub fn get_git_stats(repo_root: &Path) -> HashMap<PathBuf, GitStats> {
     // Sort by time to get most recent first
     revwalk.set_sorting(git2::Sort::TIME).ok();
 
-    // Cap at 1000 for performance safety.
-    let commit_limit = 1000;
-
+    // Capped for performance safety.
     for (i, oid) in revwalk.enumerate() {
-        if i >= commit_limit {
+        if i >= CHURN_COMMIT_LIMIT {
             break;
         }
 
@@ -53,51 +112,95 @@ pub fn get_git_stats(repo_root: &Path) -> HashMap<PathBuf, GitStats> {
             Err(_) => continue,
         };
 
-        // Compare with parent to identify modified files.
-        let tree = match commit.tree() {
-            Ok(t) => t,
-            Err(_) => continue,
-        };
+        let commit_time = commit.time().seconds().max(0) as u64;
+        let signature = commit.author();
+        let author = signature.name().unwrap_or("Unknown");
 
-        let parent_tree = commit.parent(0).and_then(|p| p.tree()).ok();
+        for_each_changed(&repo, &commit, |path| {
+            let full_path = repo_root.join(path);
+            let entry = stats_map.entry(full_path).or_insert_with(GitStats::default);
 
-        let diff = match repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None) {
-            Ok(d) => d,
-            Err(_) => continue,
-        };
-
-        let commit_time = commit.time().seconds();
-        let author = commit.author().name().unwrap_or("Unknown").to_string();
-
-        let _ = diff.foreach(
-            &mut |delta, _progress| {
-                if let Some(path) = delta.new_file().path() {
-                    let full_path = repo_root.join(path);
-                    let entry = stats_map.entry(full_path).or_insert(GitStats {
-                        last_modified: 0,
-                        author: String::new(),
-                        churn_score: 0,
-                    });
-
-                    // Commits are walked in reverse chronological order;
-                    // first occurrence is the latest modification.
-                    if entry.last_modified == 0 {
-                        entry.last_modified = commit_time as u64;
-                        entry.author = author.clone();
-                    }
-                    entry.churn_score += 1;
-                }
-                true
-            },
-            None,
-            None,
-            None,
-        );
+            // Commits are walked in reverse chronological order;
+            // first occurrence is the latest modification.
+            if entry.last_modified == 0 {
+                entry.last_modified = commit_time;
+                entry.author = author.to_string();
+            }
+            entry.churn_score += 1;
+        });
     }
 
     stats_map
 }
 
+/// Files touched by a commit since `since` (epoch seconds) whose author's name or email
+/// contains `author` (case-insensitive), as absolute paths.
+///
+/// Unlike the churn walk this one has no commit cap: it walks the whole history, or back
+/// to `since`. Empty if the directory is not a git repository.
+pub fn touched_files(
+    repo_root: &Path,
+    since: Option<u64>,
+    author: Option<&str>,
+) -> HashSet<PathBuf> {
+    let mut touched = HashSet::new();
+    let Ok(repo) = Repository::open(repo_root) else {
+        return touched;
+    };
+    let Ok(mut revwalk) = repo.revwalk() else {
+        return touched;
+    };
+    if revwalk.push_head().is_err() {
+        return touched;
+    }
+    revwalk.set_sorting(git2::Sort::TIME).ok();
+    let author = author.map(str::to_lowercase);
+
+    for oid in revwalk.flatten() {
+        let Ok(commit) = repo.find_commit(oid) else {
+            continue;
+        };
+        // Newest first, so everything after this is older too
+        if since.is_some_and(|since| (commit.time().seconds().max(0) as u64) < since) {
+            break;
+        }
+        if let Some(author) = &author {
+            let signature = commit.author();
+            let name = signature.name().unwrap_or("Unknown");
+            if !by_author(name, signature.email().unwrap_or(""), author) {
+                continue;
+            }
+        }
+        for_each_changed(&repo, &commit, |path| {
+            touched.insert(repo_root.join(path));
+        });
+    }
+    touched
+}
+
+/// Calls `f` with each path (relative to the repository) `commit` added or modified
+/// relative to its first parent.
+fn for_each_changed(repo: &Repository, commit: &git2::Commit, mut f: impl FnMut(&Path)) {
+    let Ok(tree) = commit.tree() else {
+        return;
+    };
+    let parent_tree = commit.parent(0).and_then(|p| p.tree()).ok();
+    let Ok(diff) = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None) else {
+        return;
+    };
+    let _ = diff.foreach(
+        &mut |delta, _progress| {
+            if let Some(path) = delta.new_file().path() {
+                f(path);
+            }
+            true
+        },
+        None,
+        None,
+        None,
+    );
+}
+
 /// Returns a list of files changed between HEAD and the target reference (e.g. "main", "HEAD~1").
 /// Returns paths relative to the repo root.
 pub fn get_diff_files(repo_path: &Path, target_ref: &str) -> Option<Vec<String>> {
@@ -253,4 +356,118 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_recency_and_touched_files() -> anyhow::Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let repo_root = temp_dir.path();
+        let repo = Repository::init(repo_root)?;
+        let day = 86_400;
+        let now = 1_700_000_000;
+
+        // alice.rs: Alice, 40 days ago. shared.rs: Alice 40 days ago, then Bob 2 days ago
+        let commits = [
+            (
+                "Alice",
+                "alice@example.com",
+                now - 40 * day,
+                vec!["alice.rs", "shared.rs"],
+            ),
+            ("Bob", "bob@example.com", now - 2 * day, vec!["shared.rs"]),
+        ];
+        let mut index = repo.index()?;
+        for (name, email, time, files) in commits {
+            for file in &files {
+                std::fs::write(repo_root.join(file), format!("// {} {}", name, time))?;
+                index.add_path(Path::new(file))?;
+            }
+            let tree = repo.find_tree(index.write_tree()?)?;
+            let signature = git2::Signature::new(name, email, &git2::Time::new(time as i64, 0))?;
+            let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            repo.commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                "change",
+                &tree,
+                &parents,
+            )?;
+        }
+
+        let stats = get_git_stats(repo_root);
+        let alice = &stats[&repo_root.join("alice.rs")];
+        let shared = &stats[&repo_root.join("shared.rs")];
+        assert_eq!(shared.author, "Bob");
+        assert_eq!(shared.churn_score, 2);
+
+        assert!((shared.recency(now, 2.0) - 0.5).abs() < 1e-9);
+        assert!(alice.recency(now, 30.0) < shared.recency(now, 30.0));
+        assert_eq!(alice.recency(now, 0.0), 0.0);
+
+        let (alice, shared) = (repo_root.join("alice.rs"), repo_root.join("shared.rs"));
+        let two_weeks = parse_since("2 weeks ago", now)?;
+        let recent = touched_files(repo_root, Some(two_weeks), None);
+        assert!(!recent.contains(&alice));
+        assert!(recent.contains(&shared));
+        assert!(touched_files(repo_root, None, Some("alice")).contains(&shared));
+        assert!(touched_files(repo_root, Some(two_weeks), Some("alice")).is_empty());
+        let bob = touched_files(repo_root, Some(two_weeks), Some("BOB@EXAMPLE"));
+        assert!(bob.contains(&shared));
+        Ok(())
+    }
+
+    #[test]
+    fn test_touched_files_past_churn_limit() -> anyhow::Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let repo_root = temp_dir.path();
+        let repo = Repository::init(repo_root)?;
+        let now = 1_700_000_000;
+
+        // old.rs is only touched by the first commit, which the churn walk never reaches
+        let mut index = repo.index()?;
+        for i in 0..=CHURN_COMMIT_LIMIT {
+            let (file, name) = if i == 0 {
+                ("old.rs", "Carol")
+            } else {
+                ("busy.rs", "Dave")
+            };
+            std::fs::write(repo_root.join(file), format!("// {}", i))?;
+            index.add_path(Path::new(file))?;
+            let tree = repo.find_tree(index.write_tree()?)?;
+            let time = git2::Time::new(now - (CHURN_COMMIT_LIMIT - i) as i64 * 60, 0);
+            let signature = git2::Signature::new(name, "dev@example.com", &time)?;
+            let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            repo.commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                "change",
+                &tree,
+                &parents,
+            )?;
+        }
+
+        let old = repo_root.join("old.rs");
+        assert!(!get_git_stats(repo_root).contains_key(&old));
+        assert!(touched_files(repo_root, None, Some("carol")).contains(&old));
+        assert!(touched_files(repo_root, Some(0), None).contains(&old));
+        let recent = touched_files(repo_root, Some(now as u64 - 3600), None);
+        assert!(!recent.contains(&old));
+        assert!(recent.contains(&repo_root.join("busy.rs")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now = 1_700_000_000;
+        assert_eq!(parse_since("14d", now).unwrap(), now - 14 * 86_400);
+        assert_eq!(parse_since("2w", now).unwrap(), now - 14 * 86_400);
+        assert_eq!(parse_since("3 months", now).unwrap(), now - 90 * 86_400);
+        assert_eq!(parse_since("1970-01-02", now).unwrap(), 86_400);
+        assert_eq!(parse_since("1970-01-01T01:00:00Z", now).unwrap(), 3_600);
+        assert!(parse_since("yesterday", now).is_err());
+        assert!(parse_since("weeks", now).is_err());
+    }
 }
@@ -1,9 +1,10 @@
 //! Integration utilities for external services
 //!
-//! Contains Git integration, file watching, workspace support, caching, and bundling.
+//! Contains Git integration, CODEOWNERS, file watching, workspace support, caching, and bundling.
 
 pub mod bundle;
 pub mod cache;
+pub mod codeowners;
 pub mod diff_explainer;
 pub mod git_stats;
 pub mod watch;
@@ -11,6 +12,7 @@ pub mod workspace;
 
 // Re-export commonly used items
 pub use cache::Cache;
+pub use codeowners::CodeOwners;
 pub use diff_explainer::DiffExplainer;
 pub use git_stats::{get_diff_files, get_git_stats};
 pub use watch::{Debouncer, FileWatcher, WatchEvent};
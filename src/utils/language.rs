@@ -0,0 +1,486 @@
+//! Language registry
+//!
+//! Each supported language is described once by a `LanguageSupport` entry: its tree-sitter
+//! grammar, file extensions, the queries used for imports, concepts, summaries and body
+//! elision, the placeholder that replaces elided bodies, and the import resolver.
+//! Query strings can be overridden (or added) per language from `[languages.<name>]`
+//! tables in `abyss.toml`.
+
+use crate::config::LanguageOverride;
+use crate::utils::dependencies as deps;
+use lazy_static::lazy_static;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::{Arc, RwLock};
+use tree_sitter::{Language, Parser, Query, Tree};
+
+/// Maps an import string to the repository files it refers to.
+pub type ImportResolver = fn(&str, &Path, &Path) -> Vec<PathBuf>;
+
+/// Turns one `@import` capture (node, node text, file content) into import strings.
+pub type ImportCapture = fn(tree_sitter::Node, &str, &str) -> Vec<String>;
+
+lazy_static! {
+    static ref REGISTRY: RwLock<Arc<Vec<Arc<LanguageSupport>>>> = RwLock::new(Arc::new(
+        build(&BTreeMap::new()).into_iter().map(Arc::new).collect()
+    ));
+}
+
+type QueryCache = HashMap<&'static str, HashMap<String, Option<Rc<Query>>>>;
+
+thread_local! {
+    // Parsers and compiled queries are reused per worker thread instead of per call
+    static PARSER: RefCell<Parser> = RefCell::new(Parser::new());
+    static QUERIES: RefCell<QueryCache> = RefCell::new(HashMap::new());
+}
+
+/// Everything abyss knows about handling one language.
+#[derive(Debug, Clone)]
+pub struct LanguageSupport {
+    /// Registry key, also used for `[languages.<name>]` in `abyss.toml`
+    pub name: &'static str,
+    /// File extensions (without the dot) handled by this entry
+    pub extensions: Vec<String>,
+    /// Tree-sitter grammar; `None` for languages only handled by line scanning
+    pub grammar: Option<fn() -> Language>,
+    /// Query whose `@import` captures are the file's imports
+    pub imports: Option<String>,
+    /// Query whose `@name` captures are the concepts listed in the file header
+    pub concepts: Option<String>,
+    /// Query capturing `@struct`, `@enum`, `@trait`, `@interface`, `@class`, `@type`, `@impl`, `@fn` for summaries
+    pub symbols: Option<String>,
+    /// Query whose `@body` captures are elided by AST compression
+    pub bodies: Option<String>,
+    /// Replacement text for an elided body
+    pub placeholder: String,
+    /// Post-processing of `@import` captures (use-tree expansion, quote stripping, ...)
+    pub import_capture: ImportCapture,
+    /// Import extraction for languages without a grammar
+    pub line_imports: Option<fn(&str) -> Vec<String>>,
+    /// Splits container formats (single-file components) into embedded script/markup blocks
+    pub blocks: Option<fn(&str) -> Vec<crate::utils::sfc::Block>>,
+    /// Import resolver, if the language has one
+    pub resolver: Option<ImportResolver>,
+}
+
+impl LanguageSupport {
+    fn new(name: &'static str, extensions: &[&str], grammar: fn() -> Language) -> Self {
+        Self {
+            grammar: Some(grammar),
+            ..Self::without_grammar(name, extensions)
+        }
+    }
+
+    fn without_grammar(name: &'static str, extensions: &[&str]) -> Self {
+        Self {
+            name,
+            extensions: extensions.iter().map(|e| e.to_string()).collect(),
+            grammar: None,
+            imports: None,
+            concepts: None,
+            symbols: None,
+            bodies: None,
+            placeholder: "{ /* ... */ }".to_string(),
+            import_capture: deps::quoted_import,
+            line_imports: None,
+            blocks: None,
+            resolver: None,
+        }
+    }
+
+    /// Returns the tree-sitter language, if this entry has a grammar.
+    pub fn language(&self) -> Option<Language> {
+        self.grammar.map(|grammar| grammar())
+    }
+
+    /// Parses `content` with this language's grammar, using the thread's parser.
+    pub fn parse(&self, content: &str) -> Option<Tree> {
+        let language = self.language()?;
+        PARSER.with(|parser| {
+            let mut parser = parser.borrow_mut();
+            parser.set_language(&language).ok()?;
+            parser.parse(content, None)
+        })
+    }
+
+    /// Compiles a query against this language's grammar.
+    pub fn query(&self, source: &str) -> Option<Query> {
+        Query::new(&self.language()?, source).ok()
+    }
+
+    /// Returns a compiled query from the thread-local cache, compiling it on first use.
+    pub fn cached_query(&self, source: &str) -> Option<Rc<Query>> {
+        QUERIES.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            let queries = cache.entry(self.name).or_default();
+            if let Some(query) = queries.get(source) {
+                return query.clone();
+            }
+            let query = self.query(source).map(Rc::new);
+            queries.insert(source.to_string(), query.clone());
+            query
+        })
+    }
+}
+
+/// Looks up the language handling a file extension.
+pub fn for_extension(extension: &str) -> Option<Arc<LanguageSupport>> {
+    registry()
+        .iter()
+        .find(|l| l.extensions.iter().any(|e| e == extension))
+        .cloned()
+}
+
+/// Looks up a language by registry name (`rust`, `typescript`, ...).
+pub fn by_name(name: &str) -> Option<Arc<LanguageSupport>> {
+    registry().iter().find(|l| l.name == name).cloned()
+}
+
+/// All registered languages.
+pub fn registry() -> Arc<Vec<Arc<LanguageSupport>>> {
+    REGISTRY.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Rebuilds the registry with the `[languages]` overrides from the configuration.
+pub fn configure(overrides: &BTreeMap<String, LanguageOverride>) {
+    let languages = build(overrides).into_iter().map(Arc::new).collect();
+    *REGISTRY.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(languages);
+}
+
+/// Builds the built-in table and applies overrides. Invalid queries are reported and ignored.
+pub fn build(overrides: &BTreeMap<String, LanguageOverride>) -> Vec<LanguageSupport> {
+    let mut languages = builtin();
+
+    for (name, overrides) in overrides {
+        let Some(language) = languages.iter_mut().find(|l| l.name == name) else {
+            eprintln!("Warning: Unknown language '{}' in abyss.toml", name);
+            continue;
+        };
+
+        let grammar = language.language();
+        let slots = [
+            ("imports", &overrides.imports, &mut language.imports),
+            ("concepts", &overrides.concepts, &mut language.concepts),
+            ("symbols", &overrides.symbols, &mut language.symbols),
+            ("bodies", &overrides.bodies, &mut language.bodies),
+        ];
+        for (kind, source, slot) in slots {
+            let Some(source) = source else { continue };
+            match grammar.as_ref().map(|g| Query::new(g, source)) {
+                Some(Ok(_)) => *slot = Some(source.clone()),
+                Some(Err(e)) => eprintln!(
+                    "Warning: Invalid {} query for '{}' in abyss.toml: {}",
+                    kind, name, e
+                ),
+                None => eprintln!(
+                    "Warning: '{}' has no tree-sitter grammar; {} query ignored",
+                    name, kind
+                ),
+            }
+        }
+
+        if let Some(placeholder) = &overrides.placeholder {
+            language.placeholder = placeholder.clone();
+        }
+        for extension in &overrides.extensions {
+            let extension = extension.trim_start_matches('.').to_string();
+            if !language.extensions.contains(&extension) {
+                language.extensions.push(extension);
+            }
+        }
+    }
+
+    // Extensions claimed by an override take precedence over their built-in owner
+    for (name, overrides) in overrides {
+        for extension in &overrides.extensions {
+            let extension = extension.trim_start_matches('.');
+            for language in languages.iter_mut().filter(|l| l.name != name) {
+                language.extensions.retain(|e| e != extension);
+            }
+        }
+    }
+
+    languages
+}
+
+const JS_IMPORTS: &str = r#"
+    (import_statement source: (string) @import)
+    (export_statement source: (string) @import)
+    (call_expression function: (identifier) @func arguments: (arguments (string) @import) (#eq? @func "require"))
+"#;
+
+const JS_BODIES: &str = r#"
+    (function_declaration body: (statement_block) @body)
+    (method_definition body: (statement_block) @body)
+    (arrow_function body: (statement_block) @body)
+"#;
+
+const TS_CONCEPTS: &str = r#"
+    (class_declaration name: (_) @name)
+    (interface_declaration name: (_) @name)
+    (function_declaration name: (_) @name)
+    (variable_declarator name: (_) @name value: (arrow_function))
+"#;
+
+const TS_SYMBOLS: &str = r#"
+    (class_declaration name: (_) @class)
+    (function_declaration name: (_) @fn)
+    (interface_declaration name: (_) @interface)
+    (type_alias_declaration name: (_) @type)
+"#;
+
+const C_INCLUDES: &str = r#"
+    (preproc_include path: (string_literal) @import)
+    (preproc_include path: (system_lib_string) @import)
+"#;
+
+const C_BODIES: &str = r#"
+    (function_definition body: (compound_statement) @body)
+"#;
+
+/// The built-in language table.
+pub fn builtin() -> Vec<LanguageSupport> {
+    let q = |s: &str| Some(s.to_string());
+
+    vec![
+        LanguageSupport {
+            imports: q(r#"
+                (use_declaration argument: (_) @import)
+                (mod_item name: (_) @import !body)
+                (macro_invocation
+                    macro: (identifier) @macro
+                    (#match? @macro "^(include|include_str|include_bytes)$")) @import
+                (call_expression function: (_) @func (#match? @func "compile_protos$")) @import
+            "#),
+            concepts: q(r#"
+                (struct_item name: (_) @name)
+                (enum_item name: (_) @name)
+                (trait_item name: (_) @name)
+                (impl_item type: (_) @name)
+                (function_item name: (_) @name)
+                (mod_item name: (_) @name)
+            "#),
+            symbols: q(r#"
+                (struct_item name: (_) @struct)
+                (enum_item name: (_) @enum)
+                (trait_item name: (_) @trait)
+                (function_item name: (_) @fn)
+                (impl_item type: (_) @impl)
+            "#),
+            bodies: q("(function_item body: (block) @body)"),
+            import_capture: deps::rust_import_paths,
+            resolver: Some(|i, f, r| deps::resolve_rust_import(i, f, r).into_iter().collect()),
+            ..LanguageSupport::new("rust", &["rs"], || tree_sitter_rust::LANGUAGE.into())
+        },
+        LanguageSupport {
+            imports: q(r#"
+                (import_statement name: (_) @import)
+                (import_from_statement module_name: (_) @import)
+            "#),
+            concepts: q(r#"
+                (class_definition name: (_) @name)
+                (function_definition name: (_) @name)
+            "#),
+            symbols: q(r#"
+                (class_definition name: (_) @class)
+                (function_definition name: (_) @fn)
+            "#),
+            bodies: q("(function_definition body: (block) @body)"),
+            // The body starts after the colon and indentation, so an ellipsis stays valid Python
+            placeholder: "...".to_string(),
+            import_capture: deps::python_import_paths,
+            resolver: Some(|i, f, r| deps::resolve_python_import(i, f, r).into_iter().collect()),
+            ..LanguageSupport::new("python", &["py", "ipynb"], || {
+                tree_sitter_python::LANGUAGE.into()
+            })
+        },
+        LanguageSupport {
+            imports: q(JS_IMPORTS),
+            concepts: q(r#"
+                (class_declaration name: (_) @name)
+                (function_declaration name: (_) @name)
+                (variable_declarator name: (_) @name value: (arrow_function))
+            "#),
+            symbols: q(r#"
+                (class_declaration name: (_) @class)
+                (function_declaration name: (_) @fn)
+            "#),
+            bodies: q(JS_BODIES),
+            resolver: Some(|i, f, r| deps::resolve_js_import(i, f, r).into_iter().collect()),
+            ..LanguageSupport::new("javascript", &["js", "jsx", "mjs", "cjs"], || {
+                tree_sitter_javascript::LANGUAGE.into()
+            })
+        },
+        LanguageSupport {
+            imports: q(JS_IMPORTS),
+            concepts: q(TS_CONCEPTS),
+            symbols: q(TS_SYMBOLS),
+            bodies: q(JS_BODIES),
+            resolver: Some(|i, f, r| deps::resolve_js_import(i, f, r).into_iter().collect()),
+            ..LanguageSupport::new("typescript", &["ts", "mts", "cts"], || {
+                tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()
+            })
+        },
+        LanguageSupport {
+            imports: q(JS_IMPORTS),
+            concepts: q(TS_CONCEPTS),
+            symbols: q(TS_SYMBOLS),
+            bodies: q(JS_BODIES),
+            resolver: Some(|i, f, r| deps::resolve_js_import(i, f, r).into_iter().collect()),
+            ..LanguageSupport::new("tsx", &["tsx"], || {
+                tree_sitter_typescript::LANGUAGE_TSX.into()
+            })
+        },
+        LanguageSupport {
+            imports: q("(import_spec path: (_) @import)"),
+            concepts: q(r#"
+                (type_spec name: (_) @name)
+                (function_declaration name: (_) @name)
+                (method_declaration name: (_) @name)
+            "#),
+            symbols: q(r#"
+                (type_spec name: (_) @type)
+                (function_declaration name: (_) @fn)
+            "#),
+            bodies: q(r#"
+                (function_declaration body: (block) @body)
+                (method_declaration body: (block) @body)
+            "#),
+            resolver: Some(deps::resolve_go_import),
+            ..LanguageSupport::new("go", &["go"], || tree_sitter_go::LANGUAGE.into())
+        },
+        LanguageSupport {
+            imports: q(C_INCLUDES),
+            symbols: q(r#"
+                (struct_specifier name: (_) @struct)
+                (function_definition declarator: (function_declarator declarator: (identifier) @fn))
+            "#),
+            bodies: q(C_BODIES),
+            resolver: Some(|i, f, r| deps::resolve_c_include(i, f, r).into_iter().collect()),
+            ..LanguageSupport::new("c", &["c", "h"], || tree_sitter_c::LANGUAGE.into())
+        },
+        LanguageSupport {
+            imports: q(C_INCLUDES),
+            symbols: q(r#"
+                (class_specifier name: (_) @class)
+                (function_definition declarator: (function_declarator declarator: (identifier) @fn))
+            "#),
+            bodies: q(C_BODIES),
+            resolver: Some(|i, f, r| deps::resolve_c_include(i, f, r).into_iter().collect()),
+            ..LanguageSupport::new("cpp", &["cpp", "hpp", "cc", "cxx"], || {
+                tree_sitter_cpp::LANGUAGE.into()
+            })
+        },
+        LanguageSupport {
+            imports: q(r#"
+                (import_declaration (scoped_identifier) @import)
+                (import_declaration (identifier) @import)
+            "#),
+            concepts: q(r#"
+                (class_declaration name: (_) @name)
+                (interface_declaration name: (_) @name)
+                (enum_declaration name: (_) @name)
+                (record_declaration name: (_) @name)
+                (method_declaration name: (_) @name)
+            "#),
+            symbols: q(r#"
+                (class_declaration name: (_) @class)
+                (record_declaration name: (_) @class)
+                (interface_declaration name: (_) @interface)
+                (enum_declaration name: (_) @enum)
+                (method_declaration name: (_) @fn)
+            "#),
+            bodies: q(r#"
+                (method_declaration body: (block) @body)
+                (constructor_declaration body: (constructor_body) @body)
+            "#),
+            import_capture: deps::java_import_paths,
+            resolver: Some(deps::resolve_jvm_targets),
+            ..LanguageSupport::new("java", &["java"], || tree_sitter_java::LANGUAGE.into())
+        },
+        // Components: script blocks are analysed with the JS/TS entries above
+        LanguageSupport {
+            blocks: Some(crate::utils::sfc::component_blocks),
+            resolver: Some(|i, f, r| deps::resolve_js_import(i, f, r).into_iter().collect()),
+            ..LanguageSupport::without_grammar("vue", &["vue"])
+        },
+        LanguageSupport {
+            blocks: Some(crate::utils::sfc::component_blocks),
+            resolver: Some(|i, f, r| deps::resolve_js_import(i, f, r).into_iter().collect()),
+            ..LanguageSupport::without_grammar("svelte", &["svelte"])
+        },
+        LanguageSupport {
+            blocks: Some(crate::utils::sfc::astro_blocks),
+            resolver: Some(|i, f, r| deps::resolve_js_import(i, f, r).into_iter().collect()),
+            ..LanguageSupport::without_grammar("astro", &["astro"])
+        },
+        // No Kotlin grammar is bundled; its import header is regular enough for a line scan.
+        LanguageSupport {
+            line_imports: Some(deps::extract_kotlin_imports),
+            resolver: Some(deps::resolve_jvm_targets),
+            ..LanguageSupport::without_grammar("kotlin", &["kt", "kts"])
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_queries_compile() {
+        for language in builtin() {
+            for source in [
+                &language.imports,
+                &language.concepts,
+                &language.symbols,
+                &language.bodies,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                assert!(
+                    language.query(source).is_some(),
+                    "{} query does not compile: {}",
+                    language.name,
+                    source
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_overrides() {
+        let mut overrides = BTreeMap::new();
+        overrides.insert(
+            "go".to_string(),
+            LanguageOverride {
+                concepts: Some("(type_spec name: (_) @name)".to_string()),
+                bodies: Some("(not_a_node) @body".to_string()),
+                ..Default::default()
+            },
+        );
+        overrides.insert(
+            "typescript".to_string(),
+            LanguageOverride {
+                extensions: vec![".tsx".to_string()],
+                placeholder: Some("{}".to_string()),
+                ..Default::default()
+            },
+        );
+
+        let languages = build(&overrides);
+        let go = languages.iter().find(|l| l.name == "go").unwrap();
+        assert_eq!(go.concepts.as_deref(), Some("(type_spec name: (_) @name)"));
+        // Invalid queries keep the built-in one
+        assert!(go.bodies.as_deref().unwrap().contains("method_declaration"));
+
+        let ts = languages.iter().find(|l| l.name == "typescript").unwrap();
+        assert!(ts.extensions.contains(&"tsx".to_string()));
+        assert_eq!(ts.placeholder, "{}");
+        let tsx = languages.iter().find(|l| l.name == "tsx").unwrap();
+        assert!(tsx.extensions.is_empty());
+    }
+}
@@ -3,7 +3,7 @@
 //! Organized into logical groups:
 //! - `analysis/` - Code intelligence (graph, ranking, impact, query)
 //! - `compress/` - Content optimization (AST, levels, hierarchy)
-//! - `integrations/` - External services (Git, watch, workspace, cache)
+//! - `integrations/` - External services (Git, CODEOWNERS, watch, workspace, cache)
 
 // Grouped submodules
 pub mod analysis;
@@ -14,9 +14,13 @@ pub mod integrations;
 pub mod abyssignore;
 pub mod binary;
 pub mod clipboard;
+pub mod generated;
 pub mod image;
+pub mod language;
+pub mod notebook;
 pub mod pdf;
 pub mod privacy;
+pub mod sfc;
 pub mod summary;
 pub mod tokens;
 
@@ -30,10 +34,15 @@ pub use analysis::concepts;
 pub use analysis::dependencies;
 pub use analysis::graph;
 pub use analysis::impact;
+pub use analysis::pairing;
 pub use analysis::preflight;
+pub use analysis::project;
 pub use analysis::quality;
 pub use analysis::query;
 pub use analysis::rank;
+pub use analysis::similarity;
+pub use analysis::source;
+pub use analysis::symbols;
 
 // Compression re-exports (from compress/ to avoid naming conflict)
 pub use compress::ast;
@@ -43,6 +52,7 @@ pub use compress::hierarchy;
 // Integration re-exports
 pub use integrations::bundle;
 pub use integrations::cache;
+pub use integrations::codeowners;
 pub use integrations::diff_explainer;
 pub use integrations::git_stats;
 pub use integrations::watch;
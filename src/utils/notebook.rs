@@ -0,0 +1,184 @@
+//! Jupyter notebook extraction
+//!
+//! Notebooks are JSON with base64 images and long cell outputs. They are converted to
+//! Python in the "percent" cell format (`# %%`), so the regular Python analysis applies:
+//! markdown cells become comments, code cells stay code, and outputs are truncated.
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::path::Path;
+
+/// Output lines kept per cell
+const MAX_OUTPUT_LINES: usize = 10;
+
+/// Reads a `.ipynb` file and converts it to annotated Python source.
+pub fn extract_text(path: &Path) -> Result<String> {
+    let json = std::fs::read_to_string(path).context("Failed to read notebook")?;
+    to_source(&json).context("Failed to parse notebook JSON")
+}
+
+/// Converts notebook JSON to Python source, or `None` if it is not a notebook.
+pub fn to_source(json: &str) -> Option<String> {
+    let notebook: Value = serde_json::from_str(json).ok()?;
+    let cells = notebook.get("cells")?.as_array()?;
+
+    let mut out = String::new();
+    for cell in cells {
+        let source = joined(cell.get("source"));
+        match cell.get("cell_type").and_then(Value::as_str) {
+            Some("code") => {
+                out.push_str("# %%\n");
+                for line in source.lines() {
+                    // IPython magics and shell escapes are not Python
+                    if line.starts_with('%') || line.starts_with('!') {
+                        out.push_str("# ");
+                    }
+                    out.push_str(line);
+                    out.push('\n');
+                }
+                if let Some(outputs) = cell.get("outputs").and_then(Value::as_array) {
+                    write_outputs(&mut out, outputs);
+                }
+            }
+            Some("markdown") => {
+                out.push_str("# %% [markdown]\n");
+                write_commented(&mut out, &source);
+            }
+            _ => {
+                out.push_str("# %% [raw]\n");
+                write_commented(&mut out, &source);
+            }
+        }
+        out.push('\n');
+    }
+    Some(out)
+}
+
+/// Converts raw notebook JSON for analysis; anything else is returned unchanged.
+pub fn source_text<'a>(content: &'a str, extension: &str) -> Cow<'a, str> {
+    if extension.eq_ignore_ascii_case("ipynb")
+        && content.trim_start().starts_with('{')
+        && let Some(source) = to_source(content)
+    {
+        return Cow::Owned(source);
+    }
+    Cow::Borrowed(content)
+}
+
+/// Appends text outputs as truncated comments; rich outputs become a one-line marker.
+fn write_outputs(out: &mut String, outputs: &[Value]) {
+    let mut text = String::new();
+    for output in outputs {
+        match output.get("output_type").and_then(Value::as_str) {
+            Some("stream") => text.push_str(&joined(output.get("text"))),
+            Some("execute_result" | "display_data") => {
+                let Some(data) = output.get("data").and_then(Value::as_object) else {
+                    continue;
+                };
+                if let Some(plain) = data.get("text/plain") {
+                    text.push_str(&joined(Some(plain)));
+                } else if let Some(kind) = data.keys().next() {
+                    text.push_str(&format!("[{} output omitted]", kind));
+                }
+            }
+            Some("error") => {
+                let field = |key| output.get(key).and_then(Value::as_str).unwrap_or("");
+                text.push_str(&format!("{}: {}", field("ename"), field("evalue")));
+            }
+            _ => continue,
+        }
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+    }
+    if text.trim().is_empty() {
+        return;
+    }
+
+    out.push_str("# Output:\n");
+    let lines: Vec<&str> = text.lines().collect();
+    write_commented(out, &lines[..lines.len().min(MAX_OUTPUT_LINES)].join("\n"));
+    if lines.len() > MAX_OUTPUT_LINES {
+        out.push_str(&format!(
+            "# ... ({} more lines)\n",
+            lines.len() - MAX_OUTPUT_LINES
+        ));
+    }
+}
+
+fn write_commented(out: &mut String, text: &str) {
+    for line in text.lines() {
+        if line.is_empty() {
+            out.push_str("#\n");
+        } else {
+            out.push_str("# ");
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+}
+
+/// Notebook text fields are either a string or a list of line strings.
+fn joined(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(lines)) => lines.iter().filter_map(Value::as_str).collect(),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notebook_to_source() {
+        let numbers: Vec<String> = (0..25).map(|i| format!("{}\n", i)).collect();
+        let notebook = serde_json::json!({
+            "cells": [
+                {"cell_type": "markdown", "source": ["# Analysis\n", "\n", "Load the data."]},
+                {
+                    "cell_type": "code",
+                    "source": ["%matplotlib inline\n", "import pandas as pd\n", "from .helpers import clean"],
+                    "outputs": [
+                        {"output_type": "stream", "name": "stdout", "text": numbers},
+                        {"output_type": "display_data", "data": {"image/png": "iVBORw0KGgo="}}
+                    ]
+                },
+                {
+                    "cell_type": "code",
+                    "source": "def load(path):\n    return pd.read_csv(path)\n\ndf = load('x.csv')",
+                    "outputs": [{"output_type": "error", "ename": "FileNotFoundError", "evalue": "x.csv", "traceback": ["..."]}]
+                }
+            ],
+            "metadata": {},
+            "nbformat": 4,
+            "nbformat_minor": 5
+        });
+
+        let source = to_source(&notebook.to_string()).unwrap();
+        assert!(source.starts_with("# %% [markdown]\n# # Analysis\n#\n# Load the data.\n"));
+        assert!(source.contains("# %%\n# %matplotlib inline\nimport pandas as pd\n"));
+        assert!(source.contains("# Output:\n# 0\n"));
+        assert!(source.contains("# 9\n# ... (16 more lines)\n"));
+        assert!(!source.contains("# 10\n"));
+        assert!(!source.contains("iVBORw0KGgo"));
+        assert!(source.contains("# FileNotFoundError: x.csv\n"));
+
+        let imports = crate::utils::dependencies::extract_imports(&source, "ipynb");
+        assert!(imports.contains(&"pandas".to_string()));
+        assert!(imports.contains(&".helpers".to_string()));
+        assert_eq!(
+            crate::utils::dependencies::extract_imports(&notebook.to_string(), "ipynb"),
+            imports
+        );
+
+        let compressed =
+            crate::utils::source::SourceAnalysis::analyze(&source, "ipynb").compress(&source);
+        assert!(compressed.contains("def load(path):"));
+        assert!(!compressed.contains("return pd.read_csv(path)"));
+
+        assert!(to_source("{\"not\": \"a notebook\"}").is_none());
+    }
+}
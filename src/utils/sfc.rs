@@ -0,0 +1,203 @@
+//! Single-file component splitting (Vue, Svelte, Astro)
+//!
+//! Components embed JavaScript/TypeScript in `<script>` blocks (and Astro frontmatter)
+//! next to template and style blocks. The script blocks are analysed with the regular
+//! JS/TS queries; template and style blocks are kept verbatim or collapsed.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::ops::Range;
+
+lazy_static! {
+    static ref OPEN_TAG: Regex = Regex::new(r"(?i)<(script|style|template)\b([^>]*)>").unwrap();
+    static ref LANG_ATTR: Regex = Regex::new(r#"(?i)\blang\s*=\s*["']?(\w+)"#).unwrap();
+    static ref TYPE_ATTR: Regex = Regex::new(r#"(?i)\btype\s*=\s*["']?([\w/+.-]+)"#).unwrap();
+}
+
+/// What a component block contains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockKind {
+    /// Script code, with the extension of the language it is written in
+    Script(&'static str),
+    /// Template markup
+    Template,
+    /// Stylesheet
+    Style,
+}
+
+/// The inner content of one block (tags excluded).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    pub range: Range<usize>,
+    pub kind: BlockKind,
+}
+
+/// Splits a Vue or Svelte component into its `<script>`, `<template>` and `<style>` blocks.
+/// Svelte markup outside those tags is left untouched.
+pub fn component_blocks(content: &str) -> Vec<Block> {
+    tag_blocks(content, 0)
+}
+
+/// Splits an Astro component: TypeScript frontmatter between `---` fences, then
+/// `<script>` (TypeScript by default) and `<style>` blocks in the markup.
+pub fn astro_blocks(content: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut markup_start = 0;
+
+    let leading = content.len() - content.trim_start().len();
+    if content[leading..].starts_with("---") {
+        let body_start = content[leading..]
+            .find('\n')
+            .map_or(content.len(), |i| leading + i + 1);
+        if let Some(end) = find_fence(content, body_start) {
+            blocks.push(Block {
+                range: body_start..end,
+                kind: BlockKind::Script("ts"),
+            });
+            markup_start = end;
+        }
+    }
+
+    for mut block in tag_blocks(&content[markup_start..], markup_start) {
+        if block.kind == BlockKind::Script("js") {
+            block.kind = BlockKind::Script("ts");
+        }
+        blocks.push(block);
+    }
+    blocks
+}
+
+/// Finds the closing `---` line of the frontmatter.
+fn find_fence(content: &str, from: usize) -> Option<usize> {
+    let mut offset = from;
+    for line in content[from..].split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return Some(offset);
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Scans for top-level `<script>`, `<style>` and `<template>` blocks.
+fn tag_blocks(content: &str, base: usize) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut pos = 0;
+
+    while let Some(open) = OPEN_TAG.captures_at(content, pos) {
+        let whole = open.get(0).unwrap();
+        let name = open[1].to_lowercase();
+        let attrs = &open[2];
+        let inner_start = whole.end();
+        let close = format!("</{}>", name);
+
+        // Templates nest (`<template v-if>`); the root one closes last
+        let inner_end = if name == "template" {
+            content[inner_start..].rfind(&close)
+        } else {
+            content[inner_start..].find(&close)
+        };
+        let Some(inner_end) = inner_end.map(|i| inner_start + i) else {
+            break;
+        };
+        pos = inner_end + close.len();
+
+        let kind = match name.as_str() {
+            "template" => BlockKind::Template,
+            "style" => BlockKind::Style,
+            _ => match script_language(attrs) {
+                Some(extension) => BlockKind::Script(extension),
+                // JSON-LD, templates and other non-code payloads stay markup
+                None => BlockKind::Template,
+            },
+        };
+        blocks.push(Block {
+            range: base + inner_start..base + inner_end,
+            kind,
+        });
+    }
+    blocks
+}
+
+/// Extension of the language a `<script>` tag's attributes declare, if it is code.
+fn script_language(attrs: &str) -> Option<&'static str> {
+    if let Some(kind) = TYPE_ATTR.captures(attrs) {
+        let kind = kind[1].to_lowercase();
+        if !matches!(
+            kind.as_str(),
+            "module" | "text/javascript" | "application/javascript" | "text/typescript" | "ts"
+        ) {
+            return None;
+        }
+    }
+    let lang = LANG_ATTR
+        .captures(attrs)
+        .map(|c| c[1].to_lowercase())
+        .unwrap_or_default();
+    Some(match lang.as_str() {
+        "ts" | "typescript" => "ts",
+        "tsx" => "tsx",
+        "jsx" => "jsx",
+        _ => "js",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts<'a>(content: &'a str, blocks: &[Block]) -> Vec<(BlockKind, &'a str)> {
+        blocks
+            .iter()
+            .map(|b| (b.kind, content[b.range.clone()].trim()))
+            .collect()
+    }
+
+    #[test]
+    fn test_vue_blocks() {
+        let content = r#"<template>
+  <div><template v-if="ok">yes</template></div>
+</template>
+
+<script setup lang="ts">
+import Button from './Button.vue'
+</script>
+
+<script type="application/ld+json">{"a": 1}</script>
+
+<style scoped>
+.a { color: red; }
+</style>
+"#;
+        assert_eq!(
+            texts(content, &component_blocks(content)),
+            vec![
+                (
+                    BlockKind::Template,
+                    r#"<div><template v-if="ok">yes</template></div>"#
+                ),
+                (BlockKind::Script("ts"), "import Button from './Button.vue'"),
+                (BlockKind::Template, r#"{"a": 1}"#),
+                (BlockKind::Style, ".a { color: red; }"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_astro_frontmatter() {
+        let content = "---\nimport Layout from '../layouts/Layout.astro';\nconst title = 'Home';\n---\n<Layout title={title}>\n  <h1>Hi</h1>\n</Layout>\n<script>\nimport { track } from '../lib/analytics';\n</script>\n";
+        assert_eq!(
+            texts(content, &astro_blocks(content)),
+            vec![
+                (
+                    BlockKind::Script("ts"),
+                    "import Layout from '../layouts/Layout.astro';\nconst title = 'Home';"
+                ),
+                (
+                    BlockKind::Script("ts"),
+                    "import { track } from '../lib/analytics';"
+                ),
+            ]
+        );
+    }
+}
@@ -1,67 +1,29 @@
-use tree_sitter::{Parser, Query, QueryCursor};
+use crate::utils::language::LanguageSupport;
+use tree_sitter::QueryCursor;
 
 /// Generates a brief summary of the file content based on its extension.
 /// extracted symbols (structs, functions, classes).
 pub fn summarize_content(content: &str, extension: &str) -> Option<String> {
-    let mut parser = Parser::new();
-
-    let (language, query_str) = match extension {
-        "rs" => (
-            tree_sitter_rust::LANGUAGE.into(),
-            r#"
-            (struct_item name: (_) @struct)
-            (enum_item name: (_) @enum)
-            (trait_item name: (_) @trait)
-            (function_item name: (_) @fn)
-            (impl_item type: (_) @impl)
-            "#,
-        ),
-        "py" => (
-            tree_sitter_python::LANGUAGE.into(),
-            r#"
-            (class_definition name: (_) @class)
-            (function_definition name: (_) @fn)
-            "#,
-        ),
-        "js" | "jsx" | "ts" | "tsx" => (
-            tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
-            r#"
-            (class_declaration name: (_) @class)
-            (function_declaration name: (_) @fn)
-            (interface_declaration name: (_) @interface)
-            (type_alias_declaration name: (_) @type)
-            "#,
-        ),
-        "go" => (
-            tree_sitter_go::LANGUAGE.into(),
-            r#"
-            (type_spec name: (_) @type)
-            (function_declaration name: (_) @fn)
-            "#,
-        ),
-        "c" | "h" => (
-            tree_sitter_c::LANGUAGE.into(),
-            r#"
-            (struct_specifier name: (_) @struct)
-            (function_definition declarator: (function_declarator declarator: (identifier) @fn))
-            "#,
-        ),
-        "cpp" | "hpp" | "cc" | "cxx" => (
-            tree_sitter_cpp::LANGUAGE.into(),
-            r#"
-            (class_specifier name: (_) @class)
-            (function_definition declarator: (function_declarator declarator: (identifier) @fn))
-            "#,
-        ),
-        _ => return summarize_content_regex(content),
-    };
-
-    if parser.set_language(&language).is_err() {
-        return None;
+    let content = &crate::utils::notebook::source_text(content, extension);
+    match crate::utils::language::for_extension(extension) {
+        Some(support) if support.blocks.is_some() => {
+            crate::utils::source::SourceAnalysis::analyze(content, extension).summary
+        }
+        Some(support) if support.symbols.is_some() => {
+            let tree = support.parse(content)?;
+            summarize_tree(&support, &tree, content)
+        }
+        _ => summarize_content_regex(content),
     }
+}
 
-    let tree = parser.parse(content, None)?;
-    let query = Query::new(&language, query_str).ok()?;
+/// Summarizes an already parsed tree with the language's symbol query.
+pub(crate) fn summarize_tree(
+    support: &LanguageSupport,
+    tree: &tree_sitter::Tree,
+    content: &str,
+) -> Option<String> {
+    let query = support.cached_query(support.symbols.as_deref()?)?;
     let mut cursor = QueryCursor::new();
     let mut matches = cursor.matches(&query, tree.root_node(), content.as_bytes());
 
@@ -145,13 +107,13 @@ pub fn summarize_content(content: &str, extension: &str) -> Option<String> {
 }
 
 /// Fallback summarizer using Regex for unknown languages
-fn summarize_content_regex(content: &str) -> Option<String> {
+pub(crate) fn summarize_content_regex(content: &str) -> Option<String> {
     use regex::Regex;
     // Regex fallback for languages without tree-sitter support.
     let re_class =
         Regex::new(r"^\s*(class|struct|module|interface|trait)\s+([a-zA-Z0-9_]+)").ok()?;
     let re_fn =
-        Regex::new(r"^\s*(function|def|fn|func|public\s+sub|sub)\s+([a-zA-Z0-9_]+)").ok()?;
+        Regex::new(r"^\s*(function|def|fn|fun|func|public\s+sub|sub)\s+([a-zA-Z0-9_]+)").ok()?;
 
     let mut classes = Vec::new();
     let mut functions = Vec::new();
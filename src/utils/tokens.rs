@@ -1,5 +1,127 @@
 use anyhow::Result;
-use tiktoken_rs::cl100k_base;
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use tiktoken_rs::CoreBPE;
+
+lazy_static! {
+    // Building a BPE parses its whole merge table; each is built once and shared by all threads
+    static ref O200K_BASE: Option<CoreBPE> = tiktoken_rs::o200k_base().ok();
+    static ref CL100K_BASE: Option<CoreBPE> = tiktoken_rs::cl100k_base().ok();
+    static ref P50K_BASE: Option<CoreBPE> = tiktoken_rs::p50k_base().ok();
+    /// Exact counts by hash of tokenizer and text
+    static ref COUNTS: DashMap<[u8; 16], usize> = DashMap::new();
+}
+
+/// Counts remembered before the cache is emptied; enough for a large repository's output
+/// while keeping a long `--watch` session from growing without end
+const MAX_CACHED_COUNTS: usize = 16_384;
+
+/// Encoding used to count tokens
+///
+/// Written as `o200k_base`, `cl100k_base`, `p50k_base` or `chars:<N>` (N characters per
+/// token, for models without a public BPE).
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Tokenizer {
+    /// GPT-4o and later
+    O200kBase,
+    /// GPT-4 and GPT-3.5
+    #[default]
+    Cl100kBase,
+    /// Codex and GPT-3 (`text-davinci-*`)
+    P50kBase,
+    /// Approximation: this many characters per token
+    CharsPerToken(f64),
+}
+
+impl Tokenizer {
+    /// Parse from string (for CLI and config)
+    pub fn parse(name: &str) -> Result<Self> {
+        let name = name.trim().to_lowercase();
+        match name.as_str() {
+            "o200k_base" | "o200k" => Ok(Tokenizer::O200kBase),
+            "cl100k_base" | "cl100k" => Ok(Tokenizer::Cl100kBase),
+            "p50k_base" | "p50k" => Ok(Tokenizer::P50kBase),
+            _ => {
+                let ratio = name
+                    .strip_prefix("chars:")
+                    .and_then(|n| n.parse::<f64>().ok())
+                    .filter(|n| *n > 0.0)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Unknown tokenizer '{}'. Use: o200k_base, cl100k_base, p50k_base, chars:<N>",
+                            name
+                        )
+                    })?;
+                Ok(Tokenizer::CharsPerToken(ratio))
+            }
+        }
+    }
+
+    /// The shared encoder for this tokenizer, built on first use.
+    pub fn encoder(self) -> Result<Encoder> {
+        let bpe = match self {
+            Tokenizer::O200kBase => &*O200K_BASE,
+            Tokenizer::Cl100kBase => &*CL100K_BASE,
+            Tokenizer::P50kBase => &*P50K_BASE,
+            Tokenizer::CharsPerToken(ratio) => return Ok(Encoder::Chars(ratio)),
+        };
+        bpe.as_ref()
+            .map(Encoder::Bpe)
+            .ok_or_else(|| anyhow::anyhow!("Failed to load the {} tokenizer", self))
+    }
+
+    /// Cheap estimate for ranking and selection; exact for `chars:<N>`.
+    pub fn estimate(self, text: &str) -> usize {
+        match self {
+            Tokenizer::CharsPerToken(ratio) => Encoder::Chars(ratio).count(text),
+            _ => estimate_tokens(text),
+        }
+    }
+}
+
+impl std::fmt::Display for Tokenizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Tokenizer::O200kBase => write!(f, "o200k_base"),
+            Tokenizer::Cl100kBase => write!(f, "cl100k_base"),
+            Tokenizer::P50kBase => write!(f, "p50k_base"),
+            Tokenizer::CharsPerToken(ratio) => write!(f, "chars:{}", ratio),
+        }
+    }
+}
+
+impl TryFrom<String> for Tokenizer {
+    type Error = anyhow::Error;
+
+    fn try_from(name: String) -> Result<Self> {
+        Tokenizer::parse(&name)
+    }
+}
+
+impl From<Tokenizer> for String {
+    fn from(tokenizer: Tokenizer) -> Self {
+        tokenizer.to_string()
+    }
+}
+
+/// A ready-to-use tokenizer
+#[derive(Clone, Copy)]
+pub enum Encoder {
+    Bpe(&'static CoreBPE),
+    Chars(f64),
+}
+
+impl Encoder {
+    /// Number of tokens in `text`.
+    pub fn count(&self, text: &str) -> usize {
+        match self {
+            Encoder::Bpe(bpe) => bpe.encode_with_special_tokens(text).len(),
+            Encoder::Chars(ratio) => (text.chars().count() as f64 / ratio).ceil() as usize,
+        }
+    }
+}
 
 /// Fast token estimation using heuristic (~4 chars per token for code)
 /// Use this for filtering/selection where speed matters more than precision
@@ -17,9 +139,36 @@ pub fn estimate_tokens(text: &str) -> usize {
 /// Accurate token count using tiktoken (slower but precise)
 /// Use this for final output where accuracy matters
 pub fn count_tokens(text: &str) -> Result<usize> {
-    let bpe = cl100k_base()?;
-    let tokens = bpe.encode_with_special_tokens(text);
-    Ok(tokens.len())
+    count_tokens_with(text, Tokenizer::default())
+}
+
+/// Token count with the given tokenizer; remembered by content hash (up to
+/// `MAX_CACHED_COUNTS` texts)
+pub fn count_tokens_with(text: &str, tokenizer: Tokenizer) -> Result<usize> {
+    let encoder = tokenizer.encoder()?;
+    if let Encoder::Chars(_) = encoder {
+        return Ok(encoder.count(text));
+    }
+
+    let mut context = md5::Context::new();
+    context.consume(tokenizer.to_string().as_bytes());
+    context.consume([0]);
+    context.consume(text.as_bytes());
+    let key = context.finalize().0;
+    if let Some(count) = COUNTS.get(&key) {
+        return Ok(*count);
+    }
+    let count = encoder.count(text);
+    if COUNTS.len() >= MAX_CACHED_COUNTS {
+        COUNTS.clear();
+    }
+    COUNTS.insert(key, count);
+    Ok(count)
+}
+
+/// Forgets all remembered token counts.
+pub fn clear_count_cache() {
+    COUNTS.clear();
 }
 
 /// Smart token counting: estimate for speed, accurate when needed
@@ -64,6 +213,56 @@ mod tests {
         assert!(estimate < accurate * 2);
     }
 
+    #[test]
+    fn test_tokenizers() {
+        let text = "fn main() { println!(\"hello world\"); }";
+        for name in ["o200k_base", "cl100k", "p50k", "chars:4"] {
+            let tokenizer = Tokenizer::parse(name).unwrap();
+            assert!(count_tokens_with(text, tokenizer).unwrap() > 5, "{}", name);
+        }
+        assert_eq!(
+            count_tokens_with("abcdefghij", Tokenizer::CharsPerToken(4.0)).unwrap(),
+            3
+        );
+        assert_eq!(
+            Tokenizer::parse("chars:3.5").unwrap().to_string(),
+            "chars:3.5"
+        );
+        assert!(Tokenizer::parse("chars:0").is_err());
+        assert!(Tokenizer::parse("gpt2").is_err());
+    }
+
+    #[test]
+    fn test_shared_encoder() {
+        let (Encoder::Bpe(a), Encoder::Bpe(b)) = (
+            Tokenizer::O200kBase.encoder().unwrap(),
+            Tokenizer::O200kBase.encoder().unwrap(),
+        ) else {
+            panic!("expected a BPE");
+        };
+        assert!(std::ptr::eq(a, b));
+
+        // Cached counts are per tokenizer
+        let text = "impl Display for Tokenizer { fn fmt(&self) {} }";
+        let o200k = count_tokens_with(text, Tokenizer::O200kBase).unwrap();
+        assert_eq!(
+            count_tokens_with(text, Tokenizer::O200kBase).unwrap(),
+            o200k
+        );
+        assert_eq!(
+            count_tokens_with(text, Tokenizer::P50kBase).unwrap(),
+            Tokenizer::P50kBase.encoder().unwrap().count(text)
+        );
+    }
+
+    #[test]
+    fn test_count_cache_is_bounded() {
+        for i in 0..=MAX_CACHED_COUNTS {
+            count_tokens_with(&format!("bounded {}", i), Tokenizer::P50kBase).unwrap();
+        }
+        assert!(COUNTS.len() <= MAX_CACHED_COUNTS);
+    }
+
     #[test]
     fn test_smart_counting() {
         let text = "hello world";
@@ -35,11 +35,28 @@ fn test_end_to_end_scan() -> anyhow::Result<()> {
         prompt: None,
         redact: false,
         diff: None,
+        query: None,
+        since: None,
+        author: None,
+        owner: None,
+        group_by_owner: false,
         max_tokens: None,
+        tokenizer: None,
+        model: None,
+        models: Default::default(),
+        dependency_closure: false,
+        pair_tests: false,
+        dedup: false,
+        dedup_threshold: 0.85,
+        dedup_diff: false,
         graph: false,
         compression_level: abyss::config::CompressionLevel::None,
         bundle: None,
         explain_diff: false,
+        include_dirs: vec![],
+        languages: Default::default(),
+        ranking: Default::default(),
+        generated: Default::default(),
     };
 
     run(config)?;
@@ -83,11 +100,28 @@ fn test_compression_integration() -> anyhow::Result<()> {
         prompt: None,
         redact: false,
         diff: None,
+        query: None,
+        since: None,
+        author: None,
+        owner: None,
+        group_by_owner: false,
         max_tokens: None,
+        tokenizer: None,
+        model: None,
+        models: Default::default(),
+        dependency_closure: false,
+        pair_tests: false,
+        dedup: false,
+        dedup_threshold: 0.85,
+        dedup_diff: false,
         graph: false,
         compression_level: abyss::config::CompressionLevel::None,
         bundle: None,
         explain_diff: false,
+        include_dirs: vec![],
+        languages: Default::default(),
+        ranking: Default::default(),
+        generated: Default::default(),
     };
 
     run(config)?;
@@ -124,11 +158,28 @@ fn test_prompt_integration() -> anyhow::Result<()> {
         prompt: Some("Analyze this code for bugs.".to_string()),
         redact: false,
         diff: None,
+        query: None,
+        since: None,
+        author: None,
+        owner: None,
+        group_by_owner: false,
         max_tokens: None,
+        tokenizer: None,
+        model: None,
+        models: Default::default(),
+        dependency_closure: false,
+        pair_tests: false,
+        dedup: false,
+        dedup_threshold: 0.85,
+        dedup_diff: false,
         graph: false,
         compression_level: abyss::config::CompressionLevel::None,
         bundle: None,
         explain_diff: false,
+        include_dirs: vec![],
+        languages: Default::default(),
+        ranking: Default::default(),
+        generated: Default::default(),
     };
 
     run(config)?;
@@ -37,12 +37,29 @@ fn test_image_ingestion() {
         max_depth: None,
         prompt: None,
         max_tokens: None,
+        tokenizer: None,
+        model: None,
+        models: Default::default(),
+        dependency_closure: false,
+        pair_tests: false,
+        dedup: false,
+        dedup_threshold: 0.85,
+        dedup_diff: false,
         redact: false,
         diff: None,
+        query: None,
+        since: None,
+        author: None,
+        owner: None,
+        group_by_owner: false,
         graph: false,
         compression_level: abyss::config::CompressionLevel::None,
         bundle: None,
         explain_diff: false,
+        include_dirs: vec![],
+        languages: Default::default(),
+        ranking: Default::default(),
+        generated: Default::default(),
     };
 
     // 3. Run